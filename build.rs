@@ -17,13 +17,25 @@
 // https://doc.rust-lang.org/cargo/reference/build-scripts.html#case-study-code-generation
 
 include!("src/decode/include/candidate_words.rs");
+#[cfg(any(feature = "wl_norwegian_decode", feature = "wl_german_decode"))]
+include!("src/decode/include/letter_spellings.rs");
 
 fn main() {
     #[cfg(any(
         feature = "wl_eff_encode",
         feature = "wl_eff_decode",
         feature = "wl_pgp_encode",
-        feature = "wl_pgp_decode"
+        feature = "wl_pgp_decode",
+        feature = "wl_bytewords_encode",
+        feature = "wl_bytewords_decode",
+        feature = "wl_rfc1751_encode",
+        feature = "wl_rfc1751_decode",
+        feature = "wl_eff_full_encode",
+        feature = "wl_eff_full_decode",
+        feature = "wl_norwegian_encode",
+        feature = "wl_norwegian_decode",
+        feature = "wl_german_encode",
+        feature = "wl_german_decode"
     ))]
     {
         use std::env;
@@ -81,6 +93,52 @@ fn main() {
             }
         }
 
+        #[cfg(any(feature = "wl_eff_full_encode", feature = "wl_eff_full_decode"))]
+        {
+            use std::io::{BufRead, BufReader};
+
+            let f_src = BufReader::new(File::open("eff_short_wordlist_2_0.txt").unwrap());
+            let words: Vec<_> = f_src
+                .lines()
+                .map(|line| line.unwrap().split('\t').nth(1).unwrap().to_string())
+                .collect();
+
+            #[cfg(feature = "wl_eff_full_encode")]
+            {
+                let fpath_wl_eff_full_encode = Path::new(&out_dir).join("wl_eff_full_encode.rs");
+                let mut f_wl_eff_full_encode = File::create(fpath_wl_eff_full_encode).unwrap();
+
+                writeln!(
+                    f_wl_eff_full_encode,
+                    "/// EFF Short Wordlist 2.0, all words (encode)"
+                )
+                .unwrap();
+                writeln!(
+                    f_wl_eff_full_encode,
+                    "pub const WL_EFF_FULL_ENCODE: &[&str] = &{words:?};"
+                )
+                .unwrap();
+            }
+
+            // The words are looked up by the radix decoder, so the decode list is the words in order.
+            #[cfg(feature = "wl_eff_full_decode")]
+            {
+                let fpath_wl_eff_full_decode = Path::new(&out_dir).join("wl_eff_full_decode.rs");
+                let mut f_wl_eff_full_decode = File::create(fpath_wl_eff_full_decode).unwrap();
+
+                writeln!(
+                    f_wl_eff_full_decode,
+                    "/// EFF Short Wordlist 2.0, all words (decode)"
+                )
+                .unwrap();
+                writeln!(
+                    f_wl_eff_full_decode,
+                    "pub(crate) const WL_EFF_FULL_DECODE: &[&str] = &{words:?};"
+                )
+                .unwrap();
+            }
+        }
+
         #[cfg(any(feature = "wl_pgp_encode", feature = "wl_pgp_decode"))]
         {
             use std::fs::read_to_string;
@@ -152,14 +210,240 @@ fn main() {
                     "const WL_PGP_DECODE_TWO_SYLLABLE: &[WordlistSubset] = &{wl_subsets_2:?};"
                 )
                 .unwrap();
+
+                // The decode lists hold the words in lowercase,
+                // so the words as written are kept in order alongside them.
+                writeln!(
+                    f_wl_pgp_decode,
+                    "/// PGP Word List (decode) -- PGPfone Three Syllable Word List, as written"
+                )
+                .unwrap();
+                writeln!(
+                    f_wl_pgp_decode,
+                    "const WL_PGP_DECODE_THREE_SYLLABLE_WORDS: &[&str] = &{words_3:?};"
+                )
+                .unwrap();
+
+                writeln!(
+                    f_wl_pgp_decode,
+                    "/// PGP Word List (decode) -- PGPfone Two Syllable Word List, as written"
+                )
+                .unwrap();
+                writeln!(
+                    f_wl_pgp_decode,
+                    "const WL_PGP_DECODE_TWO_SYLLABLE_WORDS: &[&str] = &{words_2:?};"
+                )
+                .unwrap();
+            }
+        }
+
+        #[cfg(any(feature = "wl_bytewords_encode", feature = "wl_bytewords_decode"))]
+        {
+            use std::fs::read_to_string;
+
+            let words_s = read_to_string("bytewords_wordlist.txt").unwrap();
+            let words: Vec<_> = words_s.split(' ').collect();
+            // The minimal form of each word is its first and last letter.
+            let words_minimal: Vec<_> = words
+                .iter()
+                .map(|w| format!("{}{}", &w[..1], &w[w.len() - 1..]))
+                .collect();
+
+            #[cfg(feature = "wl_bytewords_encode")]
+            {
+                let fpath_wl_bytewords_encode = Path::new(&out_dir).join("wl_bytewords_encode.rs");
+                let mut f_wl_bytewords_encode = File::create(fpath_wl_bytewords_encode).unwrap();
+
+                writeln!(f_wl_bytewords_encode, "/// Bytewords (encode)").unwrap();
+                writeln!(
+                    f_wl_bytewords_encode,
+                    "pub const WL_BYTEWORDS_ENCODE: &[&str] = &{words:?};"
+                )
+                .unwrap();
+
+                writeln!(f_wl_bytewords_encode, "/// Bytewords (encode) -- Minimal").unwrap();
+                writeln!(
+                    f_wl_bytewords_encode,
+                    "pub const WL_BYTEWORDS_MINIMAL_ENCODE: &[&str] = &{words_minimal:?};"
+                )
+                .unwrap();
+            }
+
+            #[cfg(feature = "wl_bytewords_decode")]
+            {
+                let words: Vec<_> = words.iter().map(|w| w.to_string()).collect();
+                let wl_decode_entries = create_wl_decode_entries(&words);
+                let wl_subsets = create_wl_decode(&wl_decode_entries);
+
+                let wl_decode_entries_minimal = create_wl_decode_entries(&words_minimal);
+                let wl_subsets_minimal = create_wl_decode(&wl_decode_entries_minimal);
+
+                let fpath_wl_bytewords_decode = Path::new(&out_dir).join("wl_bytewords_decode.rs");
+                let mut f_wl_bytewords_decode = File::create(fpath_wl_bytewords_decode).unwrap();
+
+                writeln!(f_wl_bytewords_decode, "/// Bytewords (decode)").unwrap();
+                writeln!(
+                    f_wl_bytewords_decode,
+                    "const WL_BYTEWORDS_DECODE: &[WordlistSubset] = &{wl_subsets:?};"
+                )
+                .unwrap();
+
+                writeln!(f_wl_bytewords_decode, "/// Bytewords (decode) -- Minimal").unwrap();
+                writeln!(
+                    f_wl_bytewords_decode,
+                    "const WL_BYTEWORDS_MINIMAL_DECODE: &[WordlistSubset] = &{wl_subsets_minimal:?};"
+                )
+                .unwrap();
+            }
+        }
+
+        #[cfg(any(feature = "wl_rfc1751_encode", feature = "wl_rfc1751_decode"))]
+        {
+            use std::fs::read_to_string;
+
+            let words_s = read_to_string("rfc1751_wordlist.txt").unwrap();
+            let words: Vec<_> = words_s.split(' ').collect();
+
+            #[cfg(feature = "wl_rfc1751_encode")]
+            {
+                let fpath_wl_rfc1751_encode = Path::new(&out_dir).join("wl_rfc1751_encode.rs");
+                let mut f_wl_rfc1751_encode = File::create(fpath_wl_rfc1751_encode).unwrap();
+
+                writeln!(f_wl_rfc1751_encode, "/// RFC 1751 (encode)").unwrap();
+                writeln!(
+                    f_wl_rfc1751_encode,
+                    "pub const WL_RFC1751_ENCODE: &[&str] = &{words:?};"
+                )
+                .unwrap();
+            }
+
+            // The words are not prefix-free, so instead of candidate word subsets,
+            // the decode list is the words in order, which are sorted by length and then by word.
+            #[cfg(feature = "wl_rfc1751_decode")]
+            {
+                let fpath_wl_rfc1751_decode = Path::new(&out_dir).join("wl_rfc1751_decode.rs");
+                let mut f_wl_rfc1751_decode = File::create(fpath_wl_rfc1751_decode).unwrap();
+
+                writeln!(f_wl_rfc1751_decode, "/// RFC 1751 (decode)").unwrap();
+                writeln!(
+                    f_wl_rfc1751_decode,
+                    "const WL_RFC1751_DECODE: &[&str] = &{words:?};"
+                )
+                .unwrap();
+            }
+        }
+
+        #[cfg(any(feature = "wl_norwegian_encode", feature = "wl_norwegian_decode"))]
+        {
+            use std::fs::read_to_string;
+
+            let words_s = read_to_string("norwegian_wordlist.txt").unwrap();
+            let words: Vec<_> = words_s.split(' ').collect();
+
+            #[cfg(feature = "wl_norwegian_encode")]
+            {
+                let fpath_wl_norwegian_encode = Path::new(&out_dir).join("wl_norwegian_encode.rs");
+                let mut f_wl_norwegian_encode = File::create(fpath_wl_norwegian_encode).unwrap();
+
+                writeln!(f_wl_norwegian_encode, "/// Norwegian wordlist (encode)").unwrap();
+                writeln!(
+                    f_wl_norwegian_encode,
+                    "pub const WL_NORWEGIAN_ENCODE: &[&str] = &{words:?};"
+                )
+                .unwrap();
+            }
+
+            #[cfg(feature = "wl_norwegian_decode")]
+            {
+                let spellings = ascii_spellings(&words);
+                let wl_decode_entries = create_wl_decode_entries_spelled(&spellings);
+                let wl_subsets = create_wl_decode(&wl_decode_entries);
+
+                let fpath_wl_norwegian_decode = Path::new(&out_dir).join("wl_norwegian_decode.rs");
+                let mut f_wl_norwegian_decode = File::create(fpath_wl_norwegian_decode).unwrap();
+
+                writeln!(f_wl_norwegian_decode, "/// Norwegian wordlist (decode)").unwrap();
+                writeln!(
+                    f_wl_norwegian_decode,
+                    "const WL_NORWEGIAN_DECODE: &[WordlistSubset] = &{wl_subsets:?};"
+                )
+                .unwrap();
+
+                // The decode list only holds the ASCII spellings of the words,
+                // so the words as written are kept in order alongside it.
+                writeln!(
+                    f_wl_norwegian_decode,
+                    "/// Norwegian wordlist (decode), as written"
+                )
+                .unwrap();
+                writeln!(
+                    f_wl_norwegian_decode,
+                    "const WL_NORWEGIAN_DECODE_WORDS: &[&str] = &{words:?};"
+                )
+                .unwrap();
+            }
+        }
+
+        #[cfg(any(feature = "wl_german_encode", feature = "wl_german_decode"))]
+        {
+            use std::fs::read_to_string;
+
+            let words_s = read_to_string("german_wordlist.txt").unwrap();
+            let words: Vec<_> = words_s.split(' ').collect();
+
+            #[cfg(feature = "wl_german_encode")]
+            {
+                let fpath_wl_german_encode = Path::new(&out_dir).join("wl_german_encode.rs");
+                let mut f_wl_german_encode = File::create(fpath_wl_german_encode).unwrap();
+
+                writeln!(f_wl_german_encode, "/// German wordlist (encode)").unwrap();
+                writeln!(
+                    f_wl_german_encode,
+                    "pub const WL_GERMAN_ENCODE: &[&str] = &{words:?};"
+                )
+                .unwrap();
+            }
+
+            #[cfg(feature = "wl_german_decode")]
+            {
+                let spellings = ascii_spellings(&words);
+                let wl_decode_entries = create_wl_decode_entries_spelled(&spellings);
+                let wl_subsets = create_wl_decode(&wl_decode_entries);
+
+                let fpath_wl_german_decode = Path::new(&out_dir).join("wl_german_decode.rs");
+                let mut f_wl_german_decode = File::create(fpath_wl_german_decode).unwrap();
+
+                writeln!(f_wl_german_decode, "/// German wordlist (decode)").unwrap();
+                writeln!(
+                    f_wl_german_decode,
+                    "const WL_GERMAN_DECODE: &[WordlistSubset] = &{wl_subsets:?};"
+                )
+                .unwrap();
+
+                // The decode list only holds the ASCII spellings of the words,
+                // so the words as written are kept in order alongside it.
+                writeln!(
+                    f_wl_german_decode,
+                    "/// German wordlist (decode), as written"
+                )
+                .unwrap();
+                writeln!(
+                    f_wl_german_decode,
+                    "const WL_GERMAN_DECODE_WORDS: &[&str] = &{words:?};"
+                )
+                .unwrap();
             }
         }
     }
 }
 
-#[cfg(any(feature = "wl_eff_decode", feature = "wl_pgp_decode"))]
-fn create_wl_decode_entries(words_lower: &[String]) -> Vec<(usize, Vec<WordlistDecodeEntry>)> {
-    let mut words_decode: Vec<_> = words_lower
+#[cfg(any(
+    feature = "wl_eff_decode",
+    feature = "wl_pgp_decode",
+    feature = "wl_bytewords_decode"
+))]
+fn create_wl_decode_entries(words_lower: &[String]) -> Vec<(usize, Vec<WordlistDecodeEntry<'_>>)> {
+    let words_decode: Vec<_> = words_lower
         .iter()
         .enumerate()
         .map(|(pos, word)| WordlistDecodeEntry {
@@ -167,6 +451,63 @@ fn create_wl_decode_entries(words_lower: &[String]) -> Vec<(usize, Vec<WordlistD
             byte: pos as u8,
         })
         .collect();
+    group_wl_decode_entries(words_decode)
+}
+
+/// All the ASCII spellings of each word, lowercased, along with the byte of the word.
+#[cfg(any(feature = "wl_norwegian_decode", feature = "wl_german_decode"))]
+fn ascii_spellings(words: &[&str]) -> Vec<(String, u8)> {
+    let mut spellings = vec![];
+    for (pos, word) in words.iter().enumerate() {
+        let word_spellings = word
+            .to_lowercase()
+            .chars()
+            .fold(vec![String::new()], |acc, c| {
+                let letter_spellings = LETTER_SPELLINGS
+                    .iter()
+                    .find(|(letter, _)| *letter == c)
+                    .map(|(_, letter_spellings)| letter_spellings.to_vec())
+                    .unwrap_or_else(|| {
+                        assert!(
+                            c.is_ascii_lowercase(),
+                            "no ASCII spelling of {c:?} in {word:?}"
+                        );
+                        vec![]
+                    });
+                if letter_spellings.is_empty() {
+                    return acc.into_iter().map(|s| s + &c.to_string()).collect();
+                }
+                acc.iter()
+                    .flat_map(|s| letter_spellings.iter().map(move |l| s.clone() + l))
+                    .collect()
+            });
+        spellings.extend(word_spellings.into_iter().map(|s| (s, pos as u8)));
+    }
+    spellings
+}
+
+#[cfg(any(feature = "wl_norwegian_decode", feature = "wl_german_decode"))]
+fn create_wl_decode_entries_spelled(
+    spellings: &[(String, u8)],
+) -> Vec<(usize, Vec<WordlistDecodeEntry<'_>>)> {
+    let words_decode: Vec<_> = spellings
+        .iter()
+        .map(|(word, byte)| WordlistDecodeEntry { word, byte: *byte })
+        .collect();
+    group_wl_decode_entries(words_decode)
+}
+
+/// Sort decode entries, and group them into subsets of words of the same length.
+#[cfg(any(
+    feature = "wl_eff_decode",
+    feature = "wl_pgp_decode",
+    feature = "wl_bytewords_decode",
+    feature = "wl_norwegian_decode",
+    feature = "wl_german_decode"
+))]
+fn group_wl_decode_entries(
+    mut words_decode: Vec<WordlistDecodeEntry<'_>>,
+) -> Vec<(usize, Vec<WordlistDecodeEntry<'_>>)> {
     words_decode.sort_by(|a, b| a.partial_cmp(b).unwrap());
     let wl_subsets: Vec<(_, _)> = words_decode.into_iter().fold(Vec::new(), |mut acc, entry| {
         if acc.is_empty() {
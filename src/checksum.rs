@@ -0,0 +1,97 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+/// CRC-32 (ISO-HDLC, as used by zlib and PNG) lookup table.
+#[cfg(any(
+    feature = "envelope",
+    feature = "encode_bytewords",
+    feature = "decode_bytewords"
+))]
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 (ISO-HDLC) of the given bytes.
+#[cfg(any(feature = "envelope", feature = "decode_bytewords"))]
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    bytes.iter().for_each(|&byte| crc.update(byte));
+    crc.finish()
+}
+
+/// CRC-32 (ISO-HDLC) computed one byte at a time, for data that is streamed.
+#[cfg(any(
+    feature = "envelope",
+    feature = "encode_bytewords",
+    feature = "decode_bytewords"
+))]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Crc32(u32);
+
+#[cfg(any(
+    feature = "envelope",
+    feature = "encode_bytewords",
+    feature = "decode_bytewords"
+))]
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Self(!0)
+    }
+
+    pub(crate) fn update(&mut self, byte: u8) {
+        self.0 = (self.0 >> 8) ^ CRC32_TABLE[((self.0 ^ byte as u32) & 0xFF) as usize];
+    }
+
+    pub(crate) fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+/// RFC 1751 parity of a block: the sum of its 32 pairs of bits, in two bits.
+#[cfg(any(feature = "encode_rfc1751", feature = "decode_rfc1751"))]
+pub(crate) fn rfc1751_parity(block: u64) -> u8 {
+    ((0..32).map(|i| (block >> (2 * i)) & 0b11).sum::<u64>() & 0b11) as u8
+}
+
+#[cfg(feature = "envelope")]
+#[cfg(test)]
+mod test_cases_crc32 {
+    use super::crc32;
+    use test_case::test_case;
+
+    #[test_case(b"", 0x0000_0000 ; "empty")]
+    #[test_case(b"123456789", 0xCBF4_3926 ; "check value")]
+    #[test_case(b"Wolf", 0x598C_84DC ; "bytewords test vector")]
+    fn test_crc32(bytes: &[u8], expected: u32) {
+        assert_eq!(crc32(bytes), expected);
+    }
+}
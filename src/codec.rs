@@ -0,0 +1,340 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+#[cfg(feature = "decode")]
+use crate::Ignoring;
+use crate::RadixScheme;
+
+/// Number of words in the EFF Short Wordlist 2.0, all of which are used by [`Codec::EffFull`].
+const EFF_FULL_LEN: usize = 1296;
+
+/// Selection of codec at run-time.
+///
+/// All codecs known to this crate are listed, regardless of which ones were enabled
+/// at compile-time, so that for example an envelope header naming a codec that was not
+/// compiled in can still be parsed and reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    /// PGP Word List
+    Pgp,
+    /// EFF Short Wordlist 2.0
+    Eff,
+    /// Bytewords, in the standard or URI style, with a CRC-32 checksum at the end
+    Bytewords,
+    /// Bytewords, in the minimal style of two letters per word
+    BytewordsMinimal,
+    /// RFC 1751 (S/KEY), six words for each block of eight bytes
+    Rfc1751,
+    /// EFF Short Wordlist 2.0, all 1296 words, with more than a byte per word
+    EffFull,
+    /// Proquints, five-letter words of alternating consonants and vowels for each pair of bytes
+    Proquint,
+    /// Norwegian wordlist of everyday nouns, which can be typed with or without æ, ø and å
+    Norwegian,
+    /// German wordlist of everyday nouns, which can be typed with or without ä, ö, ü and ß
+    German,
+}
+
+impl Codec {
+    /// All codecs known to this crate.
+    pub const ALL: &'static [Codec] = &[
+        Codec::Pgp,
+        Codec::Eff,
+        Codec::Bytewords,
+        Codec::BytewordsMinimal,
+        Codec::Rfc1751,
+        Codec::EffFull,
+        Codec::Proquint,
+        Codec::Norwegian,
+        Codec::German,
+    ];
+
+    /// Identifier of the codec in envelope headers.
+    pub fn id(self) -> u8 {
+        match self {
+            Codec::Pgp => 1,
+            Codec::Eff => 2,
+            Codec::Bytewords => 3,
+            Codec::BytewordsMinimal => 4,
+            Codec::Rfc1751 => 5,
+            Codec::EffFull => 6,
+            Codec::Proquint => 7,
+            Codec::Norwegian => 8,
+            Codec::German => 9,
+        }
+    }
+
+    /// Look up a codec by its identifier in envelope headers.
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.iter().copied().find(|codec| codec.id() == id)
+    }
+
+    /// Short name of the codec, as used on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Codec::Pgp => "pgp",
+            Codec::Eff => "eff",
+            Codec::Bytewords => "bytewords",
+            Codec::BytewordsMinimal => "bytewords-minimal",
+            Codec::Rfc1751 => "rfc1751",
+            Codec::EffFull => "eff-full",
+            Codec::Proquint => "proquint",
+            Codec::Norwegian => "norwegian",
+            Codec::German => "german",
+        }
+    }
+
+    /// Look up a codec by its short name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|codec| codec.name() == name)
+    }
+
+    /// Number of bytes that the codec encodes at a time.
+    /// The input to the encoder must be a whole number of such blocks.
+    pub fn block_len(self) -> usize {
+        match self {
+            Codec::Pgp | Codec::Eff | Codec::Bytewords | Codec::BytewordsMinimal => 1,
+            Codec::EffFull | Codec::Norwegian | Codec::German => 1,
+            Codec::Proquint => 2,
+            Codec::Rfc1751 => 8,
+        }
+    }
+
+    /// Number of words that the codec writes for `byte_len` bytes,
+    /// including any checksum, like the one at the end of Bytewords.
+    ///
+    /// For RFC 1751 and proquints, a partial block at the end is counted as a whole block,
+    /// even though the encoder only accepts whole blocks.
+    pub fn word_count(self, byte_len: usize) -> usize {
+        match self {
+            Codec::Pgp | Codec::Eff | Codec::Norwegian | Codec::German => byte_len,
+            Codec::Bytewords | Codec::BytewordsMinimal => byte_len + 4,
+            Codec::Rfc1751 => byte_len.div_ceil(8) * 6,
+            Codec::Proquint => byte_len.div_ceil(2),
+            Codec::EffFull => RadixScheme::for_wordlist_len(EFF_FULL_LEN)
+                .unwrap()
+                .word_count(byte_len),
+        }
+    }
+
+    /// Indices of the words that hold the bits of the byte at index `byte_position`,
+    /// for reporting positions in the decoded data as positions in the words.
+    pub fn word_span(self, byte_position: usize) -> std::ops::RangeInclusive<usize> {
+        match self {
+            Codec::Pgp
+            | Codec::Eff
+            | Codec::Bytewords
+            | Codec::BytewordsMinimal
+            | Codec::Norwegian
+            | Codec::German => byte_position..=byte_position,
+            Codec::Rfc1751 => {
+                let (block, bit) = (byte_position / 8, byte_position % 8 * 8);
+                block * 6 + bit / 11..=block * 6 + (bit + 7) / 11
+            }
+            Codec::Proquint => byte_position / 2..=byte_position / 2,
+            Codec::EffFull => RadixScheme::for_wordlist_len(EFF_FULL_LEN)
+                .unwrap()
+                .word_span(byte_position),
+        }
+    }
+
+    /// Encode bytes as words using this codec.
+    ///
+    /// Fails with [`std::io::ErrorKind::Unsupported`] if the encoder was not enabled at compile-time.
+    /// Input that the codec cannot encode, like a partial block for RFC 1751 or proquints,
+    /// is reported as an error from the returned iterator.
+    #[cfg(feature = "encode")]
+    pub fn encoder<'a, I, E>(
+        self,
+        bytes: I,
+    ) -> Result<Box<dyn Iterator<Item = Result<&'static str, E>> + 'a>, std::io::Error>
+    where
+        I: Iterator<Item = Result<u8, E>> + 'a,
+        E: From<std::io::Error> + 'a,
+    {
+        match self {
+            #[cfg(feature = "encode_pgp")]
+            Codec::Pgp => Ok(Box::new(crate::Encode::<_, crate::PgpEncode<_>>::encode(
+                bytes,
+            ))),
+            #[cfg(feature = "encode_eff")]
+            Codec::Eff => Ok(Box::new(crate::Encode::<_, crate::EffEncode<_>>::encode(
+                bytes,
+            ))),
+            #[cfg(feature = "encode_bytewords")]
+            Codec::Bytewords => Ok(Box::new(
+                crate::Encode::<_, crate::BytewordsEncode<_>>::encode(bytes),
+            )),
+            #[cfg(feature = "encode_bytewords")]
+            Codec::BytewordsMinimal => Ok(Box::new(
+                crate::Encode::<_, crate::BytewordsEncode<_>>::encode(bytes)
+                    .with_style(BytewordsStyle::Minimal),
+            )),
+            #[cfg(feature = "encode_rfc1751")]
+            Codec::Rfc1751 => Ok(Box::new(
+                crate::Encode::<_, crate::Rfc1751Encode<_>>::encode(bytes),
+            )),
+            #[cfg(feature = "encode_eff_full")]
+            Codec::EffFull => Ok(Box::new(crate::RadixEncode::new(
+                bytes,
+                crate::WL_EFF_FULL_ENCODE,
+            )?)),
+            #[cfg(feature = "encode_proquint")]
+            Codec::Proquint => Ok(Box::new(
+                crate::Encode::<_, crate::ProquintEncode<_>>::encode(bytes),
+            )),
+            #[cfg(feature = "encode_norwegian")]
+            Codec::Norwegian => Ok(Box::new(
+                crate::Encode::<_, crate::NorwegianEncode<_>>::encode(bytes),
+            )),
+            #[cfg(feature = "encode_german")]
+            Codec::German => Ok(Box::new(
+                crate::Encode::<_, crate::GermanEncode<_>>::encode(bytes),
+            )),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    /// Decode words into bytes using this codec.
+    ///
+    /// Fails with [`std::io::ErrorKind::Unsupported`] if the decoder was not enabled at compile-time.
+    #[cfg(feature = "decode")]
+    pub fn decoder<'a, I>(
+        self,
+        chars: I,
+    ) -> Result<Box<dyn crate::DecodeSymbol + 'a>, std::io::Error>
+    where
+        I: Iterator<Item = Result<char, std::io::Error>> + 'a,
+    {
+        self.decoder_ignoring(chars, crate::Ignored::default())
+    }
+
+    /// Decode words into bytes using this codec, skipping over what is `ignored` in the input.
+    ///
+    /// Fails with [`std::io::ErrorKind::Unsupported`] if the decoder was not enabled at compile-time.
+    #[cfg(feature = "decode")]
+    #[allow(unused_variables)]
+    pub fn decoder_ignoring<'a, I>(
+        self,
+        chars: I,
+        ignored: crate::Ignored,
+    ) -> Result<Box<dyn crate::DecodeSymbol + 'a>, std::io::Error>
+    where
+        I: Iterator<Item = Result<char, std::io::Error>> + 'a,
+    {
+        match self {
+            #[cfg(feature = "decode_pgp")]
+            Codec::Pgp => Ok(Box::new(
+                crate::Decode::<_, crate::PgpDecode<_>>::decode(chars).ignoring(ignored),
+            )),
+            #[cfg(feature = "decode_eff")]
+            Codec::Eff => Ok(Box::new(
+                crate::Decode::<_, crate::EffDecode<_>>::decode(chars).ignoring(ignored),
+            )),
+            #[cfg(feature = "decode_bytewords")]
+            Codec::Bytewords => Ok(Box::new(
+                crate::Decode::<_, crate::BytewordsDecode<_>>::decode(chars).ignoring(ignored),
+            )),
+            #[cfg(feature = "decode_bytewords")]
+            Codec::BytewordsMinimal => Ok(Box::new(
+                crate::Decode::<_, crate::BytewordsDecode<_>>::decode(chars)
+                    .with_style(BytewordsStyle::Minimal)
+                    .ignoring(ignored),
+            )),
+            #[cfg(feature = "decode_rfc1751")]
+            Codec::Rfc1751 => Ok(Box::new(
+                crate::Decode::<_, crate::Rfc1751Decode<_>>::decode(chars).ignoring(ignored),
+            )),
+            #[cfg(feature = "decode_eff_full")]
+            Codec::EffFull => Ok(Box::new(
+                crate::RadixDecode::new(chars, crate::decode::WL_EFF_FULL_DECODE)?
+                    .ignoring(ignored),
+            )),
+            #[cfg(feature = "decode_proquint")]
+            Codec::Proquint => Ok(Box::new(
+                crate::Decode::<_, crate::ProquintDecode<_>>::decode(chars).ignoring(ignored),
+            )),
+            #[cfg(feature = "decode_norwegian")]
+            Codec::Norwegian => Ok(Box::new(
+                crate::LocalizedDecode::norwegian(chars).ignoring(ignored),
+            )),
+            #[cfg(feature = "decode_german")]
+            Codec::German => Ok(Box::new(
+                crate::LocalizedDecode::german(chars).ignoring(ignored),
+            )),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    #[cfg(any(feature = "encode", feature = "decode"))]
+    fn unsupported(self) -> std::io::Error {
+        std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("codec {} was not enabled at compile-time", self.name()),
+        )
+    }
+}
+
+/// How Bytewords are written.
+///
+/// The standard and URI styles use the same four-letter words, and differ only in
+/// the separator between them, so the decoder reads both of them in the standard style.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BytewordsStyle {
+    /// Four-letter words separated by spaces, like `able acid also`.
+    #[default]
+    Standard,
+    /// Four-letter words separated by hyphens, like `able-acid-also`, for use in URIs.
+    Uri,
+    /// The first and last letter of each word, written together, like `aeadao`.
+    Minimal,
+}
+
+impl BytewordsStyle {
+    /// Separator between the words in this style.
+    pub fn separator(self) -> &'static str {
+        match self {
+            BytewordsStyle::Standard => " ",
+            BytewordsStyle::Uri => "-",
+            BytewordsStyle::Minimal => "",
+        }
+    }
+}
+
+impl std::fmt::Display for Codec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod test_cases_codec {
+    use super::Codec;
+
+    #[test]
+    fn test_codec_ids_and_names_are_unique() {
+        for (i, a) in Codec::ALL.iter().enumerate() {
+            assert_eq!(Codec::from_id(a.id()), Some(*a));
+            assert_eq!(Codec::from_name(a.name()), Some(*a));
+            for b in &Codec::ALL[i + 1..] {
+                assert_ne!(a.id(), b.id());
+                assert_ne!(a.name(), b.name());
+            }
+        }
+    }
+}
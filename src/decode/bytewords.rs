@@ -0,0 +1,330 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use super::{DecodeSymbol, Ignored, IgnoredFilter, Ignoring, WordMatcher};
+use crate::checksum::crc32;
+use crate::BytewordsStyle;
+use std::collections::VecDeque;
+
+/// Number of words at the end of the input that hold the checksum.
+const CHECKSUM_LEN: usize = 4;
+
+/// Base 256 decoder using Bytewords
+///
+/// The last four words are the CRC-32 checksum of the data, and are not returned.
+/// The checksum is verified at the end of the input, unless some of the words
+/// were marked as unreadable, in which case it is up to forward error correction
+/// to recover the data. With forward error correction, the checksum is verified
+/// over the corrected data, as handed back with [`DecodeSymbol::correct_symbols`].
+#[derive(Clone, Debug)]
+pub struct BytewordsDecode<I: Iterator> {
+    iter: IgnoredFilter<I>,
+    style: BytewordsStyle,
+    /// Words read, but held back until it is known whether they are the checksum.
+    held: VecDeque<Option<u8>>,
+    /// The data returned so far, for verifying the checksum.
+    data: Vec<Option<u8>>,
+    /// Number of symbols of the data that had been returned when corrected bytes
+    /// were last handed back, if they ever were.
+    corrected: Option<usize>,
+    done: bool,
+}
+
+impl<I: Iterator> Ignoring for BytewordsDecode<I> {
+    fn ignoring(mut self, ignored: Ignored) -> Self {
+        self.iter.set_ignored(ignored);
+        self
+    }
+}
+
+impl<I: Iterator> BytewordsDecode<I> {
+    /// Read the words in the given style. The standard and URI styles are read the same way.
+    pub fn with_style(mut self, style: BytewordsStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn wordlist(&self) -> &'static [super::WordlistSubset<'static>] {
+        match self.style {
+            BytewordsStyle::Standard | BytewordsStyle::Uri => super::WL_BYTEWORDS_DECODE,
+            BytewordsStyle::Minimal => super::WL_BYTEWORDS_MINIMAL_DECODE,
+        }
+    }
+
+    fn verify_checksum(&self) -> Result<(), std::io::Error> {
+        if self.held.len() < CHECKSUM_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "input is too short to hold a bytewords checksum",
+            ));
+        }
+        // The checksum cannot be verified when some of the words are unreadable.
+        let Some(checksum) = self.held.iter().copied().collect::<Option<Vec<_>>>() else {
+            return Ok(());
+        };
+        let Some(data) = self.data.iter().copied().collect::<Option<Vec<_>>>() else {
+            return Ok(());
+        };
+        let checksum = u32::from_be_bytes(checksum.try_into().unwrap());
+        if checksum != crc32(&data) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "bytewords checksum mismatch",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<I> BytewordsDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_word(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        // Minimal words are written together, so there a question mark
+        // only stands in for the rest of the word it is typed in.
+        let (word_len, minimal) = match self.style {
+            BytewordsStyle::Standard | BytewordsStyle::Uri => (4, false),
+            BytewordsStyle::Minimal => (2, true),
+        };
+        let word = match super::read_word(
+            &mut self.iter,
+            |word, c| word.extend(c.to_lowercase()),
+            |word, unreadable| {
+                if unreadable {
+                    minimal
+                } else {
+                    word.chars().count() == word_len
+                }
+            },
+        )? {
+            Ok(Some(word)) => word,
+            Ok(None) => return Some(Ok(None)),
+            Err(e) => return Some(Err(e)),
+        };
+        match WordMatcher::lookup(self.wordlist(), &word) {
+            Some(byte) => Some(Ok(Some(byte))),
+            None => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unknown word {word:?}"),
+            ))),
+        }
+    }
+}
+
+impl<I> DecodeSymbol for BytewordsDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        if self.done {
+            return None;
+        }
+        loop {
+            match self.next_word() {
+                Some(Ok(symbol)) => {
+                    self.held.push_back(symbol);
+                    if self.held.len() > CHECKSUM_LEN {
+                        let symbol = self.held.pop_front().unwrap();
+                        self.data.push(symbol);
+                        return Some(Ok(symbol));
+                    }
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.done = true;
+                    // Symbols that forward error correction has yet to correct are waited for.
+                    if self.corrected.is_some_and(|len| len < self.data.len()) {
+                        return None;
+                    }
+                    return self.verify_checksum().err().map(Err);
+                }
+            }
+        }
+    }
+
+    fn correct_symbols(&mut self, bytes: &[u8]) -> Result<(), std::io::Error> {
+        let start = self.data.len() - bytes.len();
+        for (symbol, &byte) in self.data[start..].iter_mut().zip(bytes) {
+            *symbol = Some(byte);
+        }
+        self.corrected = Some(self.data.len());
+        if self.done {
+            return self.verify_checksum();
+        }
+        Ok(())
+    }
+}
+
+impl<I> Iterator for BytewordsDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_symbol()? {
+            Ok(Some(byte)) => Some(Ok(byte)),
+            Ok(None) => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "word marked as unreadable",
+            ))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<char, E>>, E> crate::Decode<I, BytewordsDecode<I>> for I {
+    fn decode(self) -> BytewordsDecode<I> {
+        BytewordsDecode {
+            iter: Ignored::default().filter(self),
+            style: BytewordsStyle::default(),
+            held: VecDeque::new(),
+            data: vec![],
+            corrected: None,
+            done: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_decode {
+    use super::super::{Decode, DecodeSymbol};
+    use super::BytewordsDecode;
+    use crate::BytewordsStyle;
+    use std::fs::File;
+    use std::io::{BufReader, Cursor};
+    use std::path::Path;
+    use test_case::test_case;
+    use utf8_chars::BufReadCharsExt;
+
+    /// Test vector from the Bytewords specification (BCR-2020-012).
+    const TEST_VECTOR: &[u8] = &[
+        0xd9, 0x01, 0x2c, 0xa2, 0x01, 0x50, 0xc7, 0x09, 0x85, 0x80, 0x12, 0x5e, 0x2a, 0xb0, 0x98,
+        0x12, 0x53, 0x46, 0x8b, 0x2d, 0xbc, 0x52, 0x02, 0xd8, 0x64, 0x19, 0x47, 0xda,
+    ];
+
+    fn decode(style: BytewordsStyle, words: &str) -> Result<Vec<u8>, std::io::Error> {
+        let mut cursor = Cursor::new(words);
+        Decode::<_, BytewordsDecode<_>>::decode(cursor.chars())
+            .with_style(style)
+            .collect()
+    }
+
+    #[test_case(BytewordsStyle::Standard, "tuna acid draw oboe acid good slot axis limp lava brag holy door puff monk brag guru frog luau drop roof grim also trip idle chef fuel twin tied draw grim ramp" ; "standard")]
+    #[test_case(BytewordsStyle::Standard, "tuna-acid-draw-oboe-acid-good-slot-axis-limp-lava-brag-holy-door-puff-monk-brag-guru-frog-luau-drop-roof-grim-also-trip-idle-chef-fuel-twin-tied-draw-grim-ramp" ; "uri")]
+    #[test_case(BytewordsStyle::Uri, "TUNA ACID DRAW OBOE ACID GOOD SLOT AXIS\nLIMP LAVA BRAG HOLY DOOR PUFF MONK BRAG\nGURU FROG LUAU DROP ROOF GRIM ALSO TRIP\nIDLE CHEF FUEL TWIN TIED DRAW GRIM RAMP\n" ; "uppercase wrapped")]
+    #[test_case(BytewordsStyle::Minimal, "taaddwoeadgdstaslplabghydrpfmkbggufgludprfgmaotpiecffltntddwgmrp" ; "minimal")]
+    #[test_case(BytewordsStyle::Minimal, "taaddwoe adgdstas\nlplabghy drpfmkbg\ngufgludp rfgmaotp\niecffltn tddwgmrp\n" ; "minimal wrapped")]
+    fn test_positive_bytewords_decoder_test_vector(style: BytewordsStyle, words: &str) {
+        assert_eq!(decode(style, words).unwrap(), TEST_VECTOR);
+    }
+
+    #[test]
+    fn test_positive_bytewords_decoder_no_data() {
+        assert_eq!(
+            decode(BytewordsStyle::Standard, "able able able able").unwrap(),
+            &[]
+        );
+    }
+
+    #[test_case(BytewordsStyle::Standard, "tuna ? draw oboe tied draw grim ramp" ; "whole word")]
+    #[test_case(BytewordsStyle::Standard, "tuna a?id draw oboe tied draw grim ramp" ; "middle of a word")]
+    #[test_case(BytewordsStyle::Minimal, "ta?dwoetddwgmrp" ; "minimal whole word")]
+    #[test_case(BytewordsStyle::Minimal, "tad?dwoetddwgmrp" ; "minimal middle of a word")]
+    fn test_positive_bytewords_decoder_unreadable_word_skips_checksum(
+        style: BytewordsStyle,
+        words: &str,
+    ) {
+        let mut cursor = Cursor::new(words);
+        let mut decoder = Decode::<_, BytewordsDecode<_>>::decode(cursor.chars()).with_style(style);
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(symbols, &[Some(0xd9), None, Some(0x2c), Some(0xa2)]);
+    }
+
+    #[test_case("tuna acid draw oboe acid good slot axis limp lava brag holy door puff monk brag guru frog luau drop roof grim also trip idle chef fuel twin tied draw grim roof", "bytewords checksum mismatch" ; "wrong checksum")]
+    #[test_case("tuna acid draw oboe acid good slot axis limp lava brag holy door puff monk brag guru frog luau drop roof grim also trip idle chef fuel twin tied draw grim", "bytewords checksum mismatch" ; "truncated")]
+    #[test_case("tuna acid", "input is too short to hold a bytewords checksum" ; "too short")]
+    fn test_negative_bytewords_decoder_checksum(words: &str, expected: &str) {
+        let error = decode(BytewordsStyle::Standard, words).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[cfg(all(feature = "fec", feature = "encode_bytewords"))]
+    #[test_case(&[], &[3], Ok(()) ; "wrong data word")]
+    #[test_case(&[], &[18], Ok(()) ; "wrong parity word")]
+    #[test_case(&[], &[45], Ok(()) ; "wrong word in last block")]
+    #[test_case(&[30], &[5], Ok(()) ; "unreadable and wrong words")]
+    #[test_case(&[], &[52], Err("bytewords checksum mismatch") ; "wrong checksum word")]
+    fn test_bytewords_decoder_fec_corrected_checksum(
+        unreadable: &[usize],
+        wrong: &[usize],
+        expected: Result<(), &str>,
+    ) {
+        use crate::{Encode, FecDecode, FecEncode, FecParams};
+        use std::io::Read;
+
+        let params = FecParams::new(16, 4).unwrap();
+        let bytes_orig: Vec<u8> = (0u8..40).map(|b| b.wrapping_mul(71)).collect();
+        let bytes = FecEncode::new(Cursor::new(&bytes_orig).bytes(), params);
+        let mut words = Encode::<_, crate::BytewordsEncode<_>>::encode(bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        for &p in wrong {
+            // Another valid word, so that the checksum is the first to notice
+            words[p] = if words[p] == "able" { "acid" } else { "able" };
+        }
+        for &p in unreadable {
+            words[p] = "?";
+        }
+        let words = words.join(" ");
+        let mut cursor = Cursor::new(&words);
+        let decoder = Decode::<_, BytewordsDecode<_>>::decode(cursor.chars());
+        let mut decoder = FecDecode::new(decoder, params);
+        let decoded = decoder.by_ref().collect::<Result<Vec<_>, _>>();
+        match expected {
+            Ok(()) => {
+                assert_eq!(decoded.unwrap(), bytes_orig);
+                assert_eq!(decoder.corrections().len(), unreadable.len() + wrong.len());
+            }
+            Err(expected) => assert_eq!(decoded.unwrap_err().to_string(), expected),
+        }
+    }
+
+    #[test_case("id_ed25519.txt", BytewordsStyle::Standard)]
+    #[test_case("id_ed25519-uri.txt", BytewordsStyle::Uri)]
+    #[test_case("id_ed25519-minimal.txt", BytewordsStyle::Minimal)]
+    fn test_positive_bytewords_decoder_sample_data_file_id_ed25519<P: AsRef<Path>>(
+        fpath_encoded: P,
+        style: BytewordsStyle,
+    ) {
+        let fpath_original_id_ed25519 = "sample_data/original/id_ed25519";
+        let expected_bytes = std::fs::read(fpath_original_id_ed25519).unwrap();
+
+        let fpath_encoded = Path::new("sample_data/encoded/bytewords").join(fpath_encoded);
+        let mut input_encoded = BufReader::new(File::open(fpath_encoded).unwrap());
+
+        let decoded_bytes = Decode::<_, BytewordsDecode<_>>::decode(input_encoded.chars())
+            .with_style(style)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(decoded_bytes, expected_bytes);
+    }
+}
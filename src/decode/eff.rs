@@ -14,111 +14,57 @@
  * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
  */
 
-use super::WordlistSubset;
+use super::{DecodeSymbol, Ignored, IgnoredFilter, Ignoring, WordMatch, WordMatcher};
 
 /// Base 256 decoder using EFF Short Wordlist 2.0
 #[derive(Clone, Debug)]
 pub struct EffDecode<I: Iterator> {
-    iter: I,
-    candidate_wl_subsets_remaining: Vec<WordlistSubset<'static>>,
-    prev_match_len: usize,
-    curr_match_len: usize,
+    iter: IgnoredFilter<I>,
+    matcher: WordMatcher,
 }
 
-impl<I> Iterator for EffDecode<I>
+impl<I: Iterator> Ignoring for EffDecode<I> {
+    fn ignoring(mut self, ignored: Ignored) -> Self {
+        self.iter.set_ignored(ignored);
+        self
+    }
+}
+
+impl<I> DecodeSymbol for EffDecode<I>
 where
     I: Iterator<Item = Result<char, std::io::Error>>,
 {
-    type Item = Result<u8, std::io::Error>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
         for word_byte in self.iter.by_ref() {
             // We immediately return the error if one is encountered.
-            let Ok(word_char) = word_byte else { return Some(Err(word_byte.unwrap_err())) };
+            let Ok(word_char) = word_byte else {
+                return Some(Err(word_byte.unwrap_err()));
+            };
 
             let word_chars: Vec<_> = word_char.to_lowercase().collect();
 
-            // We skip space, newline and carriage return characters
+            // We skip space, newline and carriage return characters,
+            // which is also what everything else that is ignored has been replaced with
             if word_chars == [' '] || word_chars == ['\n'] || word_chars == ['\r'] {
                 continue;
             }
 
-            self.curr_match_len += word_chars.len();
-            //dbg!(self.curr_match_len);
-            //dbg!(&self.candidate_wl_subsets_remaining);
-
-            // Remove subsets that are too short from the current set of possible matches.
-            let first_subset_remaining = self
-                .candidate_wl_subsets_remaining
-                .partition_point(|wl| wl.word_len < self.curr_match_len);
-            self.candidate_wl_subsets_remaining =
-                self.candidate_wl_subsets_remaining[first_subset_remaining..].to_owned();
-
-            for subset in self.candidate_wl_subsets_remaining.iter_mut() {
-                // Find first word in subset that matches so far (alternate implementation)
-                for (i, word_char) in word_chars.iter().enumerate() {
-                    let subset_words_idx_low = subset.words.partition_point(|entry| {
-                        entry.word.chars().nth(self.prev_match_len + i).unwrap() < *word_char
-                    });
-                    //dbg!(subset.words);
-                    subset.words = &subset.words[subset_words_idx_low..];
-                    //dbg!(subset.words);
+            // A question mark marks the word it is part of as unreadable
+            if word_chars == ['?'] {
+                if let Err(e) = super::skip_unreadable_word(self.iter.by_ref()) {
+                    return Some(Err(e));
                 }
-
-                /*
-                // Find first word in subset that matches so far (original implementation)
-                let mut subset_words_idx_low = 0;
-                for entry in subset.words {
-                    let word_remainder_to_match = &entry.word[self.prev_match_len..];
-                    if word_remainder_to_match.starts_with(&*word_chars) {
-                        //dbg!(entry.word, &word_chars, word_remainder_to_match);
-                        break;
-                    }
-                    subset_words_idx_low += 1;
-                }
-                //dbg!(subset.words);
-                subset.words = &subset.words[subset_words_idx_low..];
-                //dbg!(subset.words);
-                 */
-
-                // Find last word in subset that matches so far
-                let subset_words_idx_high = subset.words.partition_point(|entry| {
-                    let word_remainder_to_match = &entry.word[self.prev_match_len..];
-                    word_remainder_to_match.starts_with(&*word_chars)
-                });
-                //dbg!(subset.words);
-                subset.words = &subset.words[..subset_words_idx_high];
-                //dbg!(subset.words);
+                self.matcher.reset(super::WL_EFF_DECODE);
+                return Some(Ok(None));
             }
 
-            // Remove empty subsets
-            self.candidate_wl_subsets_remaining = self
-                .candidate_wl_subsets_remaining
-                .clone()
-                .into_iter()
-                .filter(|wl| !wl.words.is_empty())
-                .collect();
-
-            self.prev_match_len = self.curr_match_len;
-
-            // No candidates remaining means input data was not valid
-            if self.candidate_wl_subsets_remaining.is_empty() {
-                return Some(Err(std::io::Error::from(std::io::ErrorKind::InvalidData)));
-            }
-
-            // Check for exact match
-            if self.candidate_wl_subsets_remaining.len() == 1 {
-                //dbg!(&self.candidate_wl_subsets_remaining);
-                if self.candidate_wl_subsets_remaining[0].words.len() == 1
-                    && self.curr_match_len == self.candidate_wl_subsets_remaining[0].word_len
-                {
-                    let ret_byte = self.candidate_wl_subsets_remaining[0].words[0].byte;
-
-                    self.candidate_wl_subsets_remaining = super::WL_EFF_DECODE.to_vec();
-                    self.prev_match_len = 0;
-                    self.curr_match_len = 0;
-
-                    return Some(Ok(ret_byte));
+            match self.matcher.push(&word_chars) {
+                WordMatch::Partial => {}
+                // No candidates remaining means input data was not valid
+                WordMatch::Invalid => return Some(Err(super::invalid_char(word_char))),
+                WordMatch::Word(byte) => {
+                    self.matcher.reset(super::WL_EFF_DECODE);
+                    return Some(Ok(Some(byte)));
                 }
             }
         }
@@ -126,20 +72,36 @@ where
     }
 }
 
+impl<I> Iterator for EffDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_symbol()? {
+            Ok(Some(byte)) => Some(Ok(byte)),
+            Ok(None) => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "word marked as unreadable",
+            ))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
 impl<I: Iterator<Item = Result<char, E>>, E> crate::Decode<I, EffDecode<I>> for I {
     fn decode(self) -> EffDecode<I> {
         EffDecode {
-            iter: self,
-            candidate_wl_subsets_remaining: super::WL_EFF_DECODE.to_vec(),
-            prev_match_len: 0,
-            curr_match_len: 0,
+            iter: Ignored::default().filter(self),
+            matcher: WordMatcher::new(super::WL_EFF_DECODE),
         }
     }
 }
 
 #[cfg(test)]
 mod test_cases_decode {
-    use super::super::Decode;
+    use super::super::{Decode, DecodeSymbol};
     use super::EffDecode;
     use std::fs::File;
     use std::io::{BufReader, Cursor};
@@ -154,15 +116,29 @@ mod test_cases_decode {
     #[test_case("acutenessacut\nenessacuteness"; "words mushed wrapped")]
     #[test_case("ACUTENESS ACUTENESS ACUTENESS "; "words spaced uppercase")]
     #[test_case("Acuteness ACUTEness acuteNESS "; "words spaced mixed-case")]
+    #[test_case("acuteness\tacuteness\tacuteness"; "words tab separated")]
+    #[test_case("acute-ness | acuteness, acuteness."; "words with punctuation")]
+    #[test_case("1. acuteness acuteness\n2. acuteness\n"; "words with line numbers")]
+    #[test_case("acuteness acuteness # two\nacuteness # three"; "words with comments")]
     fn test_positive_eff_decoder_0x05_0x05_0x05(words: &str) {
         let mut cursor = Cursor::new(words);
-        let words_chars = cursor.chars().into_iter();
+        let words_chars = cursor.chars();
         let decoded_bytes = Decode::<_, EffDecode<_>>::decode(words_chars)
             .collect::<Result<Vec<_>, _>>()
             .unwrap();
         assert_eq!(decoded_bytes, &[0x05u8; 3]);
     }
 
+    #[test]
+    fn test_positive_eff_decoder_unreadable_word() {
+        let mut cursor = Cursor::new("acuteness ? acuteness");
+        let mut decoder = Decode::<_, EffDecode<_>>::decode(cursor.chars());
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(symbols, &[Some(0x05u8), None, Some(0x05u8)]);
+    }
+
     #[test_case("id_ed25519.txt")]
     #[test_case("id_ed25519-fold_w_78.txt")]
     #[test_case("id_ed25519-fold_w_78_s.txt")]
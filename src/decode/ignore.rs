@@ -0,0 +1,259 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use crate::normalize::normalize;
+use std::collections::VecDeque;
+
+/// What the decoders skip over in transcripts, besides spaces and line breaks.
+///
+/// The default skips the separators and margins found in transcripts pasted
+/// from emails and chat, or laid out by the encoder, along with `#` comments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ignored {
+    /// Characters that separate words, like the tabs, hyphens and commas of pasted transcripts.
+    pub separators: Vec<char>,
+    /// Character that starts a comment, which runs to the end of the line.
+    pub comment: Option<char>,
+    /// Whether to skip line numbers, like `12` or `12.`, and byte offsets, like `0040:`,
+    /// at the start of lines.
+    pub margins: bool,
+}
+
+impl Default for Ignored {
+    fn default() -> Self {
+        Self {
+            separators: vec!['\t', '-', ',', '.', '|'],
+            comment: Some('#'),
+            margins: true,
+        }
+    }
+}
+
+impl Ignored {
+    /// Skip nothing but spaces and line breaks.
+    pub fn nothing() -> Self {
+        Self {
+            separators: vec![],
+            comment: None,
+            margins: false,
+        }
+    }
+
+    /// Normalize `chars`, and replace everything that is ignored in them with spaces.
+    ///
+    /// Each character is replaced by exactly one character, apart from invisible characters
+    /// and diacritics on their own, which are left out, and the rare compatibility characters
+    /// like ligatures that normalize to several.
+    pub fn filter<I>(self, chars: I) -> IgnoredFilter<I>
+    where
+        I: Iterator,
+    {
+        IgnoredFilter {
+            iter: chars,
+            ignored: self,
+            normalized: VecDeque::new(),
+            spelled: &[],
+            pending: VecDeque::new(),
+            at_line_start: true,
+            in_comment: false,
+        }
+    }
+}
+
+/// Decoders that skip over what is [`Ignored`] in their input.
+pub trait Ignoring: Sized {
+    /// Replace what is skipped over in the input. Characters already read are not affected.
+    fn ignoring(self, ignored: Ignored) -> Self;
+}
+
+/// Normalizes a stream of characters, and replaces everything that is [`Ignored`] in it with spaces.
+///
+/// Whitespace, dashes and letters that look like Latin letters are normalized, along with
+/// full-width and accented letters when the unicode feature is enabled at compile-time.
+#[derive(Clone, Debug)]
+pub struct IgnoredFilter<I: Iterator> {
+    iter: I,
+    ignored: Ignored,
+    /// Normalized characters not yet looked at.
+    normalized: VecDeque<char>,
+    /// Letters that are replaced by the first of their ASCII spellings instead of normalized.
+    spelled: &'static [(char, &'static [&'static str])],
+    /// Characters looked at, but not yet returned.
+    pending: VecDeque<char>,
+    at_line_start: bool,
+    in_comment: bool,
+}
+
+impl<I: Iterator> IgnoredFilter<I> {
+    /// Replace what is ignored. Characters already read are not affected.
+    pub(crate) fn set_ignored(&mut self, ignored: Ignored) {
+        self.ignored = ignored;
+    }
+
+    /// Replace the given letters, in either case, by the first of their spellings.
+    ///
+    /// This is done instead of normalizing them, which would strip `å` down to `a`
+    /// and leave `ø` as it is, so that words can be matched against their ASCII spellings.
+    #[cfg(any(feature = "decode_norwegian", feature = "decode_german"))]
+    pub(crate) fn set_spelled(&mut self, spelled: &'static [(char, &'static [&'static str])]) {
+        self.spelled = spelled;
+    }
+
+    fn next_normalized<E>(&mut self) -> Option<Result<char, E>>
+    where
+        I: Iterator<Item = Result<char, E>>,
+    {
+        while self.normalized.is_empty() {
+            match self.iter.next()? {
+                Ok(c) => match self.spelled.iter().find(|(l, _)| c.to_lowercase().eq([*l])) {
+                    Some((_, spellings)) => self.normalized.extend(spellings[0].chars()),
+                    None => normalize(c, &mut self.normalized),
+                },
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.normalized.pop_front().map(Ok)
+    }
+
+    fn map(&mut self, c: char) -> char {
+        if c == '\n' {
+            self.at_line_start = true;
+            self.in_comment = false;
+            return c;
+        }
+        if self.in_comment {
+            return ' ';
+        }
+        if Some(c) == self.ignored.comment {
+            self.in_comment = true;
+            return ' ';
+        }
+        if self.ignored.separators.contains(&c) {
+            return ' ';
+        }
+        if c != ' ' && c != '\r' {
+            self.at_line_start = false;
+        }
+        c
+    }
+}
+
+impl<I, E> Iterator for IgnoredFilter<I>
+where
+    I: Iterator<Item = Result<char, E>>,
+{
+    type Item = Result<char, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(c) = self.pending.pop_front() {
+            return Some(Ok(c));
+        }
+        let c = match self.next_normalized()? {
+            Ok(c) => c,
+            Err(e) => return Some(Err(e)),
+        };
+        if !(self.ignored.margins
+            && self.at_line_start
+            && !self.in_comment
+            && c.is_ascii_hexdigit())
+        {
+            return Some(Ok(self.map(c)));
+        }
+
+        // Read ahead to the end of what may be a line number or a byte offset.
+        // An error while reading ahead is returned right away, as decoding ends there anyway.
+        let mut token = vec![c];
+        let terminator = loop {
+            match self.next_normalized() {
+                Some(Ok(c)) if c.is_ascii_hexdigit() => token.push(c),
+                Some(Ok(c)) => break Some(c),
+                Some(Err(e)) => return Some(Err(e)),
+                None => break None,
+            }
+        };
+        let is_margin = match terminator {
+            Some(':') => true,
+            Some(t) if token.iter().all(char::is_ascii_digit) => {
+                t.is_whitespace() || ['.', ')'].contains(&t)
+            }
+            None => token.iter().all(char::is_ascii_digit),
+            _ => false,
+        };
+
+        if is_margin {
+            self.pending.extend(token.iter().skip(1).map(|_| ' '));
+            match terminator {
+                Some(t) if t.is_whitespace() => {
+                    let t = self.map(t);
+                    self.pending.push_back(t)
+                }
+                Some(_) => self.pending.push_back(' '),
+                None => {}
+            }
+            Some(Ok(' '))
+        } else {
+            self.at_line_start = false;
+            self.pending.extend(token.into_iter().skip(1));
+            if let Some(t) = terminator {
+                let t = self.map(t);
+                self.pending.push_back(t);
+            }
+            Some(Ok(c))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_ignored {
+    use super::Ignored;
+    use test_case::test_case;
+
+    fn filter(ignored: Ignored, text: &str) -> String {
+        ignored
+            .filter(text.chars().map(Ok::<_, ()>))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test_case("adult\tamulet" ; "tab")]
+    #[test_case("adult-amulet" ; "hyphen")]
+    #[test_case("adult,amulet" ; "comma")]
+    #[test_case("adult.amulet" ; "period")]
+    #[test_case("adult|amulet" ; "pipe")]
+    fn test_positive_ignored_separators(text: &str) {
+        assert_eq!(
+            filter(Ignored::default(), text),
+            "adult amulet"[..text.len()]
+        );
+    }
+
+    #[test_case("   1  0000:  adult amulet\n   2  0002:  adult\n",
+        "             adult amulet\n             adult\n" ; "line numbers and byte offsets")]
+    #[test_case("12. adult\n0a0f: amulet", "    adult\n      amulet" ; "numbered list and hex offset")]
+    #[test_case("adult # 2 words\n# amulet\namulet", "adult          \n        \namulet" ; "comments")]
+    #[test_case("adult 12\n", "adult 12\n" ; "number after words")]
+    #[test_case("beef adult\n", "beef adult\n" ; "word of hex digits")]
+    #[test_case("12adult\n", "12adult\n" ; "number joined to word")]
+    fn test_positive_ignored_default(text: &str, expected: &str) {
+        assert_eq!(filter(Ignored::default(), text), expected);
+    }
+
+    #[test]
+    fn test_positive_ignored_nothing() {
+        let text = "  1  0000:  adult-amulet\t# comment\n";
+        assert_eq!(filter(Ignored::nothing(), text), text);
+    }
+}
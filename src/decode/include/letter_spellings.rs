@@ -0,0 +1,31 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+/// ASCII spellings of the Norwegian and German letters that are not in the English alphabet,
+/// as written on keyboards without them.
+///
+/// The first spelling of each letter is the one that the letter itself is read as.
+/// The decode lists of the localized wordlists hold every spelling of each word,
+/// so that for example `blåbær` can be written `blaabaer` or `blabaer`.
+pub(crate) const LETTER_SPELLINGS: &[(char, &[&str])] = &[
+    ('æ', &["ae"]),
+    ('ø', &["oe", "o"]),
+    ('å', &["aa", "a"]),
+    ('ä', &["ae", "a"]),
+    ('ö', &["oe", "o"]),
+    ('ü', &["ue", "u"]),
+    ('ß', &["ss"]),
+];
@@ -0,0 +1,214 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use super::{
+    DecodeSymbol, Ignored, IgnoredFilter, Ignoring, WordMatch, WordMatcher, WordlistSubset,
+};
+
+/// Base 256 decoder using one of the wordlists with special letters, like the Norwegian
+/// and German wordlists
+///
+/// Words can be written with or without their special letters,
+/// see [`LocalizedDecode::norwegian`] and [`LocalizedDecode::german`].
+#[derive(Clone, Debug)]
+pub struct LocalizedDecode<I: Iterator> {
+    iter: IgnoredFilter<I>,
+    wordlist: &'static [WordlistSubset<'static>],
+    matcher: WordMatcher,
+}
+
+impl<I: Iterator<Item = Result<char, E>>, E> LocalizedDecode<I> {
+    /// Decode words of the Norwegian wordlist. Æ, ø and å can also be written
+    /// as `ae`, `oe` and `aa`, and ø and å as `o` and `a`.
+    #[cfg(feature = "decode_norwegian")]
+    pub fn norwegian(chars: I) -> Self {
+        Self::new(chars, super::WL_NORWEGIAN_DECODE)
+    }
+
+    /// Decode words of the German wordlist. Ä, ö and ü can also be written
+    /// as `ae`, `oe` and `ue` or as `a`, `o` and `u`, and ß as `ss`.
+    #[cfg(feature = "decode_german")]
+    pub fn german(chars: I) -> Self {
+        Self::new(chars, super::WL_GERMAN_DECODE)
+    }
+
+    fn new(chars: I, wordlist: &'static [WordlistSubset<'static>]) -> Self {
+        let mut iter = Ignored::default().filter(chars);
+        iter.set_spelled(super::LETTER_SPELLINGS);
+        Self {
+            iter,
+            wordlist,
+            matcher: WordMatcher::new(wordlist),
+        }
+    }
+}
+
+impl<I: Iterator> Ignoring for LocalizedDecode<I> {
+    fn ignoring(mut self, ignored: Ignored) -> Self {
+        self.iter.set_ignored(ignored);
+        self
+    }
+}
+
+impl<I> DecodeSymbol for LocalizedDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        for word_byte in self.iter.by_ref() {
+            // We immediately return the error if one is encountered.
+            let Ok(word_char) = word_byte else {
+                return Some(Err(word_byte.unwrap_err()));
+            };
+
+            let word_chars: Vec<_> = word_char.to_lowercase().collect();
+
+            // We skip space, newline and carriage return characters,
+            // which is also what everything else that is ignored has been replaced with
+            if word_chars == [' '] || word_chars == ['\n'] || word_chars == ['\r'] {
+                continue;
+            }
+
+            // A question mark marks the word it is part of as unreadable
+            if word_chars == ['?'] {
+                if let Err(e) = super::skip_unreadable_word(self.iter.by_ref()) {
+                    return Some(Err(e));
+                }
+                self.matcher.reset(self.wordlist);
+                return Some(Ok(None));
+            }
+
+            match self.matcher.push(&word_chars) {
+                WordMatch::Partial => {}
+                // No candidates remaining means input data was not valid
+                WordMatch::Invalid => return Some(Err(super::invalid_char(word_char))),
+                WordMatch::Word(byte) => {
+                    self.matcher.reset(self.wordlist);
+                    return Some(Ok(Some(byte)));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<I> Iterator for LocalizedDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_symbol()? {
+            Ok(Some(byte)) => Some(Ok(byte)),
+            Ok(None) => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "word marked as unreadable",
+            ))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_decode {
+    use super::super::DecodeSymbol;
+    use super::LocalizedDecode;
+    use std::io::Cursor;
+    use test_case::test_case;
+    use utf8_chars::BufReadCharsExt;
+
+    #[cfg(feature = "decode_norwegian")]
+    #[test_case("blåbær bjørn ørn"; "words spaced")]
+    #[test_case("BLÅBÆR Bjørn ØRN"; "words spaced uppercase")]
+    #[test_case("blåbærbjørnørn"; "words mushed")]
+    #[test_case("blaabaer bjoern oern"; "words spelled with two letters")]
+    #[test_case("blabaer bjorn orn"; "words spelled with one letter")]
+    #[test_case("blaabær bjorn Oern"; "words spelled mixed")]
+    #[test_case("blabaerbjoernorn"; "words spelled mushed")]
+    fn test_positive_norwegian_decoder(words: &str) {
+        let mut cursor = Cursor::new(words);
+        let decoded_bytes = LocalizedDecode::norwegian(cursor.chars())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded_bytes, &[0x10, 0x0B, 0xFC]);
+    }
+
+    #[cfg(feature = "decode_norwegian")]
+    #[test]
+    fn test_positive_norwegian_decoder_unreadable_word() {
+        let mut cursor = Cursor::new("blåbær ? ørn");
+        let mut decoder = LocalizedDecode::norwegian(cursor.chars());
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(symbols, &[Some(0x10u8), None, Some(0xFCu8)]);
+    }
+
+    #[cfg(feature = "decode_norwegian")]
+    #[test_case("blåbær bjørm"; "misspelled word")]
+    #[test_case("blåbær xylofon"; "word not in wordlist")]
+    #[test_case("bläbär"; "special letters of another language")]
+    fn test_negative_norwegian_decoder(words: &str) {
+        let mut cursor = Cursor::new(words);
+        let decoded_bytes =
+            LocalizedDecode::norwegian(cursor.chars()).collect::<Result<Vec<_>, _>>();
+        assert_eq!(
+            decoded_bytes.unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+    }
+
+    #[cfg(feature = "decode_german")]
+    #[test_case("Straße Brücke Fuß"; "words spaced")]
+    #[test_case("straße brücke fuß"; "words spaced lowercase")]
+    #[test_case("STRAẞE BRÜCKE FUẞ"; "words spaced uppercase")]
+    #[test_case("StraßeBrückeFuß"; "words mushed")]
+    #[test_case("Strasse Bruecke Fuss"; "words spelled with two letters")]
+    #[test_case("STRASSE BRUCKE FUSS"; "words spelled with one letter")]
+    #[test_case("strassebrueckefuss"; "words spelled mushed")]
+    fn test_positive_german_decoder(words: &str) {
+        let mut cursor = Cursor::new(words);
+        let decoded_bytes = LocalizedDecode::german(cursor.chars())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded_bytes, &[0xD2, 0x1D, 0x41]);
+    }
+
+    #[cfg(feature = "decode_german")]
+    #[test]
+    fn test_positive_german_decoder_unreadable_word() {
+        let mut cursor = Cursor::new("Straße ? Fuß");
+        let mut decoder = LocalizedDecode::german(cursor.chars());
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(symbols, &[Some(0xD2u8), None, Some(0x41u8)]);
+    }
+
+    #[cfg(feature = "decode_german")]
+    #[test_case("Straße Brüke"; "misspelled word")]
+    #[test_case("Strase"; "sharp s spelled with one letter")]
+    #[test_case("Brøcke"; "special letters of another language")]
+    fn test_negative_german_decoder(words: &str) {
+        let mut cursor = Cursor::new(words);
+        let decoded_bytes = LocalizedDecode::german(cursor.chars()).collect::<Result<Vec<_>, _>>();
+        assert_eq!(
+            decoded_bytes.unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+    }
+}
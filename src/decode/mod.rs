@@ -14,22 +14,68 @@
  * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
  */
 
+#[cfg(feature = "decode_bytewords")]
+mod bytewords;
 #[cfg(feature = "decode_eff")]
 mod eff;
+#[cfg(feature = "decode")]
+mod ignore;
+#[cfg(any(feature = "decode_norwegian", feature = "decode_german"))]
+mod localized;
 #[cfg(feature = "decode_pgp")]
 mod pgp;
+#[cfg(feature = "decode_proquint")]
+mod proquint;
+#[cfg(feature = "decode")]
+mod radix;
+#[cfg(feature = "decode_rfc1751")]
+mod rfc1751;
+#[cfg(feature = "decode")]
+mod segment;
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords"
+))]
+mod voice;
 
+#[cfg(feature = "decode_bytewords")]
+pub use bytewords::*;
 #[cfg(feature = "decode_eff")]
 pub use eff::*;
+#[cfg(feature = "decode")]
+pub use ignore::*;
+#[cfg(any(feature = "decode_norwegian", feature = "decode_german"))]
+pub use localized::*;
 #[cfg(feature = "decode_pgp")]
 pub use pgp::*;
+#[cfg(feature = "decode_proquint")]
+pub use proquint::*;
+#[cfg(feature = "decode")]
+pub use radix::*;
+#[cfg(feature = "decode_rfc1751")]
+pub use rfc1751::*;
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords"
+))]
+pub use voice::*;
 
 // Note: Decode candidate wordlist struct and decode list entry struct are currently not exported,
 //       as they are rather specific to the implementation.
 // Note: Said struct implementations are included at compile-time so that both the build script
 //       and the crate itself can share that code.
-#[cfg(any(feature = "wl_eff_decode", feature = "wl_pgp_decode"))]
+#[cfg(any(
+    feature = "wl_eff_decode",
+    feature = "wl_pgp_decode",
+    feature = "wl_bytewords_decode",
+    feature = "wl_norwegian_decode",
+    feature = "wl_german_decode"
+))]
 include!("include/candidate_words.rs");
+#[cfg(any(feature = "decode_norwegian", feature = "decode_german"))]
+include!("include/letter_spellings.rs");
 
 // Note: Decode lists are currently not exported, as they are rather specific to the implementation.
 // Note: The decode lists are generated at compile-time by the build script.
@@ -37,9 +83,412 @@ include!("include/candidate_words.rs");
 include!(concat!(env!("OUT_DIR"), "/wl_eff_decode.rs"));
 #[cfg(feature = "wl_pgp_decode")]
 include!(concat!(env!("OUT_DIR"), "/wl_pgp_decode.rs"));
+#[cfg(feature = "wl_bytewords_decode")]
+include!(concat!(env!("OUT_DIR"), "/wl_bytewords_decode.rs"));
+#[cfg(feature = "wl_rfc1751_decode")]
+include!(concat!(env!("OUT_DIR"), "/wl_rfc1751_decode.rs"));
+#[cfg(feature = "wl_eff_full_decode")]
+include!(concat!(env!("OUT_DIR"), "/wl_eff_full_decode.rs"));
+#[cfg(feature = "wl_norwegian_decode")]
+include!(concat!(env!("OUT_DIR"), "/wl_norwegian_decode.rs"));
+#[cfg(feature = "wl_german_decode")]
+include!(concat!(env!("OUT_DIR"), "/wl_german_decode.rs"));
 
 /// Base 256 decoder trait
 #[cfg(feature = "decode")]
 pub trait Decode<I: Iterator, D> {
     fn decode(self) -> D;
 }
+
+/// Decoders that can report unreadable words instead of failing on them.
+///
+/// A word that could not be read back, for example from a smudged paper backup,
+/// can be replaced by `?` in the input. Such a word is reported as an erasure (`Ok(None)`),
+/// whereas the [`Iterator`] implementation of the decoders treats it as invalid data.
+#[cfg(feature = "decode")]
+pub trait DecodeSymbol: Iterator<Item = Result<u8, std::io::Error>> {
+    /// Decode the next word, returning `Ok(None)` for a word marked as unreadable.
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>>;
+
+    /// Hand back the bytes that forward error correction corrected the last `bytes.len()`
+    /// symbols to.
+    ///
+    /// Decoders that verify a checksum at the end of the input, like the Bytewords decoder,
+    /// verify it over the corrected bytes instead of the words as read. Once this has been
+    /// called, even with no bytes, a checksum is not verified at the end of the input
+    /// before the symbols read last have been handed back. Fails if the checksum does not match.
+    fn correct_symbols(&mut self, _bytes: &[u8]) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<D: DecodeSymbol + ?Sized> DecodeSymbol for Box<D> {
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        (**self).next_symbol()
+    }
+
+    fn correct_symbols(&mut self, bytes: &[u8]) -> Result<(), std::io::Error> {
+        (**self).correct_symbols(bytes)
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<D: DecodeSymbol + ?Sized> DecodeSymbol for &mut D {
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        (**self).next_symbol()
+    }
+
+    fn correct_symbols(&mut self, bytes: &[u8]) -> Result<(), std::io::Error> {
+        (**self).correct_symbols(bytes)
+    }
+}
+
+/// Position of a character in a transcript, counting lines and columns from one.
+#[cfg(feature = "decode")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourcePosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[cfg(feature = "decode")]
+impl std::fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// The words of a list by byte, each as written along with all of its spellings.
+#[cfg(feature = "decode")]
+pub(crate) type ByteWordlist = Vec<(&'static str, Vec<&'static str>)>;
+
+/// The words of each list of `codec` by byte, as written, each with every spelling of it
+/// that the decoder reads. The PGP Word List has the two-syllable list first.
+///
+/// `None` for codecs that do not have one word for each byte,
+/// and for codecs whose decoder was not enabled at compile-time.
+#[cfg(feature = "decode")]
+pub(crate) fn byte_wordlists(codec: crate::Codec) -> Option<Vec<ByteWordlist>> {
+    match codec {
+        #[cfg(feature = "decode_pgp")]
+        crate::Codec::Pgp => Some(vec![
+            by_byte(
+                WL_PGP_DECODE_TWO_SYLLABLE,
+                Some(WL_PGP_DECODE_TWO_SYLLABLE_WORDS),
+            ),
+            by_byte(
+                WL_PGP_DECODE_THREE_SYLLABLE,
+                Some(WL_PGP_DECODE_THREE_SYLLABLE_WORDS),
+            ),
+        ]),
+        #[cfg(feature = "decode_eff")]
+        crate::Codec::Eff => Some(vec![by_byte(WL_EFF_DECODE, None)]),
+        #[cfg(feature = "decode_bytewords")]
+        crate::Codec::Bytewords => Some(vec![by_byte(WL_BYTEWORDS_DECODE, None)]),
+        #[cfg(feature = "decode_bytewords")]
+        crate::Codec::BytewordsMinimal => Some(vec![by_byte(WL_BYTEWORDS_MINIMAL_DECODE, None)]),
+        #[cfg(feature = "decode_norwegian")]
+        crate::Codec::Norwegian => Some(vec![by_byte(
+            WL_NORWEGIAN_DECODE,
+            Some(WL_NORWEGIAN_DECODE_WORDS),
+        )]),
+        #[cfg(feature = "decode_german")]
+        crate::Codec::German => Some(vec![by_byte(
+            WL_GERMAN_DECODE,
+            Some(WL_GERMAN_DECODE_WORDS),
+        )]),
+        _ => None,
+    }
+}
+
+/// The words of a decode list by byte, as written in `written` if given, or else as in the list,
+/// each with all of its spellings in the list.
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords",
+    feature = "decode_norwegian",
+    feature = "decode_german"
+))]
+fn by_byte(
+    wl: &[WordlistSubset<'static>],
+    written: Option<&'static [&'static str]>,
+) -> ByteWordlist {
+    let mut words = vec![("", vec![]); 256];
+    for entry in wl.iter().flat_map(|subset| subset.words) {
+        words[entry.byte as usize].1.push(entry.word);
+    }
+    for (byte, (word, spellings)) in words.iter_mut().enumerate() {
+        *word = written.map_or(spellings[0], |written| written[byte]);
+    }
+    words
+}
+
+/// Error for a character that does not continue any of the candidate words.
+///
+/// Characters that are not used in any word at all, even after normalization,
+/// are named in the error, since they are usually the cause of the problem.
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_norwegian",
+    feature = "decode_german"
+))]
+fn invalid_char(c: char) -> std::io::Error {
+    if c.is_ascii_alphabetic() {
+        return std::io::Error::from(std::io::ErrorKind::InvalidData);
+    }
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("unexpected character {c:?} (U+{:04X})", c as u32),
+    )
+}
+
+/// Skip the rest of a word that a question mark marked as unreadable.
+///
+/// The word ends at the next space, newline or carriage return character,
+/// which is also what everything else that is ignored has been replaced with.
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_norwegian",
+    feature = "decode_german"
+))]
+fn skip_unreadable_word<I>(iter: I) -> Result<(), std::io::Error>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    for word_char in iter {
+        if [' ', '\n', '\r'].contains(&word_char?) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Read the next word, for the decoders that look up whole words, handing each of its
+/// characters to `push` to add to the word.
+///
+/// Words end at space, newline and carriage return characters, which is also what everything
+/// else that is ignored has been replaced with, or once `complete`, given the word so far and
+/// whether it is unreadable, says so, for words that are written together.
+/// A question mark marks the word it is part of as unreadable, which is returned as `Ok(None)`.
+#[cfg(feature = "decode")]
+pub(crate) fn read_word<I>(
+    iter: &mut I,
+    mut push: impl FnMut(&mut String, char),
+    mut complete: impl FnMut(&str, bool) -> bool,
+) -> Option<Result<Option<String>, std::io::Error>>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    let mut word = String::new();
+    let mut unreadable = false;
+    for word_char in iter {
+        // We immediately return the error if one is encountered.
+        let word_char = match word_char {
+            Ok(word_char) => word_char,
+            Err(e) => return Some(Err(e)),
+        };
+        if [' ', '\n', '\r'].contains(&word_char) {
+            if word.is_empty() && !unreadable {
+                continue;
+            }
+            break;
+        }
+        if word_char == '?' {
+            unreadable = true;
+        } else if !unreadable {
+            push(&mut word, word_char);
+        }
+        if complete(&word, unreadable) {
+            break;
+        }
+    }
+    if unreadable {
+        return Some(Ok(None));
+    }
+    if word.is_empty() {
+        return None;
+    }
+    Some(Ok(Some(word)))
+}
+
+/// Outcome of feeding the characters of an input char to a [`WordMatcher`].
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords",
+    feature = "decode_norwegian",
+    feature = "decode_german"
+))]
+enum WordMatch {
+    /// The input so far is the beginning of one or more words.
+    Partial,
+    /// The input so far is exactly one word, which decodes to the given byte.
+    Word(u8),
+    /// The input so far is not the beginning of any word.
+    Invalid,
+}
+
+/// Incremental matching of input chars against the words of a decode wordlist.
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords",
+    feature = "decode_norwegian",
+    feature = "decode_german"
+))]
+#[derive(Clone, Debug)]
+struct WordMatcher {
+    candidate_wl_subsets_remaining: Vec<WordlistSubset<'static>>,
+    prev_match_len: usize,
+    curr_match_len: usize,
+}
+
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords",
+    feature = "decode_norwegian",
+    feature = "decode_german"
+))]
+impl WordMatcher {
+    fn new(wl: &[WordlistSubset<'static>]) -> Self {
+        Self {
+            candidate_wl_subsets_remaining: wl.to_vec(),
+            prev_match_len: 0,
+            curr_match_len: 0,
+        }
+    }
+
+    /// Start matching a new word against the given wordlist.
+    #[cfg(any(
+        feature = "decode_eff",
+        feature = "decode_pgp",
+        feature = "decode_norwegian",
+        feature = "decode_german"
+    ))]
+    fn reset(&mut self, wl: &[WordlistSubset<'static>]) {
+        self.candidate_wl_subsets_remaining = wl.to_vec();
+        self.prev_match_len = 0;
+        self.curr_match_len = 0;
+    }
+
+    /// The word that was matched, once [`WordMatcher::push`] has returned [`WordMatch::Word`].
+    #[cfg(any(
+        feature = "decode_eff",
+        feature = "decode_pgp",
+        feature = "decode_bytewords"
+    ))]
+    fn word(&self) -> &'static str {
+        self.candidate_wl_subsets_remaining[0].words[0].word
+    }
+
+    /// The byte of a whole word, read by [`read_word`], in the given wordlist.
+    #[cfg(feature = "decode_bytewords")]
+    fn lookup(wl: &[WordlistSubset<'static>], word: &str) -> Option<u8> {
+        let mut matcher = Self::new(wl);
+        let mut matched = WordMatch::Partial;
+        for word_char in word.chars() {
+            matched = matcher.push(&[word_char]);
+        }
+        match matched {
+            WordMatch::Word(byte) => Some(byte),
+            WordMatch::Partial | WordMatch::Invalid => None,
+        }
+    }
+
+    /// Feed the (lowercased) chars of one input char to the matcher.
+    fn push(&mut self, word_chars: &[char]) -> WordMatch {
+        self.curr_match_len += word_chars.len();
+
+        // Remove subsets that are too short from the current set of possible matches.
+        let first_subset_remaining = self
+            .candidate_wl_subsets_remaining
+            .partition_point(|wl| wl.word_len < self.curr_match_len);
+        self.candidate_wl_subsets_remaining =
+            self.candidate_wl_subsets_remaining[first_subset_remaining..].to_owned();
+
+        for subset in self.candidate_wl_subsets_remaining.iter_mut() {
+            // Find first word in subset that matches so far
+            for (i, word_char) in word_chars.iter().enumerate() {
+                let subset_words_idx_low = subset.words.partition_point(|entry| {
+                    entry.word.chars().nth(self.prev_match_len + i).unwrap() < *word_char
+                });
+                subset.words = &subset.words[subset_words_idx_low..];
+            }
+
+            // Find last word in subset that matches so far
+            let subset_words_idx_high = subset.words.partition_point(|entry| {
+                let word_remainder_to_match = &entry.word[self.prev_match_len..];
+                word_remainder_to_match.starts_with(word_chars)
+            });
+            subset.words = &subset.words[..subset_words_idx_high];
+        }
+
+        // Remove empty subsets
+        self.candidate_wl_subsets_remaining
+            .retain(|wl| !wl.words.is_empty());
+
+        self.prev_match_len = self.curr_match_len;
+
+        // No candidates remaining means input data was not valid
+        if self.candidate_wl_subsets_remaining.is_empty() {
+            return WordMatch::Invalid;
+        }
+
+        // Check for exact match
+        if self.candidate_wl_subsets_remaining.len() == 1
+            && self.candidate_wl_subsets_remaining[0].words.len() == 1
+            && self.curr_match_len == self.candidate_wl_subsets_remaining[0].word_len
+        {
+            return WordMatch::Word(self.candidate_wl_subsets_remaining[0].words[0].byte);
+        }
+
+        WordMatch::Partial
+    }
+}
+
+#[cfg(any(feature = "wl_norwegian_decode", feature = "wl_german_decode"))]
+#[cfg(test)]
+mod test_cases_wl_localized_decode {
+    use super::*;
+
+    /// No spelling of a word is the beginning of a spelling of another word,
+    /// so that words written together can be told apart.
+    fn is_prefix_free(wl: &[WordlistSubset]) -> bool {
+        let entries: Vec<_> = wl.iter().flat_map(|subset| subset.words).collect();
+        entries.iter().all(|a| {
+            entries
+                .iter()
+                .all(|b| a.byte == b.byte || !b.word.starts_with(a.word))
+        })
+    }
+
+    /// Every byte has at least one spelling.
+    fn covers_all_bytes(wl: &[WordlistSubset]) -> bool {
+        let mut bytes: Vec<_> = wl
+            .iter()
+            .flat_map(|subset| subset.words.iter().map(|entry| entry.byte))
+            .collect();
+        bytes.sort();
+        bytes.dedup();
+        bytes.len() == 256
+    }
+
+    #[cfg(feature = "wl_norwegian_decode")]
+    #[test]
+    fn test_wl_norwegian_decode_is_prefix_free_and_complete() {
+        assert!(is_prefix_free(WL_NORWEGIAN_DECODE));
+        assert!(covers_all_bytes(WL_NORWEGIAN_DECODE));
+    }
+
+    #[cfg(feature = "wl_german_decode")]
+    #[test]
+    fn test_wl_german_decode_is_prefix_free_and_complete() {
+        assert!(is_prefix_free(WL_GERMAN_DECODE));
+        assert!(covers_all_bytes(WL_GERMAN_DECODE));
+    }
+}
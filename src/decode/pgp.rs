@@ -14,118 +14,70 @@
  * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
  */
 
-use super::WordlistSubset;
+use super::{DecodeSymbol, Ignored, IgnoredFilter, Ignoring, WordMatch, WordMatcher};
 
 /// Base 256 decoder using PGP Word List
 #[derive(Clone, Debug)]
 pub struct PgpDecode<I: Iterator> {
-    iter: I,
+    iter: IgnoredFilter<I>,
     odd_even: u8,
-    candidate_wl_subsets_remaining: Vec<WordlistSubset<'static>>,
-    prev_match_len: usize,
-    curr_match_len: usize,
+    matcher: WordMatcher,
 }
 
-impl<I> Iterator for PgpDecode<I>
+impl<I: Iterator> Ignoring for PgpDecode<I> {
+    fn ignoring(mut self, ignored: Ignored) -> Self {
+        self.iter.set_ignored(ignored);
+        self
+    }
+}
+
+impl<I: Iterator> PgpDecode<I> {
+    /// Move on to the next word, which is looked up in the other one of the two lists.
+    fn advance(&mut self) {
+        self.odd_even = (self.odd_even + 1) % 2;
+        self.matcher.reset(if self.odd_even == 0 {
+            super::WL_PGP_DECODE_TWO_SYLLABLE
+        } else {
+            super::WL_PGP_DECODE_THREE_SYLLABLE
+        });
+    }
+}
+
+impl<I> DecodeSymbol for PgpDecode<I>
 where
     I: Iterator<Item = Result<char, std::io::Error>>,
 {
-    type Item = Result<u8, std::io::Error>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
         for word_byte in self.iter.by_ref() {
             // We immediately return the error if one is encountered.
-            let Ok(word_char) = word_byte else { return Some(Err(word_byte.unwrap_err())) };
+            let Ok(word_char) = word_byte else {
+                return Some(Err(word_byte.unwrap_err()));
+            };
 
             let word_chars: Vec<_> = word_char.to_lowercase().collect();
 
-            // We skip space, newline and carriage return characters
+            // We skip space, newline and carriage return characters,
+            // which is also what everything else that is ignored has been replaced with
             if word_chars == [' '] || word_chars == ['\n'] || word_chars == ['\r'] {
                 continue;
             }
 
-            self.curr_match_len += word_chars.len();
-            //dbg!(self.curr_match_len);
-            //dbg!(&self.candidate_wl_subsets_remaining);
-
-            // Remove subsets that are too short from the current set of possible matches.
-            let first_subset_remaining = self
-                .candidate_wl_subsets_remaining
-                .partition_point(|wl| wl.word_len < self.curr_match_len);
-            self.candidate_wl_subsets_remaining =
-                self.candidate_wl_subsets_remaining[first_subset_remaining..].to_owned();
-
-            for subset in self.candidate_wl_subsets_remaining.iter_mut() {
-                // Find first word in subset that matches so far (alternate implementation)
-                for (i, word_char) in word_chars.iter().enumerate() {
-                    let subset_words_idx_low = subset.words.partition_point(|entry| {
-                        entry.word.chars().nth(self.prev_match_len + i).unwrap() < *word_char
-                    });
-                    //dbg!(subset.words);
-                    subset.words = &subset.words[subset_words_idx_low..];
-                    //dbg!(subset.words);
-                }
-
-                /*
-                // Find first word in subset that matches so far (original implementation)
-                let mut subset_words_idx_low = 0;
-                for entry in subset.words {
-                    let word_remainder_to_match = &entry.word[self.prev_match_len..];
-                    if word_remainder_to_match.starts_with(&*word_chars) {
-                        //dbg!(entry.word, &word_chars, word_remainder_to_match);
-                        break;
-                    }
-                    subset_words_idx_low += 1;
+            // A question mark marks the word it is part of as unreadable
+            if word_chars == ['?'] {
+                if let Err(e) = super::skip_unreadable_word(self.iter.by_ref()) {
+                    return Some(Err(e));
                 }
-                //dbg!(subset.words);
-                subset.words = &subset.words[subset_words_idx_low..];
-                //dbg!(subset.words);
-                 */
-
-                // Find last word in subset that matches so far
-                let subset_words_idx_high = subset.words.partition_point(|entry| {
-                    let word_remainder_to_match = &entry.word[self.prev_match_len..];
-                    word_remainder_to_match.starts_with(&*word_chars)
-                });
-                //dbg!(subset.words);
-                subset.words = &subset.words[..subset_words_idx_high];
-                //dbg!(subset.words);
+                self.advance();
+                return Some(Ok(None));
             }
 
-            // Remove empty subsets
-            self.candidate_wl_subsets_remaining = self
-                .candidate_wl_subsets_remaining
-                .clone()
-                .into_iter()
-                .filter(|wl| !wl.words.is_empty())
-                .collect();
-
-            self.prev_match_len = self.curr_match_len;
-
-            // No candidates remaining means input data was not valid
-            if self.candidate_wl_subsets_remaining.is_empty() {
-                return Some(Err(std::io::Error::from(std::io::ErrorKind::InvalidData)));
-            }
-
-            // Check for exact match
-            if self.candidate_wl_subsets_remaining.len() == 1 {
-                //dbg!(&self.candidate_wl_subsets_remaining);
-                if self.candidate_wl_subsets_remaining[0].words.len() == 1
-                    && self.curr_match_len == self.candidate_wl_subsets_remaining[0].word_len
-                {
-                    let ret_byte = self.candidate_wl_subsets_remaining[0].words[0].byte;
-
-                    self.odd_even = (self.odd_even + 1) % 2;
-                    self.candidate_wl_subsets_remaining = if self.odd_even == 0 {
-                        super::WL_PGP_DECODE_TWO_SYLLABLE
-                    } else {
-                        super::WL_PGP_DECODE_THREE_SYLLABLE
-                    }
-                    .to_vec();
-                    self.prev_match_len = 0;
-                    self.curr_match_len = 0;
-
-                    return Some(Ok(ret_byte));
+            match self.matcher.push(&word_chars) {
+                WordMatch::Partial => {}
+                // No candidates remaining means input data was not valid
+                WordMatch::Invalid => return Some(Err(super::invalid_char(word_char))),
+                WordMatch::Word(byte) => {
+                    self.advance();
+                    return Some(Ok(Some(byte)));
                 }
             }
         }
@@ -133,21 +85,37 @@ where
     }
 }
 
+impl<I> Iterator for PgpDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_symbol()? {
+            Ok(Some(byte)) => Some(Ok(byte)),
+            Ok(None) => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "word marked as unreadable",
+            ))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
 impl<I: Iterator<Item = Result<char, E>>, E> crate::Decode<I, PgpDecode<I>> for I {
     fn decode(self) -> PgpDecode<I> {
         PgpDecode {
-            iter: self,
+            iter: Ignored::default().filter(self),
             odd_even: 0,
-            candidate_wl_subsets_remaining: super::WL_PGP_DECODE_TWO_SYLLABLE.to_vec(),
-            prev_match_len: 0,
-            curr_match_len: 0,
+            matcher: WordMatcher::new(super::WL_PGP_DECODE_TWO_SYLLABLE),
         }
     }
 }
 
 #[cfg(test)]
 mod test_cases_decode {
-    use super::super::Decode;
+    use super::super::{Decode, DecodeSymbol, Ignored, Ignoring};
     use super::PgpDecode;
     use std::fs::File;
     use std::io::{BufReader, Cursor};
@@ -162,15 +130,42 @@ mod test_cases_decode {
     #[test_case("adultamuletad\nult"; "words mushed wrapped")]
     #[test_case("ADULT AMULET ADULT "; "words spaced uppercase")]
     #[test_case("Adult AMUlet aDULT "; "words spaced mixed-case")]
+    #[test_case("adult\tamulet\tadult"; "words tab separated")]
+    #[test_case("adult-amulet, adult."; "words with punctuation")]
+    #[test_case("   1  0000:  adult amulet\n   2  0002:  adult\n"; "words with line numbers and byte offsets")]
+    #[test_case("# backup of 0x05 0x05 0x05\nadult amulet # first two\nadult"; "words with comments")]
     fn test_positive_pgp_decoder_0x05_0x05_0x05(words: &str) {
         let mut cursor = Cursor::new(words);
-        let words_chars = cursor.chars().into_iter();
+        let words_chars = cursor.chars();
         let decoded_bytes = Decode::<_, PgpDecode<_>>::decode(words_chars)
             .collect::<Result<Vec<_>, _>>()
             .unwrap();
         assert_eq!(decoded_bytes, &[0x05u8; 3]);
     }
 
+    #[test]
+    fn test_negative_pgp_decoder_ignoring_nothing() {
+        let mut cursor = Cursor::new("adult\tamulet");
+        let decoded = Decode::<_, PgpDecode<_>>::decode(cursor.chars())
+            .ignoring(Ignored::nothing())
+            .collect::<Result<Vec<_>, _>>();
+        assert_eq!(decoded.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test_case("adult ? ADULT" ; "whole word")]
+    #[test_case("adult ?? ADULT" ; "whole word twice")]
+    #[test_case("adult ?mulet ADULT" ; "start of a word")]
+    #[test_case("adult amu?et ADULT" ; "middle of a word")]
+    #[test_case("adult amu?\nADULT" ; "end of a word")]
+    fn test_positive_pgp_decoder_unreadable_word(words: &str) {
+        let mut cursor = Cursor::new(words);
+        let mut decoder = Decode::<_, PgpDecode<_>>::decode(cursor.chars());
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(symbols, &[Some(0x05u8), None, Some(0x05u8)]);
+    }
+
     #[test_case("id_ed25519.txt")]
     #[test_case("id_ed25519-fold_w_78.txt")]
     #[test_case("id_ed25519-fold_w_78_s.txt")]
@@ -189,6 +184,36 @@ mod test_cases_decode {
         assert_eq!(decoded_bytes, expected_bytes);
     }
 
+    #[test_case(|t| t.replace(' ', "\u{00A0}") ; "no-break spaces")]
+    #[test_case(|t| t.replace(' ', " \u{2013} ") ; "en dashes between words")]
+    #[test_case(|t| t.replace('a', "\u{0430}").replace('o', "\u{043E}") ; "cyrillic look-alikes")]
+    #[cfg_attr(feature = "unicode", test_case(|t| t.replace('e', "\u{00E9}").replace('a', "a\u{0300}") ; "diacritics"))]
+    #[cfg_attr(feature = "unicode", test_case(|t| t.chars().map(|c| match c {
+        'A'..='Z' | 'a'..='z' => char::from_u32(c as u32 + 0xFEE0).unwrap(),
+        c => c,
+    }).collect() ; "full-width letters"))]
+    fn test_positive_pgp_decoder_sample_data_file_id_ed25519_unicode(mangle: fn(&str) -> String) {
+        let expected_bytes = std::fs::read("sample_data/original/id_ed25519").unwrap();
+        let encoded =
+            std::fs::read_to_string("sample_data/encoded/pgp/id_ed25519-fold_w_78_s.txt").unwrap();
+        let mut input_encoded = Cursor::new(mangle(&encoded));
+
+        let decoded_bytes = Decode::<_, PgpDecode<_>>::decode(input_encoded.chars())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(decoded_bytes, expected_bytes);
+    }
+
+    #[test_case("adult amußlet", "unexpected character 'ß' (U+00DF)" ; "foreign letter")]
+    #[test_case("adult amu€let", "unexpected character '€' (U+20AC)" ; "symbol")]
+    fn test_negative_pgp_decoder_foreign_character(words: &str, expected_error: &str) {
+        let mut cursor = Cursor::new(words);
+        let decoded =
+            Decode::<_, PgpDecode<_>>::decode(cursor.chars()).collect::<Result<Vec<_>, _>>();
+        assert_eq!(decoded.unwrap_err().to_string(), expected_error);
+    }
+
     #[test_case("id_ed25519-fold_w_78_s.txt")]
     #[test_case("id_ed25519-fold_w_78_s-trimmed.txt")]
     fn test_negative_pgp_decoder_sample_data_file_id_ed25519<P: AsRef<Path>>(fpath_encoded: P) {
@@ -0,0 +1,183 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use super::{DecodeSymbol, Ignored, IgnoredFilter, Ignoring};
+
+/// Consonants of a proquint, each standing for four bits.
+const PROQUINT_CONSONANTS: &str = "bdfghjklmnprstvz";
+/// Vowels of a proquint, each standing for two bits.
+const PROQUINT_VOWELS: &str = "aiou";
+/// Number of letters in each proquint.
+const QUINT_LEN: usize = 5;
+
+/// Base 256 decoder using proquints (PRO-nouncable QUINT-uplets)
+///
+/// Each five-letter word is read as a pair of bytes. Words are matched regardless of case,
+/// and are usually separated by hyphens, as in `lusab-babad`, though they need not be.
+#[derive(Clone, Debug)]
+pub struct ProquintDecode<I: Iterator> {
+    iter: IgnoredFilter<I>,
+    /// Second byte of the last decoded word, not yet returned.
+    low: Option<Option<u8>>,
+}
+
+impl<I: Iterator> Ignoring for ProquintDecode<I> {
+    fn ignoring(mut self, ignored: Ignored) -> Self {
+        self.iter.set_ignored(ignored);
+        self
+    }
+}
+
+/// Value of a proquint, or `None` if `word` is not one.
+fn quint_value(word: &str) -> Option<u16> {
+    if word.chars().count() != QUINT_LEN {
+        return None;
+    }
+    word.chars().enumerate().try_fold(0u16, |value, (i, c)| {
+        let (letters, bits) = match i % 2 {
+            0 => (PROQUINT_CONSONANTS, 4),
+            _ => (PROQUINT_VOWELS, 2),
+        };
+        let digit = letters.find(c)?;
+        Some((value << bits) | digit as u16)
+    })
+}
+
+impl<I> ProquintDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_word(&mut self) -> Option<Result<Option<u16>, std::io::Error>> {
+        let word = match super::read_word(
+            &mut self.iter,
+            |word, c| word.extend(c.to_lowercase()),
+            // Words that are written together are told apart by their length
+            |word, unreadable| !unreadable && word.chars().count() == QUINT_LEN,
+        )? {
+            Ok(Some(word)) => word,
+            Ok(None) => return Some(Ok(None)),
+            Err(e) => return Some(Err(e)),
+        };
+        match quint_value(&word) {
+            Some(value) => Some(Ok(Some(value))),
+            None => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unknown word {word:?}"),
+            ))),
+        }
+    }
+}
+
+impl<I> DecodeSymbol for ProquintDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        if let Some(low) = self.low.take() {
+            return Some(Ok(low));
+        }
+        match self.next_word()? {
+            Ok(Some(value)) => {
+                let [high, low] = value.to_be_bytes();
+                self.low = Some(Some(low));
+                Some(Ok(Some(high)))
+            }
+            Ok(None) => {
+                self.low = Some(None);
+                Some(Ok(None))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<I> Iterator for ProquintDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_symbol()? {
+            Ok(Some(byte)) => Some(Ok(byte)),
+            Ok(None) => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "word marked as unreadable",
+            ))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<char, E>>, E> crate::Decode<I, ProquintDecode<I>> for I {
+    fn decode(self) -> ProquintDecode<I> {
+        ProquintDecode {
+            iter: Ignored::default().filter(self),
+            low: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_decode {
+    use super::super::{Decode, DecodeSymbol};
+    use super::ProquintDecode;
+    use std::io::Cursor;
+    use test_case::test_case;
+    use utf8_chars::BufReadCharsExt;
+
+    fn decode(words: &str) -> Result<Vec<u8>, std::io::Error> {
+        let mut cursor = Cursor::new(words);
+        Decode::<_, ProquintDecode<_>>::decode(cursor.chars()).collect()
+    }
+
+    // IPv4 addresses from the proquint specification.
+    #[test_case("lusab-babad", &[127, 0, 0, 1] ; "127.0.0.1")]
+    #[test_case("gutuk-bisog", &[63, 118, 7, 35] ; "63.118.7.35")]
+    #[test_case("mabiv-gibot", &[128, 30, 52, 45] ; "128.30.52.45")]
+    #[test_case("natag-lisaf", &[147, 67, 119, 2] ; "147.67.119.2")]
+    #[test_case("tibup-zujah", &[212, 58, 253, 68] ; "212.58.253.68")]
+    #[test_case("tobog-higil", &[216, 35, 68, 215] ; "216.35.68.215")]
+    #[test_case("sinid-makam", &[198, 81, 129, 136] ; "198.81.129.136")]
+    #[test_case("LUSAB BABAD\n", &[127, 0, 0, 1] ; "uppercase with spaces")]
+    #[test_case("lusabbabad", &[127, 0, 0, 1] ; "written together")]
+    #[test_case("lusab-ba\u{200B}bad", &[127, 0, 0, 1] ; "zero width space")]
+    #[test_case("lusab-ba\u{00AD}bad", &[127, 0, 0, 1] ; "soft hyphen")]
+    #[cfg_attr(feature = "unicode", test_case("lusa\u{0301}b-babad", &[127, 0, 0, 1] ; "decomposed accent"))]
+    #[test_case("", &[] ; "no data")]
+    fn test_positive_proquint_decoder(words: &str, expected: &[u8]) {
+        assert_eq!(decode(words).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_positive_proquint_decoder_unreadable_word() {
+        let mut cursor = Cursor::new("lusab-?-babad");
+        let mut decoder = Decode::<_, ProquintDecode<_>>::decode(cursor.chars());
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(symbols, &[Some(127), Some(0), None, None, Some(0), Some(1)]);
+    }
+
+    #[test_case("lusab-babda", "unknown word \"babda\"" ; "vowel for consonant")]
+    #[test_case("lusab-babac", "unknown word \"babac\"" ; "letter not used")]
+    #[test_case("lusab-bab", "unknown word \"bab\"" ; "truncated")]
+    fn test_negative_proquint_decoder(words: &str, expected: &str) {
+        let error = decode(words).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), expected);
+    }
+}
@@ -0,0 +1,405 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use super::segment::segment;
+use super::{DecodeSymbol, Ignored, IgnoredFilter, Ignoring};
+use crate::normalize::normalize_word;
+use crate::radix::{digits_for, offset, RadixScheme};
+use std::collections::{HashMap, VecDeque};
+
+/// Decoder for wordlists of any size, the counterpart of [`RadixEncode`](crate::RadixEncode).
+///
+/// Words are matched regardless of case, and can be written together. If some words in
+/// the wordlist are the beginning of others, words written together are split in the only way
+/// they can be, and are an error if they can be split in more than one way; words that are
+/// separated, for example by spaces, are read as written. What is [`Ignored`] is not skipped
+/// where it could be part of a word in the wordlist, like the hyphen in `yo-yo`.
+#[derive(Clone, Debug)]
+pub struct RadixDecode<I: Iterator> {
+    iter: IgnoredFilter<I>,
+    /// Index of each word in the wordlist, by its normalized, lowercase form.
+    lookup: HashMap<String, u32>,
+    /// Whether no word is the beginning of another, so that words written together
+    /// can be told apart as they are read.
+    prefix_free: bool,
+    /// Length of the longest word, in characters.
+    max_word_len: usize,
+    /// Words split from words written together, not yet decoded.
+    split_words: VecDeque<u32>,
+    scheme: RadixScheme,
+    /// Word read, but not decoded until it is known whether it is the last one.
+    held: Option<Option<u32>>,
+    /// Words of the current block, when converting blocks.
+    block: Vec<Option<u32>>,
+    /// Bits read, but not yet decoded, along with which of them are unreadable, when packing bits.
+    bits: u64,
+    erased_bits: u64,
+    bits_len: u32,
+    decoded: VecDeque<Option<u8>>,
+    done: bool,
+}
+
+impl<I: Iterator> RadixDecode<I> {
+    /// Decode the words in `chars` with the given words.
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidInput`] if there are fewer than two words,
+    /// or if a word appears more than once, regardless of case and diacritics.
+    pub fn new<W: AsRef<str>>(chars: I, words: &[W]) -> Result<Self, std::io::Error> {
+        let scheme = RadixScheme::for_wordlist_len(words.len())?;
+        let mut lookup = HashMap::with_capacity(words.len());
+        for (idx, word) in words.iter().enumerate() {
+            let word = normalize_word(word.as_ref());
+            if lookup.insert(word.clone(), idx as u32).is_some() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("word {word:?} appears more than once in the wordlist"),
+                ));
+            }
+        }
+        let mut sorted: Vec<&String> = lookup.keys().collect();
+        sorted.sort();
+        let prefix_free = sorted.windows(2).all(|w| !w[1].starts_with(w[0].as_str()));
+        let max_word_len = sorted.iter().map(|word| word.chars().count()).max();
+        let mut decoder = Self {
+            iter: Ignored::default().filter(chars),
+            lookup,
+            prefix_free,
+            max_word_len: max_word_len.unwrap_or(0),
+            split_words: VecDeque::new(),
+            scheme,
+            held: None,
+            block: vec![],
+            bits: 0,
+            erased_bits: 0,
+            bits_len: 0,
+            decoded: VecDeque::new(),
+            done: false,
+        };
+        decoder
+            .iter
+            .set_ignored(decoder.keep_word_chars(Ignored::default()));
+        Ok(decoder)
+    }
+
+    /// Leave out what would be skipped in words of the wordlist: separators that are part
+    /// of words, the comment character if it is part of a word, and margins if a word
+    /// is a number, which could be taken for a line number.
+    fn keep_word_chars(&self, mut ignored: Ignored) -> Ignored {
+        let words = || self.lookup.keys();
+        ignored
+            .separators
+            .retain(|c| !words().any(|word| word.contains(*c)));
+        if ignored
+            .comment
+            .is_some_and(|c| words().any(|word| word.contains(c)))
+        {
+            ignored.comment = None;
+        }
+        if words().any(|word| word.chars().all(|c| c.is_ascii_digit())) {
+            ignored.margins = false;
+        }
+        ignored
+    }
+
+    /// Decode a word, given whether it is the last one.
+    fn push_word(&mut self, word: Option<u32>, last: bool) -> Result<(), std::io::Error> {
+        match self.scheme {
+            RadixScheme::Bytes => {
+                self.decoded.push_back(word.map(|word| word as u8));
+                Ok(())
+            }
+            RadixScheme::BitPacked { bits } if !last => {
+                self.push_bits(word.map_or(0, u64::from), word.is_none(), bits);
+                Ok(())
+            }
+            RadixScheme::BitPacked { bits } => {
+                let word = match word {
+                    Some(word) if word != 0 => u64::from(word),
+                    Some(_) => return Err(invalid_end()),
+                    None => return Err(unreadable_end()),
+                };
+                // Leave out the end marker, and the 0 bits that follow it.
+                let padding = word.trailing_zeros() + 1;
+                self.push_bits(word >> padding, false, bits - padding);
+                if self.bits_len != 0 {
+                    return Err(invalid_end());
+                }
+                Ok(())
+            }
+            RadixScheme::Blocks {
+                radix,
+                block_len,
+                block_words,
+            } => {
+                self.block.push(word);
+                if !last && self.block.len() < block_words {
+                    return Ok(());
+                }
+                let block = std::mem::take(&mut self.block);
+                let value = block.iter().try_fold(Some(0u128), |value, &digit| {
+                    digit.map(|digit| {
+                        value
+                            .and_then(|value| value.checked_mul(radix))
+                            .and_then(|value| value.checked_add(u128::from(digit)))
+                    })
+                });
+                if !last {
+                    match value {
+                        None => self.decoded.extend([None].repeat(block_len)),
+                        Some(Some(value)) if value < 1 << (8 * block_len) => self.decoded.extend(
+                            value.to_be_bytes()[16 - block_len..]
+                                .iter()
+                                .map(|&b| Some(b)),
+                        ),
+                        Some(_) => {
+                            return Err(std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                "words do not form a valid block",
+                            ))
+                        }
+                    }
+                    return Ok(());
+                }
+                let Some(value) = value else {
+                    return Err(unreadable_end());
+                };
+                // The last block holds as many bytes as it takes for its value to be at least
+                // the number of shorter byte strings, and it is written with as few words as it takes.
+                let value = value.ok_or_else(invalid_end)?;
+                let len = (1..=block_len)
+                    .rev()
+                    .find(|&len| offset(len) <= value)
+                    .ok_or_else(invalid_end)?;
+                if value >= offset(block_len + 1)
+                    || block.len() != digits_for(radix, offset(len + 1) - 1)
+                {
+                    return Err(invalid_end());
+                }
+                let value = value - offset(len);
+                self.decoded
+                    .extend(value.to_be_bytes()[16 - len..].iter().map(|&b| Some(b)));
+                Ok(())
+            }
+        }
+    }
+
+    fn push_bits(&mut self, value: u64, erased: bool, len: u32) {
+        let mask = (1 << len) - 1;
+        self.bits = (self.bits << len) | value;
+        self.erased_bits = (self.erased_bits << len) | if erased { mask } else { 0 };
+        self.bits_len += len;
+        while self.bits_len >= 8 {
+            self.bits_len -= 8;
+            let byte = (self.bits >> self.bits_len) as u8;
+            let erased = (self.erased_bits >> self.bits_len) & 0xFF != 0;
+            self.decoded.push_back((!erased).then_some(byte));
+        }
+        self.bits &= (1 << self.bits_len) - 1;
+        self.erased_bits &= (1 << self.bits_len) - 1;
+    }
+}
+
+impl<I: Iterator> Ignoring for RadixDecode<I> {
+    fn ignoring(mut self, ignored: Ignored) -> Self {
+        let ignored = self.keep_word_chars(ignored);
+        self.iter.set_ignored(ignored);
+        self
+    }
+}
+
+fn invalid_end() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "the last words do not mark a valid end of the data",
+    )
+}
+
+fn unreadable_end() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "the last words, which mark the end of the data, are unreadable",
+    )
+}
+
+impl<I> RadixDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_word(&mut self) -> Option<Result<Option<u32>, std::io::Error>> {
+        if let Some(idx) = self.split_words.pop_front() {
+            return Some(Ok(Some(idx)));
+        }
+        let word = match super::read_word(
+            &mut self.iter,
+            |word, c| word.extend(c.to_lowercase()),
+            // Words that are written together are told apart when no word begins another
+            |word, unreadable| !unreadable && self.prefix_free && self.lookup.contains_key(word),
+        )? {
+            Ok(Some(word)) => word,
+            Ok(None) => return Some(Ok(None)),
+            Err(e) => return Some(Err(e)),
+        };
+        if let Some(&idx) = self.lookup.get(&word) {
+            return Some(Ok(Some(idx)));
+        }
+        match segment(&word, &self.lookup, self.max_word_len) {
+            Ok(words) => {
+                self.split_words = words.into();
+                self.split_words.pop_front().map(|idx| Ok(Some(idx)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<I> DecodeSymbol for RadixDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        while self.decoded.is_empty() && !self.done {
+            let result = match self.next_word() {
+                Some(Ok(word)) => match self.held.replace(word) {
+                    Some(held) => self.push_word(held, false),
+                    None => Ok(()),
+                },
+                Some(Err(e)) => Err(e),
+                None => {
+                    self.done = true;
+                    match self.held.take() {
+                        Some(held) => self.push_word(held, true),
+                        // No words at all is no data when converting blocks,
+                        // while packed bits always end with the end marker.
+                        None if matches!(self.scheme, RadixScheme::BitPacked { .. }) => {
+                            Err(invalid_end())
+                        }
+                        None => Ok(()),
+                    }
+                }
+            };
+            if let Err(e) = result {
+                return Some(Err(e));
+            }
+        }
+        self.decoded.pop_front().map(Ok)
+    }
+}
+
+impl<I> Iterator for RadixDecode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_symbol()? {
+            Ok(Some(byte)) => Some(Ok(byte)),
+            Ok(None) => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "word marked as unreadable",
+            ))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_decode {
+    use super::super::DecodeSymbol;
+    use super::RadixDecode;
+    use test_case::test_case;
+
+    const DIGITS: &[&str] = &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    const BITS: &[&str] = &["o", "i"];
+
+    fn decode(text: &str, words: &[&str]) -> Result<Vec<u8>, std::io::Error> {
+        RadixDecode::new(text.chars().map(Ok), words)?.collect()
+    }
+
+    #[test_case("i", &[] ; "no data")]
+    #[test_case("i o i o o i o i i", &[0xA5] ; "one byte")]
+    #[test_case("o o o o o o o o\ni i i i i i i i i", &[0x00, 0xFF] ; "two bytes")]
+    fn test_positive_radix_decoder_bit_packed(text: &str, expected: &[u8]) {
+        assert_eq!(decode(text, BITS).unwrap(), expected);
+    }
+
+    #[test_case("", &[] ; "no data")]
+    #[test_case("0 0 1", &[0x00] ; "one byte")]
+    #[test_case("2 5 6", &[0xFF] ; "one byte max")]
+    #[test_case("0 0 2 5 7", &[0x00, 0x00] ; "two bytes")]
+    fn test_positive_radix_decoder_blocks(text: &str, expected: &[u8]) {
+        assert_eq!(decode(text, DIGITS).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_positive_radix_decoder_hyphenated_words() {
+        let words = ["yo-yo", "zone", "zoom"];
+        let text = "yo-yo yo-yo yo-yo yo-yo ZONE zoom";
+        assert_eq!(decode(text, &words).unwrap(), &[0x04]);
+    }
+
+    #[test_case("yo-yo yo-yo zo\u{200B}ne yo-yo yo yo yo yo zoom" ; "zero width space")]
+    #[test_case("yo-yo yo-yo zo\u{00AD}ne yo-yo yo yo yo yo zoom" ; "soft hyphen")]
+    #[cfg_attr(feature = "unicode", test_case("yo-yo yo-yo zo\u{0301}ne yo-yo yo yo yo yo zoom" ; "decomposed accent"))]
+    #[test_case("yo-yoyo-yozoneyo-yoyoyoyoyozoom" ; "written together")]
+    #[test_case("yo-yo yo-yo zone yo-yo yo yo yo yo zoom" ; "separated")]
+    fn test_positive_radix_decoder_words_beginning_others(text: &str) {
+        let words = ["yo-yo", "zone", "zoom", "yo"];
+        assert_eq!(decode(text, &words).unwrap(), &[0x04, 0xFF]);
+    }
+
+    #[test]
+    fn test_negative_radix_decoder_ambiguous_words() {
+        let words = ["ab", "abc", "cd", "d"];
+        let error = decode("ab abcd", &words).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "\"abcd\" can be read as words in more than one way, like \"ab cd\" or \"abc d\"; separate the words to tell them apart"
+        );
+    }
+
+    #[test]
+    fn test_positive_radix_decoder_unreadable_word() {
+        // With one bit per word, an unreadable word makes one byte unreadable.
+        let text = "? o i o i o i i  i i o o i i o i  i";
+        let mut decoder = RadixDecode::new(text.chars().map(Ok), BITS).unwrap();
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(symbols, &[None, Some(0xCD)]);
+    }
+
+    #[test_case("o", BITS, "the last words do not mark a valid end of the data" ; "no end marker")]
+    #[test_case("i o", BITS, "the last words do not mark a valid end of the data" ; "partial byte")]
+    #[test_case("", BITS, "the last words do not mark a valid end of the data" ; "no words")]
+    #[test_case("i ?", BITS, "the last words, which mark the end of the data, are unreadable" ; "unreadable end")]
+    #[test_case("0 0 0", DIGITS, "the last words do not mark a valid end of the data" ; "no bytes in last block")]
+    #[test_case("0 0 0 1", DIGITS, "the last words do not mark a valid end of the data" ; "too many words")]
+    #[test_case("0 0 x", DIGITS, "unknown word \"x\"" ; "unknown word")]
+    fn test_negative_radix_decoder(text: &str, words: &[&str], expected: &str) {
+        let error = decode(text, words).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test]
+    fn test_negative_radix_decoder_duplicate_word() {
+        let error = RadixDecode::new("".chars().map(Ok::<_, std::io::Error>), &["a", "b", "A"])
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,253 @@
+/*
+ * Copyright (c) 2018, 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use super::{DecodeSymbol, Ignored, IgnoredFilter, Ignoring};
+use crate::checksum::rfc1751_parity;
+use std::collections::VecDeque;
+
+/// Number of bytes in each block.
+const BLOCK_LEN: usize = 8;
+/// Number of words for each block.
+const BLOCK_WORDS: usize = 6;
+
+/// Base 256 decoder using the RFC 1751 (S/KEY) dictionary
+///
+/// Words are read six at a time, and the parity bits of each block are checked.
+/// Since some words are prefixes of others, words must be separated, for example by spaces.
+/// Words are matched regardless of case, and the digits `1`, `0` and `5` are read as
+/// the letters `L`, `O` and `S`, like the reference implementation does.
+#[derive(Clone, Debug)]
+pub struct Rfc1751Decode<I: Iterator> {
+    iter: IgnoredFilter<I>,
+    /// Indices of the words read so far in the current block, `None` for unreadable words.
+    words: Vec<Option<u16>>,
+    /// Bytes of the last decoded block not yet returned.
+    decoded: VecDeque<Option<u8>>,
+    blocks: usize,
+}
+
+impl<I: Iterator> Ignoring for Rfc1751Decode<I> {
+    fn ignoring(mut self, ignored: Ignored) -> Self {
+        self.iter.set_ignored(ignored);
+        self
+    }
+}
+
+impl<I: Iterator> Rfc1751Decode<I> {
+    fn decode_block(&mut self) -> Result<(), std::io::Error> {
+        self.blocks += 1;
+        let bits = self.words.iter().fold(0u128, |bits, idx| {
+            (bits << 11) | u128::from(idx.unwrap_or(0))
+        });
+        let block = ((bits >> 2) as u64).to_be_bytes();
+
+        // The parity cannot be checked when some of the words are unreadable,
+        // so the bytes that share bits with an unreadable word are unreadable as well.
+        if self.words.contains(&None) {
+            let erased = |j: usize| {
+                self.words
+                    .iter()
+                    .enumerate()
+                    .any(|(i, idx)| idx.is_none() && 11 * i < 8 * j + 8 && 8 * j < 11 * i + 11)
+            };
+            self.decoded = (0..BLOCK_LEN)
+                .map(|j| (!erased(j)).then_some(block[j]))
+                .collect();
+        } else if (bits & 0b11) as u8 != rfc1751_parity(u64::from_be_bytes(block)) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("RFC 1751 parity check failed in block {}", self.blocks),
+            ));
+        } else {
+            self.decoded = block.into_iter().map(Some).collect();
+        }
+        self.words.clear();
+        Ok(())
+    }
+}
+
+/// Look up a word in the dictionary, which lists the words of one to three letters first.
+fn word_index(word: &str) -> Option<u16> {
+    let wl = super::WL_RFC1751_DECODE;
+    let short_len = wl.partition_point(|w| w.len() <= 3);
+    let (offset, part) = match word.len() {
+        1..=3 => (0, &wl[..short_len]),
+        4 => (short_len, &wl[short_len..]),
+        _ => return None,
+    };
+    part.binary_search(&word)
+        .ok()
+        .map(|idx| (offset + idx) as u16)
+}
+
+impl<I> Rfc1751Decode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_word(&mut self) -> Option<Result<Option<u16>, std::io::Error>> {
+        let word = match super::read_word(
+            &mut self.iter,
+            |word, c| {
+                word.extend(c.to_uppercase().map(|c| match c {
+                    '1' => 'L',
+                    '0' => 'O',
+                    '5' => 'S',
+                    c => c,
+                }))
+            },
+            |_, _| false,
+        )? {
+            Ok(Some(word)) => word,
+            Ok(None) => return Some(Ok(None)),
+            Err(e) => return Some(Err(e)),
+        };
+        match word_index(&word) {
+            Some(idx) => Some(Ok(Some(idx))),
+            None => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unknown word {word:?}"),
+            ))),
+        }
+    }
+}
+
+impl<I> DecodeSymbol for Rfc1751Decode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        while self.decoded.is_empty() {
+            match self.next_word() {
+                Some(Ok(idx)) => {
+                    self.words.push(idx);
+                    if self.words.len() == BLOCK_WORDS {
+                        if let Err(e) = self.decode_block() {
+                            self.words.clear();
+                            return Some(Err(e));
+                        }
+                    }
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None if self.words.is_empty() => return None,
+                None => {
+                    let words = std::mem::take(&mut self.words).len();
+                    return Some(Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!(
+                            "input ended after {words} of the {BLOCK_WORDS} words of an RFC 1751 block"
+                        ),
+                    )));
+                }
+            }
+        }
+        self.decoded.pop_front().map(Ok)
+    }
+}
+
+impl<I> Iterator for Rfc1751Decode<I>
+where
+    I: Iterator<Item = Result<char, std::io::Error>>,
+{
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_symbol()? {
+            Ok(Some(byte)) => Some(Ok(byte)),
+            Ok(None) => Some(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "word marked as unreadable",
+            ))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<char, E>>, E> crate::Decode<I, Rfc1751Decode<I>> for I {
+    fn decode(self) -> Rfc1751Decode<I> {
+        Rfc1751Decode {
+            iter: Ignored::default().filter(self),
+            words: Vec::with_capacity(BLOCK_WORDS),
+            decoded: VecDeque::with_capacity(BLOCK_LEN),
+            blocks: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_decode {
+    use super::super::{Decode, DecodeSymbol};
+    use super::Rfc1751Decode;
+    use std::io::Cursor;
+    use test_case::test_case;
+    use utf8_chars::BufReadCharsExt;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn decode(words: &str) -> Result<Vec<u8>, std::io::Error> {
+        let mut cursor = Cursor::new(words);
+        Decode::<_, Rfc1751Decode<_>>::decode(cursor.chars()).collect()
+    }
+
+    // Test vectors from RFC 1751.
+    #[test_case("TIDE ITCH SLOW REIN RULE MOT", "EB33F77EE73D4053" ; "one block")]
+    #[test_case("RASH BUSH MILK LOOK BAD BRIM AVID GAFF BAIT ROT POD LOVE", "CCAC2AED591056BE4F90FD441C534766" ; "two blocks")]
+    #[test_case("TROD MUTE TAIL WARM CHAR KONG HAAG CITY BORE O TEAL AWL", "EFF81F9BFBC65350920CDD7416DE8009" ; "two blocks with short words")]
+    #[test_case("tide itch slow\nrein rule mot\n", "EB33F77EE73D4053" ; "lowercase wrapped")]
+    #[test_case("TIDE-ITCH-5LOW-REIN-RU1E-M0T", "EB33F77EE73D4053" ; "digits for letters")]
+    #[test_case("TIDE IT\u{200B}CH SLOW REIN RULE MOT", "EB33F77EE73D4053" ; "zero width space")]
+    #[test_case("TIDE ITCH SLOW RE\u{00AD}IN RULE MOT", "EB33F77EE73D4053" ; "soft hyphen")]
+    #[cfg_attr(feature = "unicode", test_case("TIDE ITCH SLO\u{0301}W REIN RULE MOT", "EB33F77EE73D4053" ; "decomposed accent"))]
+    fn test_positive_rfc1751_decoder(words: &str, expected: &str) {
+        assert_eq!(decode(words).unwrap(), hex(expected));
+    }
+
+    #[test]
+    fn test_positive_rfc1751_decoder_unreadable_word() {
+        let mut cursor = Cursor::new("TIDE ? SLOW REIN RULE MOT");
+        let mut decoder = Decode::<_, Rfc1751Decode<_>>::decode(cursor.chars());
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            symbols,
+            &[
+                Some(0xEB),
+                None,
+                None,
+                Some(0x7E),
+                Some(0xE7),
+                Some(0x3D),
+                Some(0x40),
+                Some(0x53)
+            ]
+        );
+    }
+
+    #[test_case("TIDE ITCH SLOW REIN RULE MOW", "RFC 1751 parity check failed in block 1" ; "wrong parity")]
+    #[test_case("TIDE ITCH SLOW REIN RULE", "input ended after 5 of the 6 words of an RFC 1751 block" ; "truncated")]
+    #[test_case("TIDE ITCH SLOW REIN RULE MOTT", "unknown word \"MOTT\"" ; "unknown word")]
+    #[test_case("TIDE ITCH SLOW REIN RULE MOTE", "unknown word \"MOTE\"" ; "longer word")]
+    fn test_negative_rfc1751_decoder(words: &str, expected: &str) {
+        let error = decode(words).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), expected);
+    }
+}
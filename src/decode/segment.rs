@@ -0,0 +1,166 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Splitting of words written together, for wordlists where some words begin others.
+
+use std::collections::HashMap;
+
+/// Most ways of splitting the same text into words that are listed in an ambiguity error.
+const ALTERNATIVES_SHOWN: usize = 3;
+
+/// Split `text`, which holds words written together, into the words of `lookup`.
+///
+/// Every way of splitting the text is considered, rather than taking the first word that
+/// matches, so that `abcd` is read as `ab cd` when `abc` is a word but `d` is not.
+/// Fails with [`std::io::ErrorKind::InvalidData`] if the text cannot be split into words,
+/// or if it can be split in more than one way, listing some of the ways.
+/// Words are at most `max_word_len` characters long.
+pub(crate) fn segment(
+    text: &str,
+    lookup: &HashMap<String, u32>,
+    max_word_len: usize,
+) -> Result<Vec<u32>, std::io::Error> {
+    let bounds: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([text.len()])
+        .collect();
+    let len = bounds.len() - 1;
+    let word = |i: usize, j: usize| lookup.get(&text[bounds[i]..bounds[j]]).copied();
+
+    // The number of ways to split the text from each character on, counting up to two,
+    // which is enough to tell whether there is only one.
+    let mut ways = vec![0u8; len + 1];
+    ways[len] = 1;
+    for i in (0..len).rev() {
+        ways[i] = (i + 1..=len.min(i + max_word_len))
+            .filter(|&j| word(i, j).is_some())
+            .fold(0, |sum, j| sum.saturating_add(ways[j]).min(2));
+    }
+
+    let mut alternatives = vec![];
+    let mut ends = vec![];
+    collect_splits(
+        0,
+        len,
+        &ways,
+        max_word_len,
+        &word,
+        &mut ends,
+        &mut alternatives,
+    );
+    match alternatives.len() {
+        0 => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("unknown word {text:?}"),
+        )),
+        1 => {
+            let ends = alternatives.pop().unwrap();
+            Ok(std::iter::once(0)
+                .chain(ends.iter().copied())
+                .zip(&ends)
+                .map(|(i, &j)| word(i, j).unwrap())
+                .collect())
+        }
+        _ => {
+            let alternatives: Vec<String> = alternatives
+                .iter()
+                .map(|ends| {
+                    let words: Vec<&str> = std::iter::once(0)
+                        .chain(ends.iter().copied())
+                        .zip(ends)
+                        .map(|(i, &j)| &text[bounds[i]..bounds[j]])
+                        .collect();
+                    format!("{:?}", words.join(" "))
+                })
+                .collect();
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "{text:?} can be read as words in more than one way, like {}; separate the words to tell them apart",
+                    alternatives.join(" or ")
+                ),
+            ))
+        }
+    }
+}
+
+/// Collect ways of splitting the text from character `i` on, as the ends of the words,
+/// following only words after which the text can be split.
+fn collect_splits(
+    i: usize,
+    len: usize,
+    ways: &[u8],
+    max_word_len: usize,
+    word: &impl Fn(usize, usize) -> Option<u32>,
+    ends: &mut Vec<usize>,
+    alternatives: &mut Vec<Vec<usize>>,
+) {
+    if i == len {
+        alternatives.push(ends.clone());
+        return;
+    }
+    for j in i + 1..=len.min(i + max_word_len) {
+        if alternatives.len() == ALTERNATIVES_SHOWN {
+            return;
+        }
+        if ways[j] > 0 && word(i, j).is_some() {
+            ends.push(j);
+            collect_splits(j, len, ways, max_word_len, word, ends, alternatives);
+            ends.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_segment {
+    use super::segment;
+    use std::collections::HashMap;
+    use test_case::test_case;
+
+    fn lookup(words: &[&str]) -> HashMap<String, u32> {
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| (word.to_string(), i as u32))
+            .collect()
+    }
+
+    fn max_word_len(words: &[&str]) -> usize {
+        words.iter().map(|w| w.chars().count()).max().unwrap()
+    }
+
+    #[test_case("abcd", &["ab", "abc", "cd"], &[0, 2] ; "first word not the longest")]
+    #[test_case("abcd", &["ab", "abc", "d"], &[1, 2] ; "first word the longest")]
+    #[test_case("aaab", &["a", "aab", "bb"], &[0, 1] ; "backtracking")]
+    #[test_case("ababab", &["ab", "aba", "ba"], &[0, 0, 0] ; "repeated")]
+    #[test_case("gåsøy", &["gå", "gås", "øy"], &[1, 2] ; "non-ASCII")]
+    fn test_positive_segment(text: &str, words: &[&str], expected: &[u32]) {
+        assert_eq!(
+            segment(text, &lookup(words), max_word_len(words)).unwrap(),
+            expected
+        );
+    }
+
+    #[test_case("abcd", &["ab", "abc", "cd", "d"], "\"abcd\" can be read as words in more than one way, like \"ab cd\" or \"abc d\"; separate the words to tell them apart" ; "two ways")]
+    #[test_case("aaaa", &["a", "aa"], "\"aaaa\" can be read as words in more than one way, like \"a a a a\" or \"a a aa\" or \"a aa a\"; separate the words to tell them apart" ; "many ways")]
+    #[test_case("abx", &["ab", "a", "b"], "unknown word \"abx\"" ; "no way")]
+    fn test_negative_segment(text: &str, words: &[&str], expected: &str) {
+        let error = segment(text, &lookup(words), max_word_len(words)).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), expected);
+    }
+}
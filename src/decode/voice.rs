@@ -0,0 +1,378 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Interpretation of transcripts made with speech-to-text.
+
+use super::{WordMatch, WordMatcher, WordlistSubset};
+use crate::normalize::normalize;
+use crate::wordlist::edit_distance;
+use crate::Codec;
+
+/// Words that speech-to-text writes down for hesitations.
+const FILLERS: &[&str] = &[
+    "uh", "um", "umm", "uhm", "er", "erm", "ah", "eh", "hmm", "hm", "mm", "mhm",
+];
+
+/// Digits, as speech-to-text sometimes writes the number words of compound words.
+const DIGITS: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Known misrecognitions of words, with whether the substitution is certain enough
+/// to be made without asking for confirmation.
+///
+/// Words split into several words are not listed, as the parts are joined anyway.
+const MISRECOGNITIONS: &[(&str, &str, bool)] = &[
+    // PGP Word List, two syllable words
+    ("al gol", "algol", true),
+    ("algal", "algol", false),
+    ("amo", "ammo", true),
+    ("as tech", "aztec", true),
+    ("aztek", "aztec", true),
+    ("bell fast", "belfast", true),
+    ("berzerk", "berserk", true),
+    ("bezerk", "berserk", true),
+    ("burr bank", "burbank", true),
+    ("clacks on", "klaxon", true),
+    ("klaxton", "klaxon", true),
+    ("crows foot", "crowfoot", true),
+    ("do pont", "dupont", true),
+    ("dew pont", "dupont", true),
+    ("i glass", "eyeglass", true),
+    ("i tooth", "eyetooth", true),
+    ("glass go", "glasgow", true),
+    ("glass cow", "glasgow", false),
+    ("guy ger", "geiger", true),
+    ("jane us", "janus", false),
+    ("janice", "janus", false),
+    ("key we", "kiwi", true),
+    ("orka", "orca", true),
+    ("pie thon", "python", true),
+    ("scott land", "scotland", true),
+    ("tie coon", "tycoon", true),
+    ("zoo lou", "zulu", true),
+    ("zoo loo", "zulu", true),
+    // PGP Word List, three syllable words
+    ("four ever", "forever", true),
+    ("is tan bull", "istanbul", true),
+    ("med usa", "medusa", true),
+    ("o hi o", "ohio", true),
+    ("oh hi oh", "ohio", true),
+    ("two morrow", "tomorrow", true),
+    ("yuca tan", "yucatan", true),
+    ("you ca tan", "yucatan", true),
+];
+
+/// Most spoken parts that a single word can be split into.
+const MAX_PARTS: usize = 3;
+
+/// A word that was substituted for what speech-to-text heard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VoiceSubstitution {
+    /// Position of the word, counting from zero.
+    pub position: usize,
+    /// What speech-to-text heard.
+    pub heard: String,
+    /// The word it was taken to be.
+    pub word: &'static str,
+    /// Whether the substitution is uncertain, and should be confirmed.
+    pub uncertain: bool,
+}
+
+/// A transcript made with speech-to-text, interpreted as words of a codec.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VoiceTranscript {
+    /// The words, separated by spaces, ready for the decoder of the codec.
+    pub text: String,
+    /// The words that were not heard as they are written.
+    pub substitutions: Vec<VoiceSubstitution>,
+}
+
+/// Interpret a transcript made with speech-to-text as words of the given codec.
+///
+/// Filler words like "uh" and "um" are left out, words split into several parts are joined,
+/// digits are read as number words, and known misrecognitions are replaced by the word
+/// that was meant. What is left is taken to be the closest word, if there is one
+/// that is clearly the closest; such substitutions are marked as uncertain.
+///
+/// Fails with [`std::io::ErrorKind::InvalidData`] if a word cannot be made out.
+pub fn interpret_voice(codec: Codec, text: &str) -> Result<VoiceTranscript, std::io::Error> {
+    let lists = wordlists(codec)?;
+    let tokens = tokenize(text);
+    let mut words = vec![];
+    let mut substitutions = vec![];
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let position = words.len();
+        if tokens[i] == "?" {
+            words.push("?");
+            i += 1;
+            continue;
+        }
+
+        let list = lists[position % lists.len()];
+        let (parts, word, substitution) = match_exact(list, &tokens[i..])
+            .map(|(parts, word)| (parts, word, None))
+            .or_else(|| {
+                match_misrecognition(list, &tokens[i..])
+                    .map(|(parts, word, certain)| (parts, word, Some(!certain)))
+            })
+            .or_else(|| {
+                match_closest(list, &tokens[i..]).map(|(parts, word)| (parts, word, Some(true)))
+            })
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "could not make out word {} from \"{}\"",
+                        position + 1,
+                        tokens[i..tokens.len().min(i + MAX_PARTS)].join(" ")
+                    ),
+                )
+            })?;
+
+        if let Some(uncertain) = substitution {
+            substitutions.push(VoiceSubstitution {
+                position,
+                heard: tokens[i..i + parts].join(" "),
+                word,
+                uncertain,
+            });
+        }
+        words.push(word);
+        i += parts;
+    }
+
+    Ok(VoiceTranscript {
+        text: words.join(" "),
+        substitutions,
+    })
+}
+
+/// The wordlists that the words of a codec are taken from, in turn.
+fn wordlists(codec: Codec) -> Result<Vec<&'static [WordlistSubset<'static>]>, std::io::Error> {
+    match codec {
+        #[cfg(feature = "decode_pgp")]
+        Codec::Pgp => Ok(vec![
+            super::WL_PGP_DECODE_TWO_SYLLABLE,
+            super::WL_PGP_DECODE_THREE_SYLLABLE,
+        ]),
+        #[cfg(feature = "decode_eff")]
+        Codec::Eff => Ok(vec![super::WL_EFF_DECODE]),
+        #[cfg(feature = "decode_bytewords")]
+        Codec::Bytewords => Ok(vec![super::WL_BYTEWORDS_DECODE]),
+        #[cfg(feature = "decode_bytewords")]
+        Codec::BytewordsMinimal => Ok(vec![super::WL_BYTEWORDS_MINIMAL_DECODE]),
+        Codec::Rfc1751 | Codec::EffFull | Codec::Proquint | Codec::Norwegian | Codec::German => {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("voice mode does not support codec {codec}"),
+            ))
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("codec {codec} was not enabled at compile-time"),
+        )),
+    }
+}
+
+/// Split a transcript into lowercase spoken parts, leaving out filler words
+/// and spelling out digits.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut normalized = String::new();
+    for c in text.chars() {
+        normalize(c, &mut normalized);
+    }
+
+    let mut spaced = String::new();
+    let mut prev = ' ';
+    for c in normalized.chars().flat_map(char::to_lowercase) {
+        match c {
+            // Apostrophes, as in "crow's foot", are left out
+            '\'' | '\u{2019}' => continue,
+            '?' => spaced.push_str(" ? "),
+            // Digits are parts of their own, even when written together with letters
+            c if c.is_ascii_digit() => {
+                spaced.push(' ');
+                spaced.push_str(DIGITS[c.to_digit(10).unwrap() as usize]);
+                spaced.push(' ');
+            }
+            c if c.is_alphabetic() => {
+                if prev.is_ascii_digit() {
+                    spaced.push(' ');
+                }
+                spaced.push(c);
+            }
+            _ => spaced.push(' '),
+        }
+        prev = c;
+    }
+
+    spaced
+        .split_whitespace()
+        .filter(|token| !FILLERS.contains(token))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Find a word written as the next few parts joined together,
+/// matching them the same way as the decoders match words written together.
+fn match_exact<S: AsRef<str>>(
+    list: &[WordlistSubset<'static>],
+    tokens: &[S],
+) -> Option<(usize, &'static str)> {
+    let mut matcher = WordMatcher::new(list);
+    for (parts, token) in tokens.iter().take(MAX_PARTS).enumerate() {
+        let mut chars = token.as_ref().chars().peekable();
+        while let Some(c) = chars.next() {
+            match matcher.push(&[c]) {
+                WordMatch::Partial => {}
+                WordMatch::Invalid => return None,
+                // The word has to end where a part does.
+                WordMatch::Word(_) => {
+                    return chars.peek().is_none().then(|| (parts + 1, matcher.word()))
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Find a known misrecognition of a word in the next few parts, preferring the longest.
+fn match_misrecognition(
+    list: &[WordlistSubset<'static>],
+    tokens: &[String],
+) -> Option<(usize, &'static str, bool)> {
+    MISRECOGNITIONS
+        .iter()
+        .filter_map(|&(heard, word, certain)| {
+            let heard: Vec<_> = heard.split(' ').collect();
+            let matches = tokens.len() >= heard.len()
+                && tokens.iter().zip(&heard).all(|(token, part)| token == part);
+            matches
+                .then(|| match_exact(list, &[word]))
+                .flatten()
+                .map(|(_, word)| (heard.len(), word, certain))
+        })
+        .max_by_key(|&(parts, _, _)| parts)
+}
+
+/// Find the word that is clearly closest to the next few parts joined together,
+/// allowing for about one wrong letter in every four.
+fn match_closest(
+    list: &[WordlistSubset<'static>],
+    tokens: &[String],
+) -> Option<(usize, &'static str)> {
+    let mut best: Option<(usize, usize, &'static str)> = None;
+    let mut tied = false;
+    for parts in 1..=MAX_PARTS.min(tokens.len()) {
+        let heard = tokens[..parts].concat();
+        for entry in list.iter().flat_map(|subset| subset.words) {
+            let distance = edit_distance(&heard, entry.word);
+            if distance > entry.word.len() / 4 {
+                continue;
+            }
+            match best {
+                Some((best_distance, _, _)) if distance > best_distance => {}
+                Some((best_distance, _, best_word))
+                    if distance == best_distance && best_word != entry.word =>
+                {
+                    tied = true
+                }
+                _ => {
+                    tied = false;
+                    best = Some((distance, parts, entry.word));
+                }
+            }
+        }
+    }
+    match best {
+        Some((_, parts, word)) if !tied => Some((parts, word)),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "decode_pgp")]
+#[cfg(test)]
+mod test_cases_voice {
+    use super::{interpret_voice, VoiceSubstitution};
+    use crate::Codec;
+    use test_case::test_case;
+
+    #[test_case("dupont ohio eightball istanbul" ; "as written")]
+    #[test_case("Du pont, Ohio. Eight ball, Istanbul." ; "split compound words")]
+    #[test_case("uh dupont um ohio, er, eightball hmm istanbul" ; "filler words")]
+    #[test_case("dupont ohio 8 ball istanbul" ; "digits")]
+    #[test_case("dupont ohio 8-ball istanbul" ; "digits with hyphen")]
+    #[test_case("do pont, oh hi oh, eight ball, is tan bull" ; "misrecognitions")]
+    fn test_positive_voice_pgp(text: &str) {
+        let transcript = interpret_voice(Codec::Pgp, text).unwrap();
+        assert_eq!(transcript.text, "dupont ohio eightball istanbul");
+        assert!(transcript.substitutions.iter().all(|s| !s.uncertain));
+    }
+
+    #[test]
+    fn test_positive_voice_pgp_substitutions() {
+        let transcript = interpret_voice(Codec::Pgp, "do pont ? janice ohio chekup").unwrap();
+        assert_eq!(transcript.text, "dupont ? janus ohio checkup");
+        assert_eq!(
+            transcript.substitutions,
+            &[
+                VoiceSubstitution {
+                    position: 0,
+                    heard: "do pont".to_string(),
+                    word: "dupont",
+                    uncertain: false,
+                },
+                VoiceSubstitution {
+                    position: 2,
+                    heard: "janice".to_string(),
+                    word: "janus",
+                    uncertain: true,
+                },
+                VoiceSubstitution {
+                    position: 4,
+                    heard: "chekup".to_string(),
+                    word: "checkup",
+                    uncertain: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_positive_voice_pgp_decodes() {
+        let transcript = interpret_voice(Codec::Pgp, "adult, uh, a mule et; adult").unwrap();
+        let decoded = Codec::Pgp
+            .decoder(transcript.text.chars().map(Ok))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, &[0x05u8; 3]);
+    }
+
+    #[test]
+    fn test_negative_voice_pgp_unknown_word() {
+        let error = interpret_voice(Codec::Pgp, "dupont gobbledygook").unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "could not make out word 2 from \"gobbledygook\""
+        );
+    }
+}
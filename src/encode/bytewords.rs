@@ -0,0 +1,125 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use crate::checksum::Crc32;
+use crate::BytewordsStyle;
+
+/// Base 256 encoder using Bytewords
+///
+/// The words are followed by four words for the CRC-32 checksum of the data,
+/// in big-endian order.
+#[derive(Clone, Debug)]
+pub struct BytewordsEncode<I: Iterator> {
+    iter: I,
+    style: BytewordsStyle,
+    crc: Crc32,
+    checksum: Option<std::array::IntoIter<u8, 4>>,
+}
+
+impl<I: Iterator> BytewordsEncode<I> {
+    /// Write the words in the given style. Only the minimal style changes the words themselves;
+    /// the separator between the words is up to the caller.
+    pub fn with_style(mut self, style: BytewordsStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn word(&self, byte: u8) -> &'static str {
+        match self.style {
+            BytewordsStyle::Standard | BytewordsStyle::Uri => {
+                crate::WL_BYTEWORDS_ENCODE[byte as usize]
+            }
+            BytewordsStyle::Minimal => crate::WL_BYTEWORDS_MINIMAL_ENCODE[byte as usize],
+        }
+    }
+}
+
+impl<I, E> Iterator for BytewordsEncode<I>
+where
+    I: Iterator<Item = Result<u8, E>>,
+{
+    type Item = Result<&'static str, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(checksum) = self.checksum.as_mut() {
+            let byte = checksum.next()?;
+            return Some(Ok(self.word(byte)));
+        }
+        match self.iter.next() {
+            Some(Ok(byte)) => {
+                self.crc.update(byte);
+                Some(Ok(self.word(byte)))
+            }
+            Some(Err(e)) => Some(Err(e)),
+            None => {
+                self.checksum = Some(self.crc.finish().to_be_bytes().into_iter());
+                self.next()
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<u8, E>>, E> crate::Encode<I, BytewordsEncode<I>> for I {
+    fn encode(self) -> BytewordsEncode<I> {
+        BytewordsEncode {
+            iter: self,
+            style: BytewordsStyle::default(),
+            crc: Crc32::new(),
+            checksum: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_encode {
+    use super::super::Encode;
+    use super::BytewordsEncode;
+    use crate::BytewordsStyle;
+    use std::io::{Cursor, Read};
+    use test_case::test_case;
+
+    /// Test vector from the Bytewords specification (BCR-2020-012).
+    const TEST_VECTOR: &str = "d9012ca20150c7098580125e2ab0981253468b2dbc5202d8641947da";
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test_case(BytewordsStyle::Standard, " ", "tuna acid draw oboe acid good slot axis limp lava brag holy door puff monk brag guru frog luau drop roof grim also trip idle chef fuel twin tied draw grim ramp" ; "standard")]
+    #[test_case(BytewordsStyle::Uri, "-", "tuna-acid-draw-oboe-acid-good-slot-axis-limp-lava-brag-holy-door-puff-monk-brag-guru-frog-luau-drop-roof-grim-also-trip-idle-chef-fuel-twin-tied-draw-grim-ramp" ; "uri")]
+    #[test_case(BytewordsStyle::Minimal, "", "taaddwoeadgdstaslplabghydrpfmkbggufgludprfgmaotpiecffltntddwgmrp" ; "minimal")]
+    fn test_positive_bytewords_encoder(style: BytewordsStyle, separator: &str, expected: &str) {
+        let bytes = Cursor::new(hex(TEST_VECTOR)).bytes();
+        let encoded_words = Encode::<_, BytewordsEncode<_>>::encode(bytes)
+            .with_style(style)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(encoded_words.join(separator), expected);
+        assert_eq!(style.separator(), separator);
+    }
+
+    #[test]
+    fn test_positive_bytewords_encoder_no_data() {
+        let bytes = Cursor::new([]).bytes();
+        let encoded_words = Encode::<_, BytewordsEncode<_>>::encode(bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(encoded_words, &["able"; 4]);
+    }
+}
@@ -49,7 +49,7 @@ mod test_cases_encode {
 
     #[test_case(&[0x05u8; 3], &["acuteness"; 3] ; "data 0x05 0x05 0x05")]
     fn test_positive_eff_encoder(bytes: &[u8], expected_words: &[&str]) {
-        let bytes = Cursor::new(bytes).bytes().into_iter();
+        let bytes = Cursor::new(bytes).bytes();
         let encoded_words = Encode::<_, EffEncode<_>>::encode(bytes)
             .collect::<Result<Vec<_>, _>>()
             .unwrap();
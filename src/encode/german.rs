@@ -0,0 +1,59 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+/// Base 256 encoder using the German wordlist
+#[derive(Clone, Debug)]
+pub struct GermanEncode<I: Iterator> {
+    iter: I,
+}
+
+impl<I, E> Iterator for GermanEncode<I>
+where
+    I: Iterator<Item = Result<u8, E>>,
+{
+    type Item = Result<&'static str, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next()? {
+            Ok(byte) => Some(Ok(crate::WL_GERMAN_ENCODE[byte as usize])),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<u8, E>>, E> crate::Encode<I, GermanEncode<I>> for I {
+    fn encode(self) -> GermanEncode<I> {
+        GermanEncode { iter: self }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_encode {
+    use super::super::Encode;
+    use super::GermanEncode;
+    use std::io::{Cursor, Read};
+    use test_case::test_case;
+
+    #[test_case(&[0x00, 0xFF], &["Abend", "Zwiebel"] ; "data 0x00 0xFF")]
+    #[test_case(&[0xD2, 0x1D, 0x41], &["Straße", "Brücke", "Fuß"] ; "data 0xD2 0x1D 0x41")]
+    fn test_positive_german_encoder(bytes: &[u8], expected_words: &[&str]) {
+        let bytes = Cursor::new(bytes).bytes();
+        let encoded_words = Encode::<_, GermanEncode<_>>::encode(bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(encoded_words, expected_words);
+    }
+}
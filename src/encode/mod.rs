@@ -14,20 +14,58 @@
  * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
  */
 
+#[cfg(feature = "encode_bytewords")]
+mod bytewords;
 #[cfg(feature = "encode_eff")]
 mod eff;
+#[cfg(feature = "encode_german")]
+mod german;
+#[cfg(feature = "encode_norwegian")]
+mod norwegian;
 #[cfg(feature = "encode_pgp")]
 mod pgp;
+#[cfg(feature = "encode_proquint")]
+mod proquint;
+#[cfg(feature = "encode")]
+mod radix;
+#[cfg(feature = "encode_rfc1751")]
+mod rfc1751;
+#[cfg(feature = "encode")]
+mod speech;
 
+#[cfg(feature = "encode_bytewords")]
+pub use bytewords::*;
 #[cfg(feature = "encode_eff")]
 pub use eff::*;
+#[cfg(feature = "encode_german")]
+pub use german::*;
+#[cfg(feature = "encode_norwegian")]
+pub use norwegian::*;
 #[cfg(feature = "encode_pgp")]
 pub use pgp::*;
+#[cfg(feature = "encode_proquint")]
+pub use proquint::*;
+#[cfg(feature = "encode")]
+pub use radix::*;
+#[cfg(feature = "encode_rfc1751")]
+pub use rfc1751::*;
+#[cfg(feature = "encode")]
+pub use speech::*;
 
 #[cfg(feature = "wl_eff_encode")]
 include!(concat!(env!("OUT_DIR"), "/wl_eff_encode.rs"));
 #[cfg(feature = "wl_pgp_encode")]
 include!(concat!(env!("OUT_DIR"), "/wl_pgp_encode.rs"));
+#[cfg(feature = "wl_bytewords_encode")]
+include!(concat!(env!("OUT_DIR"), "/wl_bytewords_encode.rs"));
+#[cfg(feature = "wl_rfc1751_encode")]
+include!(concat!(env!("OUT_DIR"), "/wl_rfc1751_encode.rs"));
+#[cfg(feature = "wl_eff_full_encode")]
+include!(concat!(env!("OUT_DIR"), "/wl_eff_full_encode.rs"));
+#[cfg(feature = "wl_norwegian_encode")]
+include!(concat!(env!("OUT_DIR"), "/wl_norwegian_encode.rs"));
+#[cfg(feature = "wl_german_encode")]
+include!(concat!(env!("OUT_DIR"), "/wl_german_encode.rs"));
 
 /// Base 256 encoder trait
 #[cfg(feature = "encode")]
@@ -35,7 +73,11 @@ pub trait Encode<I: Iterator, E> {
     fn encode(self) -> E;
 }
 
-#[cfg(any(feature = "wl_eff_encode", feature = "wl_pgp_encode"))]
+#[cfg(any(
+    feature = "wl_eff_encode",
+    feature = "wl_pgp_encode",
+    feature = "wl_bytewords_encode"
+))]
 #[cfg(test)]
 mod tests_word_lists_sorted_extent {
     use super::*;
@@ -62,6 +104,13 @@ mod tests_word_lists_sorted_extent {
         }));
     }
 
+    #[cfg(feature = "wl_bytewords_encode")]
+    #[test]
+    /// Bytewords (encode) is sorted.
+    fn test_wl_bytewords_encode_is_sorted() {
+        assert!(WL_BYTEWORDS_ENCODE.windows(2).all(|w| w[0] < w[1]));
+    }
+
     #[cfg(feature = "wl_pgp_encode")]
     #[test]
     /// PGP Word List (encode) – PGPfone Two Syllable Word List is sorted
@@ -72,6 +121,75 @@ mod tests_word_lists_sorted_extent {
     }
 }
 
+#[cfg(any(
+    feature = "wl_eff_encode",
+    feature = "wl_pgp_encode",
+    feature = "wl_norwegian_encode",
+    feature = "wl_german_encode"
+))]
+#[cfg(test)]
+mod test_cases_wl_quality {
+    use super::*;
+    use crate::WordlistReport;
+
+    /// The words are all read as different words, and words written together
+    /// can only be read one way, since no word is the beginning of another.
+    fn assert_readable(report: &WordlistReport) {
+        assert!(report.invalid.is_empty(), "{report}");
+        assert!(report.duplicates.is_empty(), "{report}");
+        assert!(report.prefix_collisions.is_empty(), "{report}");
+        assert_eq!(report.ambiguous_segmentation, None, "{report}");
+    }
+
+    #[cfg(feature = "wl_eff_encode")]
+    #[test]
+    /// EFF Short Wordlist 2.0 (encode) has words that are at least three edits apart,
+    /// that are told apart by their first three letters, and few that sound alike.
+    fn test_wl_eff_encode_quality() {
+        let report = WordlistReport::new(WL_EFF_ENCODE);
+        assert_readable(&report);
+        assert!(report.is_usable());
+        assert_eq!(report.min_edit_distance.as_ref().unwrap().0, 3);
+        assert_eq!(report.unique_prefix_len, Some(3));
+        assert!(report.metaphone_collisions.len() <= 2, "{report}");
+    }
+
+    #[cfg(feature = "wl_pgp_encode")]
+    #[test]
+    /// PGP Word List (encode) has words that are at least two edits apart within each list,
+    /// and the decoder can read words of both lists written together.
+    fn test_wl_pgp_encode_quality() {
+        for wl in [WL_PGP_ENCODE_TWO_SYLLABLE, WL_PGP_ENCODE_THREE_SYLLABLE] {
+            let report = WordlistReport::new(wl);
+            assert_readable(&report);
+            assert!(report.is_usable());
+            assert!(report.min_edit_distance.as_ref().unwrap().0 >= 2);
+            assert!(report.metaphone_collisions.len() <= 2, "{report}");
+        }
+        let both: Vec<_> = WL_PGP_ENCODE_TWO_SYLLABLE
+            .iter()
+            .chain(WL_PGP_ENCODE_THREE_SYLLABLE)
+            .collect();
+        assert_readable(&WordlistReport::new(&both));
+    }
+
+    #[cfg(feature = "wl_norwegian_encode")]
+    #[test]
+    fn test_wl_norwegian_encode_quality() {
+        let report = WordlistReport::new(WL_NORWEGIAN_ENCODE);
+        assert_readable(&report);
+        assert!(report.is_usable());
+    }
+
+    #[cfg(feature = "wl_german_encode")]
+    #[test]
+    fn test_wl_german_encode_quality() {
+        let report = WordlistReport::new(WL_GERMAN_ENCODE);
+        assert_readable(&report);
+        assert!(report.is_usable());
+    }
+}
+
 #[cfg(feature = "wl_eff_encode")]
 #[cfg(test)]
 #[test]
@@ -91,3 +209,78 @@ mod test_cases_wl_pgp_encode_contains_256_words {
         assert_eq!(wl.len(), 256);
     }
 }
+
+#[cfg(feature = "wl_bytewords_encode")]
+#[cfg(test)]
+mod test_cases_wl_bytewords_encode {
+    use super::*;
+
+    #[test]
+    fn test_wl_bytewords_encode_contains_256_four_letter_words() {
+        assert_eq!(WL_BYTEWORDS_ENCODE.len(), 256);
+        assert!(WL_BYTEWORDS_ENCODE.iter().all(|w| w.len() == 4));
+    }
+
+    #[test]
+    fn test_wl_bytewords_minimal_encode_is_unique() {
+        let mut words = WL_BYTEWORDS_MINIMAL_ENCODE.to_vec();
+        words.sort();
+        words.dedup();
+        assert_eq!(words.len(), 256);
+    }
+}
+
+#[cfg(feature = "wl_rfc1751_encode")]
+#[cfg(test)]
+mod test_cases_wl_rfc1751_encode {
+    use super::*;
+
+    #[test]
+    fn test_wl_rfc1751_encode_contains_2048_words() {
+        assert_eq!(WL_RFC1751_ENCODE.len(), 2048);
+    }
+
+    #[test]
+    /// RFC 1751 (encode) has the 571 words of one to three letters first,
+    /// followed by the 1477 four-letter words, and each part is sorted.
+    fn test_wl_rfc1751_encode_is_sorted_by_length_then_word() {
+        let (short, long) = WL_RFC1751_ENCODE.split_at(571);
+        assert!(short.iter().all(|w| (1..=3).contains(&w.len())));
+        assert!(long.iter().all(|w| w.len() == 4));
+        assert!(short.windows(2).all(|w| w[0] < w[1]));
+        assert!(long.windows(2).all(|w| w[0] < w[1]));
+    }
+}
+
+#[cfg(feature = "wl_eff_full_encode")]
+#[cfg(test)]
+#[test]
+fn test_wl_eff_full_encode_contains_1296_words() {
+    assert_eq!(WL_EFF_FULL_ENCODE.len(), 1296);
+}
+
+#[cfg(any(feature = "wl_norwegian_encode", feature = "wl_german_encode"))]
+#[cfg(test)]
+mod test_cases_wl_localized_encode {
+    use super::*;
+
+    /// Words that only differ in case would not be told apart when decoding.
+    fn contains_256_unique_words(wl: &[&str]) -> bool {
+        let mut words: Vec<_> = wl.iter().map(|w| w.to_lowercase()).collect();
+        words.sort();
+        words.dedup();
+        words.len() == 256
+    }
+
+    #[cfg(feature = "wl_norwegian_encode")]
+    #[test]
+    fn test_wl_norwegian_encode_contains_256_unique_words() {
+        assert!(contains_256_unique_words(WL_NORWEGIAN_ENCODE));
+    }
+
+    #[cfg(feature = "wl_german_encode")]
+    #[test]
+    fn test_wl_german_encode_contains_256_unique_words() {
+        assert!(contains_256_unique_words(WL_GERMAN_ENCODE));
+    }
+}
@@ -0,0 +1,124 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use std::sync::OnceLock;
+
+/// Consonants of a proquint, each standing for four bits.
+const PROQUINT_CONSONANTS: [char; 16] = [
+    'b', 'd', 'f', 'g', 'h', 'j', 'k', 'l', 'm', 'n', 'p', 'r', 's', 't', 'v', 'z',
+];
+/// Vowels of a proquint, each standing for two bits.
+const PROQUINT_VOWELS: [char; 4] = ['a', 'i', 'o', 'u'];
+/// Number of letters in each proquint.
+const QUINT_LEN: usize = 5;
+
+/// All 65536 proquints, in order, written together.
+///
+/// The proquints are computed rather than stored, and this lets the encoder
+/// hand them out as `&'static str` like the encoders that use wordlists.
+fn all_quints() -> &'static str {
+    static QUINTS: OnceLock<String> = OnceLock::new();
+    QUINTS.get_or_init(|| (0..=u16::MAX).flat_map(quint_chars).collect())
+}
+
+/// The letters of the proquint for `value`, consonant and vowel alternating.
+fn quint_chars(value: u16) -> [char; QUINT_LEN] {
+    let value = usize::from(value);
+    [
+        PROQUINT_CONSONANTS[value >> 12],
+        PROQUINT_VOWELS[(value >> 10) & 0b11],
+        PROQUINT_CONSONANTS[(value >> 6) & 0b1111],
+        PROQUINT_VOWELS[(value >> 4) & 0b11],
+        PROQUINT_CONSONANTS[value & 0b1111],
+    ]
+}
+
+/// Base 256 encoder using proquints (PRO-nouncable QUINT-uplets)
+///
+/// Each pair of bytes is written as a five-letter word of alternating consonants and vowels,
+/// like `lusab` for `7F 00`. The input must be a whole number of such pairs.
+#[derive(Clone, Debug)]
+pub struct ProquintEncode<I: Iterator> {
+    iter: I,
+}
+
+impl<I, E> Iterator for ProquintEncode<I>
+where
+    I: Iterator<Item = Result<u8, E>>,
+    E: From<std::io::Error>,
+{
+    type Item = Result<&'static str, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let high = match self.iter.next()? {
+            Ok(byte) => byte,
+            Err(e) => return Some(Err(e)),
+        };
+        let low = match self.iter.next() {
+            Some(Ok(byte)) => byte,
+            Some(Err(e)) => return Some(Err(e)),
+            None => {
+                return Some(Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "proquints encode pairs of bytes, but the last pair has only one",
+                )
+                .into()))
+            }
+        };
+        let start = usize::from(u16::from_be_bytes([high, low])) * QUINT_LEN;
+        Some(Ok(&all_quints()[start..start + QUINT_LEN]))
+    }
+}
+
+impl<I: Iterator<Item = Result<u8, E>>, E> crate::Encode<I, ProquintEncode<I>> for I {
+    fn encode(self) -> ProquintEncode<I> {
+        ProquintEncode { iter: self }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_encode {
+    use super::super::Encode;
+    use super::ProquintEncode;
+    use std::io::{Cursor, Read};
+    use test_case::test_case;
+
+    fn encode(bytes: &[u8]) -> Result<Vec<&'static str>, std::io::Error> {
+        Encode::<_, ProquintEncode<_>>::encode(Cursor::new(bytes).bytes()).collect()
+    }
+
+    // IPv4 addresses from the proquint specification.
+    #[test_case(&[127, 0, 0, 1], "lusab-babad" ; "127.0.0.1")]
+    #[test_case(&[63, 84, 220, 193], "gutih-tugad" ; "63.84.220.193")]
+    #[test_case(&[140, 98, 193, 141], "mudof-sakat" ; "140.98.193.141")]
+    #[test_case(&[64, 255, 6, 200], "haguz-biram" ; "64.255.6.200")]
+    #[test_case(&[216, 68, 232, 21], "todah-vobij" ; "216.68.232.21")]
+    #[test_case(&[12, 110, 110, 204], "budov-kuras" ; "12.110.110.204")]
+    #[test_case(&[0x00, 0x00, 0xFF, 0xFF], "babab-zuzuz" ; "lowest and highest")]
+    fn test_positive_proquint_encoder(bytes: &[u8], expected: &str) {
+        assert_eq!(encode(bytes).unwrap().join("-"), expected);
+    }
+
+    #[test]
+    fn test_negative_proquint_encoder_odd_length() {
+        let error = encode(&[127, 0, 0]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "proquints encode pairs of bytes, but the last pair has only one"
+        );
+    }
+}
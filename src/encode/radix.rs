@@ -0,0 +1,202 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use crate::radix::{digits_for, offset, RadixScheme};
+use std::collections::VecDeque;
+
+/// Encoder for wordlists of any size, writing more than eight bits per word
+/// for wordlists of more than 256 words, and one word per byte for 256 words.
+///
+/// See [`RadixScheme`] for how bytes are converted to words.
+#[derive(Clone, Debug)]
+pub struct RadixEncode<'w, I: Iterator, W> {
+    iter: I,
+    words: &'w [W],
+    scheme: RadixScheme,
+    /// Indices of the words converted, but not yet returned.
+    pending: VecDeque<usize>,
+    /// Bits read, but not yet written, when packing bits.
+    bits: u64,
+    bits_len: u32,
+    /// Byte read ahead, to know whether a block is the last one.
+    ahead: Option<u8>,
+    done: bool,
+}
+
+impl<'w, I: Iterator, W: AsRef<str>> RadixEncode<'w, I, W> {
+    /// Encode `bytes` with the given words.
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidInput`] if there are fewer than two words.
+    pub fn new(bytes: I, words: &'w [W]) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            iter: bytes,
+            words,
+            scheme: RadixScheme::for_wordlist_len(words.len())?,
+            pending: VecDeque::new(),
+            bits: 0,
+            bits_len: 0,
+            ahead: None,
+            done: false,
+        })
+    }
+
+    /// Queue the words for `value`, written as `count` digits.
+    fn push_digits(&mut self, radix: u128, mut value: u128, count: usize) {
+        let start = self.pending.len();
+        for _ in 0..count {
+            self.pending.insert(start, (value % radix) as usize);
+            value /= radix;
+        }
+    }
+}
+
+impl<'w, I, W, E> RadixEncode<'w, I, W>
+where
+    I: Iterator<Item = Result<u8, E>>,
+    W: AsRef<str>,
+{
+    fn next_byte(&mut self) -> Option<Result<u8, E>> {
+        self.ahead.take().map(Ok).or_else(|| self.iter.next())
+    }
+
+    /// Read the next bytes, and queue the words for them.
+    fn fill(&mut self) -> Result<(), E> {
+        match self.scheme {
+            RadixScheme::Bytes => match self.next_byte().transpose()? {
+                Some(byte) => self.pending.push_back(usize::from(byte)),
+                None => self.done = true,
+            },
+            RadixScheme::BitPacked { bits } => match self.next_byte().transpose()? {
+                Some(byte) => {
+                    self.bits = (self.bits << 8) | u64::from(byte);
+                    self.bits_len += 8;
+                    while self.bits_len >= bits {
+                        self.bits_len -= bits;
+                        let word = (self.bits >> self.bits_len) & ((1 << bits) - 1);
+                        self.pending.push_back(word as usize);
+                    }
+                    self.bits &= (1 << self.bits_len) - 1;
+                }
+                None => {
+                    // The end marker, a 1 bit, followed by 0 bits up to the end of the word.
+                    let marked = (self.bits << 1) | 1;
+                    self.pending
+                        .push_back((marked << (bits - self.bits_len - 1)) as usize);
+                    self.done = true;
+                }
+            },
+            RadixScheme::Blocks {
+                radix,
+                block_len,
+                block_words,
+            } => {
+                let mut block = Vec::with_capacity(block_len);
+                while block.len() < block_len {
+                    match self.next_byte().transpose()? {
+                        Some(byte) => block.push(byte),
+                        None => break,
+                    }
+                }
+                let value = block
+                    .iter()
+                    .fold(0u128, |value, &byte| (value << 8) | u128::from(byte));
+                self.ahead = self.iter.next().transpose()?;
+                if self.ahead.is_some() {
+                    self.push_digits(radix, value, block_words);
+                } else {
+                    if !block.is_empty() {
+                        let value = offset(block.len()) + value;
+                        let count = digits_for(radix, offset(block.len() + 1) - 1);
+                        self.push_digits(radix, value, count);
+                    }
+                    self.done = true;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'w, I, W, E> Iterator for RadixEncode<'w, I, W>
+where
+    I: Iterator<Item = Result<u8, E>>,
+    W: AsRef<str>,
+{
+    type Item = Result<&'w str, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.done {
+            if let Err(e) = self.fill() {
+                return Some(Err(e));
+            }
+        }
+        let idx = self.pending.pop_front()?;
+        Some(Ok(self.words[idx].as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod test_cases_encode {
+    use super::RadixEncode;
+    use std::io::{Cursor, Read};
+    use test_case::test_case;
+
+    const DIGITS: &[&str] = &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    const BITS: &[&str] = &["0", "1"];
+
+    fn encode<W: AsRef<str>>(bytes: &[u8], words: &[W]) -> String {
+        RadixEncode::new(Cursor::new(bytes).bytes(), words)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .concat()
+    }
+
+    #[test_case(&[], "1" ; "no data")]
+    #[test_case(&[0xA5], "101001011" ; "one byte")]
+    #[test_case(&[0x00, 0xFF], "00000000111111111" ; "two bytes")]
+    fn test_positive_radix_encoder_bit_packed(bytes: &[u8], expected: &str) {
+        assert_eq!(encode(bytes, BITS), expected);
+    }
+
+    #[test]
+    fn test_positive_radix_encoder_bit_packed_eleven_bits() {
+        let words: Vec<_> = (0..2048).map(|i| format!("{i:011b} ")).collect();
+        // 16 data bits, then the end marker and five 0 bits to fill the second word.
+        assert_eq!(encode(&[0xAB, 0xCD], &words), "10101011110 01101100000 ");
+    }
+
+    // Decimal digits give blocks of 12 bytes as 29 digits. The last block counts the shorter
+    // byte strings first: 1 for the empty one, and then 256 for those of one byte.
+    #[test_case(&[], "" ; "no data")]
+    #[test_case(&[0x00], "001" ; "one byte")]
+    #[test_case(&[0xFF], "256" ; "one byte max")]
+    #[test_case(&[0x00, 0x00], "00257" ; "two bytes")]
+    fn test_positive_radix_encoder_blocks(bytes: &[u8], expected: &str) {
+        assert_eq!(encode(bytes, DIGITS), expected);
+    }
+
+    #[test]
+    fn test_positive_radix_encoder_blocks_whole_block_then_last() {
+        let whole_block = u128::from_be_bytes(
+            [[0; 4].as_slice(), &[0x01; 12]]
+                .concat()
+                .try_into()
+                .unwrap(),
+        );
+        assert_eq!(encode(&[0x01; 13], DIGITS), format!("{whole_block:029}002"));
+    }
+}
@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) 2018, 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+use crate::checksum::rfc1751_parity;
+
+/// Number of bytes in each block.
+const BLOCK_LEN: usize = 8;
+/// Number of words for each block.
+const BLOCK_WORDS: usize = 6;
+
+/// Base 256 encoder using the RFC 1751 (S/KEY) dictionary
+///
+/// Each block of eight bytes is written as six words of eleven bits each,
+/// which makes room for two parity bits. The input must be a whole number of blocks.
+#[derive(Clone, Debug)]
+pub struct Rfc1751Encode<I: Iterator> {
+    iter: I,
+    /// Remaining words of the block being written.
+    words: Option<std::array::IntoIter<&'static str, BLOCK_WORDS>>,
+}
+
+/// Indices into the dictionary of the six words for a block.
+fn block_indices(block: u64) -> [usize; BLOCK_WORDS] {
+    let bits = (u128::from(block) << 2) | u128::from(rfc1751_parity(block));
+    std::array::from_fn(|i| ((bits >> (11 * (BLOCK_WORDS - 1 - i))) & 0x7ff) as usize)
+}
+
+impl<I, E> Iterator for Rfc1751Encode<I>
+where
+    I: Iterator<Item = Result<u8, E>>,
+    E: From<std::io::Error>,
+{
+    type Item = Result<&'static str, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(word) = self.words.as_mut().and_then(Iterator::next) {
+            return Some(Ok(word));
+        }
+        let mut block = [0u8; BLOCK_LEN];
+        for (i, byte) in block.iter_mut().enumerate() {
+            match self.iter.next() {
+                Some(Ok(b)) => *byte = b,
+                Some(Err(e)) => return Some(Err(e)),
+                None if i == 0 => return None,
+                None => {
+                    return Some(Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!(
+                        "RFC 1751 encodes blocks of {BLOCK_LEN} bytes, but the last block has {i}"
+                    ),
+                    )
+                    .into()))
+                }
+            }
+        }
+        let mut words = block_indices(u64::from_be_bytes(block))
+            .map(|idx| crate::WL_RFC1751_ENCODE[idx])
+            .into_iter();
+        let word = words.next().map(Ok);
+        self.words = Some(words);
+        word
+    }
+}
+
+impl<I: Iterator<Item = Result<u8, E>>, E> crate::Encode<I, Rfc1751Encode<I>> for I {
+    fn encode(self) -> Rfc1751Encode<I> {
+        Rfc1751Encode {
+            iter: self,
+            words: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cases_encode {
+    use super::super::Encode;
+    use super::Rfc1751Encode;
+    use std::io::{Cursor, Read};
+    use test_case::test_case;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn encode(bytes: &[u8]) -> Result<Vec<&'static str>, std::io::Error> {
+        Encode::<_, Rfc1751Encode<_>>::encode(Cursor::new(bytes).bytes()).collect()
+    }
+
+    // Test vectors from RFC 1751.
+    #[test_case("EB33F77EE73D4053", "TIDE ITCH SLOW REIN RULE MOT" ; "one block")]
+    #[test_case("CCAC2AED591056BE4F90FD441C534766", "RASH BUSH MILK LOOK BAD BRIM AVID GAFF BAIT ROT POD LOVE" ; "two blocks")]
+    #[test_case("EFF81F9BFBC65350920CDD7416DE8009", "TROD MUTE TAIL WARM CHAR KONG HAAG CITY BORE O TEAL AWL" ; "two blocks with short words")]
+    fn test_positive_rfc1751_encoder(bytes: &str, expected: &str) {
+        assert_eq!(encode(&hex(bytes)).unwrap().join(" "), expected);
+    }
+
+    #[test]
+    fn test_positive_rfc1751_encoder_no_data() {
+        assert!(encode(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_negative_rfc1751_encoder_partial_block() {
+        let error = encode(&hex("EB33F77EE73D4053EB33")).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "RFC 1751 encodes blocks of 8 bytes, but the last block has 2"
+        );
+    }
+}
@@ -0,0 +1,329 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Scripts for reading encoded words aloud, either to a person or with text-to-speech.
+
+use std::io::Write;
+
+/// Pause between words within a group, in SSML.
+const WORD_BREAK: &str = "500ms";
+/// Pause between groups, in SSML.
+const GROUP_BREAK: &str = "1500ms";
+
+/// The NATO phonetic alphabet, for the letters `a` to `z`.
+const NATO_ALPHABET: &[&str] = &[
+    "alfa", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliett",
+    "kilo", "lima", "mike", "november", "oscar", "papa", "quebec", "romeo", "sierra", "tango",
+    "uniform", "victor", "whiskey", "x-ray", "yankee", "zulu",
+];
+
+const SMALL_NUMBERS: &[&str] = &[
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: &[&str] = &[
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const SCALES: &[(u64, &str)] = &[
+    (1_000_000_000_000_000_000, "quintillion"),
+    (1_000_000_000_000_000, "quadrillion"),
+    (1_000_000_000_000, "trillion"),
+    (1_000_000_000, "billion"),
+    (1_000_000, "million"),
+    (1_000, "thousand"),
+];
+
+/// Kind of script to write.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpeechFormat {
+    /// Plain text for a person to read aloud, one word per line.
+    #[default]
+    Script,
+    /// [SSML](https://www.w3.org/TR/speech-synthesis11/) for text-to-speech.
+    Ssml,
+}
+
+/// Which words to spell out with the NATO phonetic alphabet, after saying them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpellOut {
+    #[default]
+    Never,
+    /// Proper names, like Dupont and Istanbul, which are the rare words
+    /// in the PGP Word List, and the ones that listeners most often misspell.
+    Names,
+    Always,
+}
+
+/// How to read encoded words aloud.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpeechParams {
+    pub format: SpeechFormat,
+    /// Number of words in each group. Each group is announced by its number,
+    /// and followed by a pause.
+    pub group: usize,
+    pub spell_out: SpellOut,
+}
+
+impl Default for SpeechParams {
+    fn default() -> Self {
+        Self {
+            format: SpeechFormat::default(),
+            group: 4,
+            spell_out: SpellOut::default(),
+        }
+    }
+}
+
+/// Writes encoded words as a script for reading them aloud.
+/// The output is complete only once [`SpeechScript::finish`] has been called.
+#[derive(Debug)]
+pub struct SpeechScript<W: Write> {
+    inner: W,
+    params: SpeechParams,
+    words: usize,
+}
+
+impl<W: Write> SpeechScript<W> {
+    pub fn new(inner: W, params: SpeechParams) -> Self {
+        Self {
+            inner,
+            params,
+            words: 0,
+        }
+    }
+
+    pub fn write_word(&mut self, word: &str) -> std::io::Result<()> {
+        let group = self.params.group.max(1);
+        if self.words.is_multiple_of(group) {
+            if self.words == 0 {
+                self.write_start()?;
+            } else {
+                self.write_group_end()?;
+            }
+            let announcement = format!("Group {}", number_words((self.words / group + 1) as u64));
+            match self.params.format {
+                SpeechFormat::Script => writeln!(self.inner, "{announcement}:")?,
+                SpeechFormat::Ssml => {
+                    writeln!(self.inner, "  <p>")?;
+                    writeln!(self.inner, "    <s>{announcement}.</s>")?;
+                }
+            }
+        }
+
+        let spelled = match self.params.spell_out {
+            SpellOut::Always => true,
+            SpellOut::Names => word.starts_with(|c: char| c.is_uppercase()),
+            SpellOut::Never => false,
+        }
+        .then(|| spell_nato(word));
+        match (self.params.format, spelled) {
+            (SpeechFormat::Script, None) => writeln!(self.inner, "  {word}")?,
+            (SpeechFormat::Script, Some(spelled)) => writeln!(self.inner, "  {word}: {spelled}")?,
+            (SpeechFormat::Ssml, spelled) => {
+                writeln!(self.inner, "    <break time=\"{WORD_BREAK}\"/>")?;
+                match spelled {
+                    None => writeln!(self.inner, "    <s>{}</s>", escape_xml(word))?,
+                    Some(spelled) => writeln!(
+                        self.inner,
+                        "    <s>{}, spelled {}</s>",
+                        escape_xml(word),
+                        escape_xml(&spelled)
+                    )?,
+                }
+            }
+        }
+        self.words += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> std::io::Result<()> {
+        if self.words == 0 {
+            self.write_start()?;
+        } else if self.params.format == SpeechFormat::Ssml {
+            writeln!(self.inner, "  </p>")?;
+        }
+        if self.params.format == SpeechFormat::Ssml {
+            writeln!(self.inner, "</speak>")?;
+        }
+        self.inner.flush()
+    }
+
+    fn write_start(&mut self) -> std::io::Result<()> {
+        if self.params.format == SpeechFormat::Ssml {
+            writeln!(self.inner, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+            writeln!(
+                self.inner,
+                "<speak version=\"1.1\" xmlns=\"http://www.w3.org/2001/10/synthesis\" xml:lang=\"en-US\">"
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_group_end(&mut self) -> std::io::Result<()> {
+        match self.params.format {
+            SpeechFormat::Script => writeln!(self.inner),
+            SpeechFormat::Ssml => {
+                writeln!(self.inner, "  </p>")?;
+                writeln!(self.inner, "  <break time=\"{GROUP_BREAK}\"/>")
+            }
+        }
+    }
+}
+
+/// Spell out a word with the NATO phonetic alphabet, for example `golf oscar` for `Go`.
+///
+/// Characters other than the letters `a` to `z` are kept as they are.
+pub fn spell_nato(word: &str) -> String {
+    word.chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ 'a'..='z' => NATO_ALPHABET[(c as u8 - b'a') as usize].to_string(),
+            c => c.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Write out a number in English words, for example `twenty-one` for 21.
+pub fn number_words(n: u64) -> String {
+    if n < 20 {
+        return SMALL_NUMBERS[n as usize].to_string();
+    }
+    if n < 100 {
+        return match n % 10 {
+            0 => TENS[(n / 10) as usize].to_string(),
+            ones => format!(
+                "{}-{}",
+                TENS[(n / 10) as usize],
+                SMALL_NUMBERS[ones as usize]
+            ),
+        };
+    }
+    let (scale, name) = SCALES
+        .iter()
+        .find(|(scale, _)| n >= *scale)
+        .copied()
+        .unwrap_or((100, "hundred"));
+    match n % scale {
+        0 => format!("{} {name}", number_words(n / scale)),
+        rest => format!("{} {name} {}", number_words(n / scale), number_words(rest)),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test_cases_speech {
+    use super::{number_words, spell_nato, SpeechFormat, SpeechParams, SpeechScript, SpellOut};
+    use test_case::test_case;
+
+    const WORDS: &[&str] = &["freedom", "gravity", "Glasgow", "hemisphere", "goggles"];
+
+    fn speak(params: SpeechParams, words: &[&str]) -> String {
+        let mut output = vec![];
+        let mut script = SpeechScript::new(&mut output, params);
+        for word in words {
+            script.write_word(word).unwrap();
+        }
+        script.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test_case(0, "zero")]
+    #[test_case(7, "seven")]
+    #[test_case(21, "twenty-one")]
+    #[test_case(40, "forty")]
+    #[test_case(100, "one hundred")]
+    #[test_case(342, "three hundred forty-two")]
+    #[test_case(1_005, "one thousand five")]
+    #[test_case(2_500_000, "two million five hundred thousand")]
+    fn test_positive_number_words(n: u64, expected: &str) {
+        assert_eq!(number_words(n), expected);
+    }
+
+    #[test]
+    fn test_positive_spell_nato() {
+        assert_eq!(
+            spell_nato("Glasgow"),
+            "golf lima alfa sierra golf oscar whiskey"
+        );
+    }
+
+    #[test]
+    fn test_positive_speech_script() {
+        let params = SpeechParams {
+            group: 2,
+            spell_out: SpellOut::Names,
+            ..Default::default()
+        };
+        assert_eq!(
+            speak(params, WORDS),
+            "Group one:\n  freedom\n  gravity\n\n\
+             Group two:\n  Glasgow: golf lima alfa sierra golf oscar whiskey\n  hemisphere\n\n\
+             Group three:\n  goggles\n"
+        );
+    }
+
+    #[test]
+    fn test_positive_speech_ssml() {
+        let params = SpeechParams {
+            format: SpeechFormat::Ssml,
+            group: 4,
+            spell_out: SpellOut::Never,
+        };
+        let ssml = speak(params, WORDS);
+        assert!(ssml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<speak "));
+        assert!(ssml.ends_with("    <s>goggles</s>\n  </p>\n</speak>\n"));
+        assert!(ssml.contains("    <s>Group two.</s>\n"));
+        assert_eq!(ssml.matches("<break time=\"1500ms\"/>").count(), 1);
+        assert_eq!(ssml.matches("<break time=\"500ms\"/>").count(), 5);
+    }
+
+    #[test_case(SpeechFormat::Script, "" ; "script")]
+    #[test_case(SpeechFormat::Ssml, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <speak version=\"1.1\" xmlns=\"http://www.w3.org/2001/10/synthesis\" xml:lang=\"en-US\">\n\
+        </speak>\n" ; "ssml")]
+    fn test_positive_speech_no_words(format: SpeechFormat, expected: &str) {
+        let params = SpeechParams {
+            format,
+            ..Default::default()
+        };
+        assert_eq!(speak(params, &[]), expected);
+    }
+}
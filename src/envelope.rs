@@ -0,0 +1,797 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Self-describing envelope format.
+//!
+//! An envelope starts with a short header, encoded as words using the same codec
+//! as the rest of the data. The header consists of the following bytes:
+//!
+//! | Bytes   | Content                                                              |
+//! |---------|----------------------------------------------------------------------|
+//! | 1       | Magic and format version, `0xA1` for version 1                       |
+//! | 1       | Codec identifier, see [`Codec::id`]                                  |
+//! | 1       | Flags                                                                |
+//! | 4       | Payload length in bytes, big-endian                                  |
+//! | 1 + *n* | Length and ASCII bytes of the content type, if its flag is set       |
+//! | 2       | FEC data and parity lengths, if its flag is set                      |
+//! | 4       | Part number, part count and big-endian set identifier, if its flag is set |
+//! | 1       | Header check, the lowest byte of the CRC-32 of the preceding bytes   |
+//!
+//! The header is followed by the payload, and then by the big-endian CRC-32
+//! of the payload if the checksum flag is set. With forward error correction,
+//! the payload and its checksum are protected as described in [`FecParams`].
+//! The header itself is not protected by forward error correction.
+//!
+//! For codecs that encode blocks of several bytes, like RFC 1751, the envelope
+//! is padded with zero bytes to a whole number of blocks.
+//!
+//! A large payload can be [`split`] into several parts, each in its own envelope,
+//! which are put back together by [`join`].
+//!
+//! Since the magic byte encodes to a different first word in each codec,
+//! the codec of an envelope can be detected from its first word.
+
+use crate::{Codec, FecCorrection, FecParams};
+
+/// Format version written by this crate.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+const MAGIC: u8 = 0xA0;
+const MAGIC_MASK: u8 = 0xF0;
+
+const FLAG_CHECKSUM: u8 = 0x01;
+const FLAG_COMPRESSED: u8 = 0x02;
+const FLAG_ENCRYPTED: u8 = 0x04;
+const FLAG_CONTENT_TYPE: u8 = 0x08;
+const FLAG_FEC: u8 = 0x10;
+const FLAG_PART: u8 = 0x20;
+const FLAGS_KNOWN: u8 =
+    FLAG_CHECKSUM | FLAG_COMPRESSED | FLAG_ENCRYPTED | FLAG_CONTENT_TYPE | FLAG_FEC | FLAG_PART;
+
+/// Envelope header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// Codec used for the words of the envelope.
+    pub codec: Codec,
+    /// Length of the payload in bytes.
+    pub payload_len: u32,
+    /// Whether the payload is followed by its CRC-32.
+    pub checksum: bool,
+    /// Whether the payload was compressed before encoding. For information only.
+    pub compressed: bool,
+    /// Whether the payload was encrypted before encoding. For information only.
+    pub encrypted: bool,
+    /// Type of content, for example `openssh-private-key`. At most 255 ASCII characters.
+    pub content_type: Option<String>,
+    /// Forward error correction applied to the payload and its checksum.
+    pub fec: Option<FecParams>,
+    /// Position of this envelope among the parts of a larger payload.
+    pub part: Option<Part>,
+}
+
+/// Position of an envelope among the parts of a larger payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Part {
+    /// Number of this part, counting from 1.
+    pub number: u8,
+    /// Total number of parts.
+    pub count: u8,
+    /// Identifies the parts of the same payload.
+    /// It is the lowest 16 bits of the CRC-32 of the whole payload.
+    pub set_id: u16,
+}
+
+impl Header {
+    /// Header for a payload of the given length, with a checksum and no other options.
+    pub fn new(codec: Codec, payload_len: u32) -> Self {
+        Self {
+            codec,
+            payload_len,
+            checksum: true,
+            compressed: false,
+            encrypted: false,
+            content_type: None,
+            fec: None,
+            part: None,
+        }
+    }
+
+    /// The bytes of the header.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, std::io::Error> {
+        let mut flags = 0;
+        for (is_set, flag) in [
+            (self.checksum, FLAG_CHECKSUM),
+            (self.compressed, FLAG_COMPRESSED),
+            (self.encrypted, FLAG_ENCRYPTED),
+            (self.content_type.is_some(), FLAG_CONTENT_TYPE),
+            (self.fec.is_some(), FLAG_FEC),
+            (self.part.is_some(), FLAG_PART),
+        ] {
+            if is_set {
+                flags |= flag;
+            }
+        }
+
+        let mut bytes = vec![MAGIC | ENVELOPE_VERSION, self.codec.id(), flags];
+        bytes.extend(self.payload_len.to_be_bytes());
+        if let Some(content_type) = &self.content_type {
+            if content_type.len() > 255 || !content_type.bytes().all(|b| b.is_ascii_graphic()) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "content type must be at most 255 printable ASCII characters",
+                ));
+            }
+            bytes.push(content_type.len() as u8);
+            bytes.extend(content_type.bytes());
+        }
+        if let Some(fec) = self.fec {
+            bytes.extend([fec.data_len() as u8, fec.parity_len() as u8]);
+        }
+        if let Some(part) = self.part {
+            bytes.extend([part.number, part.count]);
+            bytes.extend(part.set_id.to_be_bytes());
+        }
+        bytes.push(crc32_low_byte(&bytes));
+        Ok(bytes)
+    }
+
+    /// Number of words of an envelope with this header, including the header itself.
+    pub fn word_count(&self) -> Result<usize, std::io::Error> {
+        let sealed_len = sealed_len(self)?;
+        Ok(self
+            .codec
+            .word_count(sealed_len.next_multiple_of(self.codec.block_len())))
+    }
+
+    /// Read a header from the beginning of a stream of bytes.
+    pub fn read<I>(bytes: &mut I) -> Result<Self, std::io::Error>
+    where
+        I: Iterator<Item = Result<u8, std::io::Error>>,
+    {
+        let mut header_bytes = vec![];
+        let mut next = |n: usize| -> Result<Vec<u8>, std::io::Error> {
+            let mut read = Vec::with_capacity(n);
+            for _ in 0..n {
+                match bytes.next() {
+                    Some(byte) => read.push(byte?),
+                    None => return Err(invalid_header("envelope header is truncated")),
+                }
+            }
+            header_bytes.extend(&read);
+            Ok(read)
+        };
+
+        let fixed = next(7)?;
+        if fixed[0] & MAGIC_MASK != MAGIC {
+            return Err(invalid_header(
+                "data does not start with an envelope header",
+            ));
+        }
+        if fixed[0] & !MAGIC_MASK != ENVELOPE_VERSION {
+            return Err(invalid_header(&format!(
+                "unsupported envelope version {}",
+                fixed[0] & !MAGIC_MASK
+            )));
+        }
+        let codec = Codec::from_id(fixed[1])
+            .ok_or_else(|| invalid_header(&format!("unknown codec identifier {}", fixed[1])))?;
+        let flags = fixed[2];
+        if flags & !FLAGS_KNOWN != 0 {
+            return Err(invalid_header(&format!("unknown flags {flags:#04x}")));
+        }
+        let payload_len = u32::from_be_bytes([fixed[3], fixed[4], fixed[5], fixed[6]]);
+
+        let content_type = if flags & FLAG_CONTENT_TYPE != 0 {
+            let len = next(1)?[0] as usize;
+            let content_type = next(len)?;
+            if !content_type.iter().all(u8::is_ascii_graphic) {
+                return Err(invalid_header("content type is not printable ASCII"));
+            }
+            Some(String::from_utf8(content_type).unwrap())
+        } else {
+            None
+        };
+        let fec = if flags & FLAG_FEC != 0 {
+            let params = next(2)?;
+            Some(
+                FecParams::new(params[0] as usize, params[1] as usize)
+                    .map_err(|_| invalid_header("invalid FEC parameters"))?,
+            )
+        } else {
+            None
+        };
+
+        let part = if flags & FLAG_PART != 0 {
+            let part = next(4)?;
+            if part[0] == 0 || part[0] > part[1] {
+                return Err(invalid_header(&format!(
+                    "invalid part number {} of {}",
+                    part[0], part[1]
+                )));
+            }
+            Some(Part {
+                number: part[0],
+                count: part[1],
+                set_id: u16::from_be_bytes([part[2], part[3]]),
+            })
+        } else {
+            None
+        };
+
+        let check = next(1)?[0];
+        if crc32_low_byte(&header_bytes[..header_bytes.len() - 1]) != check {
+            return Err(invalid_header("envelope header check failed"));
+        }
+
+        Ok(Self {
+            codec,
+            payload_len,
+            checksum: flags & FLAG_CHECKSUM != 0,
+            compressed: flags & FLAG_COMPRESSED != 0,
+            encrypted: flags & FLAG_ENCRYPTED != 0,
+            content_type,
+            fec,
+            part,
+        })
+    }
+}
+
+fn crc32_low_byte(bytes: &[u8]) -> u8 {
+    crate::checksum::crc32(bytes) as u8
+}
+
+fn invalid_header(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Put a payload in an envelope, returning the bytes to be encoded with `header.codec`.
+pub fn seal(header: &Header, payload: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    if header.payload_len as usize != payload.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "payload length does not match the envelope header",
+        ));
+    }
+    let mut body = payload.to_vec();
+    if header.checksum {
+        body.extend(crate::checksum::crc32(payload).to_be_bytes());
+    }
+
+    let mut bytes = header.to_bytes()?;
+    match header.fec {
+        Some(fec) => bytes.extend(
+            crate::FecEncode::new(body.into_iter().map(Ok::<_, std::io::Error>), fec)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        None => bytes.extend(body),
+    }
+    bytes.resize(bytes.len().next_multiple_of(header.codec.block_len()), 0);
+    Ok(bytes)
+}
+
+/// Position in the payload of each byte that [`seal`] returns for `header`,
+/// or `None` for the bytes of the header, the checksum and forward error correction.
+/// The padding at the end is left out.
+pub fn payload_positions(header: &Header) -> Result<Vec<Option<usize>>, std::io::Error> {
+    let payload_len = header.payload_len as usize;
+    let body_len = payload_len + if header.checksum { 4 } else { 0 };
+    let body = match header.fec {
+        Some(fec) => fec.payload_positions(body_len),
+        None => (0..body_len).map(Some).collect(),
+    };
+    let mut positions = vec![None; header.to_bytes()?.len()];
+    positions.extend(
+        body.into_iter()
+            .map(|position| position.filter(|&position| position < payload_len)),
+    );
+    Ok(positions)
+}
+
+/// Number of bytes in an envelope with the given header, not counting padding.
+fn sealed_len(header: &Header) -> Result<usize, std::io::Error> {
+    let body_len = header.payload_len as usize + if header.checksum { 4 } else { 0 };
+    let parity_len = match header.fec {
+        Some(fec) => body_len.div_ceil(fec.data_len()) * fec.parity_len(),
+        None => 0,
+    };
+    Ok(header.to_bytes()?.len() + body_len + parity_len)
+}
+
+/// Split a payload into `count` parts of nearly equal size, each in its own envelope.
+///
+/// The parts get the options of the given header, and are returned as the bytes
+/// to be encoded with `header.codec`.
+pub fn split(
+    header: &Header,
+    payload: &[u8],
+    count: usize,
+) -> Result<Vec<Vec<u8>>, std::io::Error> {
+    if count == 0 || count > 255 || count > payload.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "cannot split {} bytes into {count} parts; between 1 and 255 parts are supported",
+                payload.len()
+            ),
+        ));
+    }
+    let set_id = crate::checksum::crc32(payload) as u16;
+    let (chunk_len, longer_chunks) = (payload.len() / count, payload.len() % count);
+
+    let mut parts = Vec::with_capacity(count);
+    let mut rest = payload;
+    for i in 0..count {
+        let (chunk, remaining) = rest.split_at(chunk_len + usize::from(i < longer_chunks));
+        rest = remaining;
+        let mut part_header = header.clone();
+        part_header.payload_len = chunk.len() as u32;
+        part_header.part = Some(Part {
+            number: i as u8 + 1,
+            count: count as u8,
+            set_id,
+        });
+        parts.push(seal(&part_header, chunk)?);
+    }
+    Ok(parts)
+}
+
+/// Smallest number of parts for which no part, as split by [`split`], exceeds `max_words` words.
+pub fn part_count_for_words(
+    header: &Header,
+    payload_len: usize,
+    max_words: usize,
+) -> Result<usize, std::io::Error> {
+    for count in 1..=payload_len.min(255) {
+        let mut part_header = header.clone();
+        part_header.payload_len = payload_len.div_ceil(count) as u32;
+        part_header.part = Some(Part {
+            number: 1,
+            count: count as u8,
+            set_id: 0,
+        });
+        if header.codec.word_count(sealed_len(&part_header)?) <= max_words {
+            return Ok(count);
+        }
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("cannot split {payload_len} bytes into parts of at most {max_words} words"),
+    ))
+}
+
+/// Put the payload split by [`split`] back together from its parts, given in any order.
+pub fn join(mut parts: Vec<Opened>) -> Result<Vec<u8>, std::io::Error> {
+    let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+    let Some(first) = parts.first() else {
+        return Err(invalid("no parts given".to_string()));
+    };
+    let Some(Part { count, set_id, .. }) = first.header.part else {
+        return Err(invalid(
+            "envelope is not a part of a split payload".to_string(),
+        ));
+    };
+    for opened in &parts {
+        match opened.header.part {
+            Some(part) if part.count == count && part.set_id == set_id => {}
+            Some(part) => {
+                return Err(invalid(format!(
+                    "part {} of {} (set {:04x}) does not belong with the parts of set {set_id:04x}",
+                    part.number, part.count, part.set_id
+                )))
+            }
+            None => {
+                return Err(invalid(
+                    "envelope is not a part of a split payload".to_string(),
+                ))
+            }
+        }
+    }
+
+    parts.sort_by_key(|opened| opened.header.part.map(|part| part.number));
+    let numbers: Vec<_> = parts
+        .iter()
+        .filter_map(|opened| opened.header.part.map(|part| part.number))
+        .collect();
+    let duplicates: Vec<_> = numbers
+        .windows(2)
+        .filter(|w| w[0] == w[1])
+        .map(|w| w[0].to_string())
+        .collect();
+    if !duplicates.is_empty() {
+        return Err(invalid(format!(
+            "duplicate parts: {} (of {count})",
+            duplicates.join(", ")
+        )));
+    }
+    let missing: Vec<_> = (1..=count)
+        .filter(|n| !numbers.contains(n))
+        .map(|n| n.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(invalid(format!(
+            "missing parts: {} (of {count})",
+            missing.join(", ")
+        )));
+    }
+
+    let payload: Vec<u8> = parts
+        .into_iter()
+        .flat_map(|opened| opened.payload)
+        .collect();
+    if crate::checksum::crc32(&payload) as u16 != set_id {
+        return Err(invalid(
+            "reassembled payload does not match the set identifier of its parts".to_string(),
+        ));
+    }
+    Ok(payload)
+}
+
+/// Contents of an opened envelope.
+#[derive(Clone, Debug)]
+pub struct Opened {
+    pub header: Header,
+    pub payload: Vec<u8>,
+    /// Words corrected using forward error correction, counting positions from the first header word.
+    pub corrections: Vec<FecCorrection>,
+}
+
+/// Read an envelope from a decoder, verifying the length and checksum of the payload.
+#[cfg(feature = "decode")]
+pub fn open<D: crate::DecodeSymbol>(mut decoder: D) -> Result<Opened, std::io::Error> {
+    let header = Header::read(&mut decoder)?;
+    let header_len = header.to_bytes()?.len();
+    let body_len = header.payload_len as usize + if header.checksum { 4 } else { 0 };
+    let sealed_len = sealed_len(&header)?;
+    let padding_len = sealed_len.next_multiple_of(header.codec.block_len()) - sealed_len;
+    let mut body_symbols = TakeSymbols {
+        inner: &mut decoder,
+        remaining: sealed_len - header_len,
+    };
+
+    let (mut payload, corrections) = match header.fec {
+        Some(fec) => {
+            let mut fec_decoder = crate::FecDecode::new(&mut body_symbols, fec);
+            let body = fec_decoder.by_ref().collect::<Result<Vec<_>, _>>()?;
+            let corrections = fec_decoder
+                .corrections()
+                .iter()
+                .map(|c| FecCorrection {
+                    position: header_len + c.position,
+                    ..*c
+                })
+                .collect();
+            (body, corrections)
+        }
+        None => (body_symbols.collect::<Result<Vec<_>, _>>()?, vec![]),
+    };
+
+    if payload.len() != body_len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "envelope should contain {body_len} bytes after the header, but contains {}",
+                payload.len()
+            ),
+        ));
+    }
+    let trailing_len = std::iter::from_fn(|| decoder.next_symbol())
+        .collect::<Result<Vec<_>, _>>()?
+        .len();
+    if trailing_len > padding_len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "envelope is followed by {} bytes that are not part of it",
+                trailing_len - padding_len
+            ),
+        ));
+    }
+    if header.checksum {
+        let checksum = payload.split_off(header.payload_len as usize);
+        if crate::checksum::crc32(&payload).to_be_bytes() != *checksum {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "payload checksum mismatch",
+            ));
+        }
+    }
+
+    Ok(Opened {
+        header,
+        payload,
+        corrections,
+    })
+}
+
+/// Reads at most `remaining` symbols, leaving the rest, like padding, to be read separately.
+#[cfg(feature = "decode")]
+struct TakeSymbols<D> {
+    inner: D,
+    remaining: usize,
+}
+
+#[cfg(feature = "decode")]
+impl<D: crate::DecodeSymbol> Iterator for TakeSymbols<D> {
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        self.inner.next()
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<D: crate::DecodeSymbol> crate::DecodeSymbol for TakeSymbols<D> {
+    fn next_symbol(&mut self) -> Option<Result<Option<u8>, std::io::Error>> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        self.inner.next_symbol()
+    }
+
+    fn correct_symbols(&mut self, bytes: &[u8]) -> Result<(), std::io::Error> {
+        self.inner.correct_symbols(bytes)
+    }
+}
+
+/// Detect the codec of an envelope from its first words.
+///
+/// The words are read with each codec in turn, and the first codec that reads them as a header,
+/// with a valid check byte, that names that codec is the one detected.
+#[cfg(feature = "decode")]
+pub fn detect_codec(text: &str) -> Option<Codec> {
+    Codec::ALL.iter().copied().find(|&codec| {
+        let Ok(mut decoder) = codec.decoder(text.chars().map(Ok)) else {
+            return false;
+        };
+        Header::read(&mut decoder).is_ok_and(|header| header.codec == codec)
+    })
+}
+
+#[cfg(all(feature = "encode", feature = "decode"))]
+#[cfg(test)]
+mod test_cases_envelope {
+    use super::{
+        detect_codec, join, open, part_count_for_words, payload_positions, seal, split, Header,
+    };
+    use crate::{Codec, FecParams};
+    use test_case::test_case;
+
+    fn encode(header: &Header, payload: &[u8]) -> String {
+        let bytes = seal(header, payload).unwrap();
+        header
+            .codec
+            .encoder(bytes.into_iter().map(Ok::<_, std::io::Error>))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .join(" ")
+    }
+
+    /// Encode sealed bytes as words and open them again.
+    fn reopen(sealed: &[u8]) -> super::Opened {
+        let words = Codec::Pgp
+            .encoder(sealed.iter().copied().map(Ok::<_, std::io::Error>))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .join(" ");
+        decode(&words).unwrap()
+    }
+
+    fn decode(words: &str) -> Result<super::Opened, std::io::Error> {
+        let codec = detect_codec(words).expect("codec should be detected");
+        open(codec.decoder(words.chars().map(Ok))?)
+    }
+
+    #[cfg_attr(
+        all(feature = "encode_pgp", feature = "decode_pgp"),
+        test_case(Codec::Pgp ; "pgp")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_eff", feature = "decode_eff"),
+        test_case(Codec::Eff ; "eff")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_bytewords", feature = "decode_bytewords"),
+        test_case(Codec::Bytewords ; "bytewords")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_bytewords", feature = "decode_bytewords"),
+        test_case(Codec::BytewordsMinimal ; "bytewords minimal")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_rfc1751", feature = "decode_rfc1751"),
+        test_case(Codec::Rfc1751 ; "rfc1751")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_eff_full", feature = "decode_eff_full"),
+        test_case(Codec::EffFull ; "eff full")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_proquint", feature = "decode_proquint"),
+        test_case(Codec::Proquint ; "proquint")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_norwegian", feature = "decode_norwegian"),
+        test_case(Codec::Norwegian ; "norwegian")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_german", feature = "decode_german"),
+        test_case(Codec::German ; "german")
+    )]
+    fn test_positive_envelope_roundtrip(codec: Codec) {
+        let payload = std::fs::read("sample_data/original/id_ed25519").unwrap();
+        let mut header = Header::new(codec, payload.len() as u32);
+        header.compressed = true;
+        header.content_type = Some("openssh-private-key".to_string());
+        header.fec = Some(FecParams::new(32, 4).unwrap());
+
+        let words = encode(&header, &payload);
+        assert_eq!(words.split(' ').count(), header.word_count().unwrap());
+        let opened = decode(&words).unwrap();
+        assert_eq!(opened.header, header);
+        assert_eq!(opened.payload, payload);
+        assert!(opened.corrections.is_empty());
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test]
+    fn test_negative_envelope_trailing_data() {
+        let payload = [0x05u8; 20];
+        let header = Header::new(Codec::Pgp, payload.len() as u32);
+        let words = encode(&header, &payload) + " aardvark adroitness";
+        let error = decode(&words).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "envelope is followed by 2 bytes that are not part of it"
+        );
+    }
+
+    #[test_case(b' ' ; "space")]
+    #[test_case(0xC3 ; "not ascii")]
+    fn test_negative_header_content_type(byte: u8) {
+        let mut header = Header::new(Codec::Pgp, 0);
+        header.content_type = Some("text/plain".to_string());
+        let mut bytes = header.to_bytes().unwrap();
+        bytes[12] = byte;
+        let error = Header::read(&mut bytes.into_iter().map(Ok)).unwrap_err();
+        assert_eq!(error.to_string(), "content type is not printable ASCII");
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test]
+    fn test_positive_envelope_fec_positions() {
+        let payload = [0x05u8; 20];
+        let mut header = Header::new(Codec::Pgp, payload.len() as u32);
+        header.fec = Some(FecParams::new(16, 4).unwrap());
+        let mut words: Vec<_> = encode(&header, &payload)
+            .split(' ')
+            .map(String::from)
+            .collect();
+        words[12] = "?".to_string();
+
+        let opened = decode(&words.join(" ")).unwrap();
+        assert_eq!(opened.payload, payload);
+        assert_eq!(opened.corrections.len(), 1);
+        assert_eq!(opened.corrections[0].position, 12);
+    }
+
+    #[test_case(false, None ; "payload only")]
+    #[test_case(true, None ; "with checksum")]
+    #[test_case(true, Some((4, 2)) ; "with checksum and fec")]
+    fn test_positive_payload_positions(checksum: bool, fec: Option<(usize, usize)>) {
+        let payload: Vec<u8> = (0u8..10).collect();
+        let mut header = Header::new(Codec::Pgp, payload.len() as u32);
+        header.checksum = checksum;
+        header.fec =
+            fec.map(|(data_len, parity_len)| FecParams::new(data_len, parity_len).unwrap());
+        let sealed = seal(&header, &payload).unwrap();
+        let positions = payload_positions(&header).unwrap();
+        assert_eq!(positions.len(), sealed.len());
+        // Every byte of the payload is where its position says it is, and only there.
+        for (byte, position) in sealed.iter().zip(&positions) {
+            if let Some(position) = position {
+                assert_eq!(*byte, payload[*position]);
+            }
+        }
+        let payload_positions: Vec<_> = positions.into_iter().flatten().collect();
+        assert_eq!(payload_positions, (0..payload.len()).collect::<Vec<_>>());
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test_case(2, "aardvark" ; "header word")]
+    #[test_case(12, "aardvark" ; "payload word")]
+    #[test_case(20, "" ; "truncated")]
+    fn test_negative_envelope_corrupted(position: usize, replacement: &str) {
+        let payload = [0x05u8; 8];
+        let header = Header::new(Codec::Pgp, payload.len() as u32);
+        let mut words: Vec<_> = encode(&header, &payload)
+            .split(' ')
+            .map(String::from)
+            .collect();
+        words.truncate(words.len().min(position + 1));
+        let position = position.min(words.len() - 1);
+        words[position] = replacement.to_string();
+
+        // A corrupted header is not detected, so the envelope is opened with the codec given.
+        let words = words.join(" ");
+        let decoder = Codec::Pgp.decoder(words.chars().map(Ok)).unwrap();
+        assert_eq!(
+            open(decoder).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test_case(&[3, 1, 2, 4] ; "shuffled")]
+    #[test_case(&[1, 2, 3, 4] ; "in order")]
+    fn test_positive_split_join(order: &[usize]) {
+        let payload = std::fs::read("sample_data/original/id_ed25519").unwrap();
+        let header = Header::new(Codec::Pgp, 0);
+        let parts = split(&header, &payload, 4).unwrap();
+        let opened = order.iter().map(|&n| reopen(&parts[n - 1])).collect();
+        assert_eq!(join(opened).unwrap(), payload);
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test_case(&[1, 3], "missing parts: 2 (of 3)" ; "missing")]
+    #[test_case(&[1, 2, 2, 3], "duplicate parts: 2 (of 3)" ; "duplicate")]
+    fn test_negative_split_join(order: &[usize], expected_error: &str) {
+        let payload = [0x05u8; 30];
+        let header = Header::new(Codec::Pgp, 0);
+        let parts = split(&header, &payload, 3).unwrap();
+        let opened = order.iter().map(|&n| reopen(&parts[n - 1])).collect();
+        assert_eq!(join(opened).unwrap_err().to_string(), expected_error);
+    }
+
+    #[test_case(100, 1 ; "fits in one part")]
+    #[test_case(60, 2 ; "two parts")]
+    fn test_part_count_for_words(max_words: usize, expected: usize) {
+        let header = Header::new(Codec::Pgp, 0);
+        let count = part_count_for_words(&header, 80, max_words).unwrap();
+        assert_eq!(count, expected);
+        let longest = split(&header, &[0u8; 80], count)
+            .unwrap()
+            .iter()
+            .map(|part| part.len())
+            .max()
+            .unwrap();
+        assert!(longest <= max_words);
+    }
+
+    #[cfg(all(feature = "decode_pgp", feature = "decode_eff"))]
+    #[test]
+    fn test_negative_envelope_not_detected() {
+        assert_eq!(detect_codec("adult amulet adult"), None);
+        assert_eq!(detect_codec("acuteness acuteness"), None);
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test]
+    fn test_negative_envelope_not_detected_header_check_failed() {
+        let header = Header::new(Codec::Pgp, 3);
+        let mut bytes = seal(&header, &[1, 2, 3]).unwrap();
+        bytes[header.to_bytes().unwrap().len() - 1] ^= 0xFF;
+        let words = Codec::Pgp
+            .encoder(bytes.into_iter().map(Ok::<_, std::io::Error>))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .join(" ");
+        assert_eq!(detect_codec(&words), None);
+    }
+}
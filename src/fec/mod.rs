@@ -0,0 +1,347 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Reed-Solomon forward error correction.
+//!
+//! The payload is split into blocks of `data_len` bytes, and each block is followed by
+//! `parity_len` parity bytes. The last block may be shorter. Since every byte is encoded
+//! as one word, a block with `parity_len` parity words can recover from up to `parity_len`
+//! unreadable words (marked as `?`), or from up to `parity_len / 2` wrong words,
+//! or from any combination where twice the wrong words plus the unreadable words
+//! do not exceed `parity_len`.
+
+mod reed_solomon;
+
+/// Block layout for forward error correction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FecParams {
+    data_len: usize,
+    parity_len: usize,
+}
+
+impl FecParams {
+    /// Blocks of `data_len` payload bytes followed by `parity_len` parity bytes.
+    ///
+    /// Both must be non-zero, and a whole block can be at most 255 bytes.
+    pub fn new(data_len: usize, parity_len: usize) -> Result<Self, std::io::Error> {
+        if data_len == 0 || parity_len == 0 || data_len + parity_len > 255 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "FEC blocks need at least one data and one parity byte, and at most 255 bytes in total",
+            ));
+        }
+        Ok(Self {
+            data_len,
+            parity_len,
+        })
+    }
+
+    /// Number of payload bytes per block.
+    pub fn data_len(&self) -> usize {
+        self.data_len
+    }
+
+    /// Number of parity bytes per block.
+    pub fn parity_len(&self) -> usize {
+        self.parity_len
+    }
+
+    /// Position in the payload of each byte that [`FecEncode`] returns
+    /// for `payload_len` payload bytes, or `None` for the parity bytes.
+    pub fn payload_positions(&self, payload_len: usize) -> Vec<Option<usize>> {
+        let mut positions = Vec::with_capacity(payload_len + self.parity_len);
+        for block_start in (0..payload_len).step_by(self.data_len) {
+            let block_end = payload_len.min(block_start + self.data_len);
+            positions.extend((block_start..block_end).map(Some));
+            positions.extend(std::iter::repeat_n(None, self.parity_len));
+        }
+        positions
+    }
+}
+
+/// Adds Reed-Solomon parity bytes to a stream of bytes, ahead of encoding it as words.
+#[derive(Clone, Debug)]
+pub struct FecEncode<I: Iterator> {
+    iter: I,
+    params: FecParams,
+    block: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<I: Iterator> FecEncode<I> {
+    pub fn new(iter: I, params: FecParams) -> Self {
+        Self {
+            iter,
+            params,
+            block: Vec::with_capacity(params.data_len + params.parity_len),
+            pos: 0,
+            done: false,
+        }
+    }
+}
+
+impl<I, E> Iterator for FecEncode<I>
+where
+    I: Iterator<Item = Result<u8, E>>,
+{
+    type Item = Result<u8, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.block.len() {
+            if self.done {
+                return None;
+            }
+            self.block.clear();
+            self.pos = 0;
+            while self.block.len() < self.params.data_len {
+                match self.iter.next() {
+                    Some(Ok(byte)) => self.block.push(byte),
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => {
+                        self.done = true;
+                        break;
+                    }
+                }
+            }
+            if self.block.is_empty() {
+                return None;
+            }
+            let parity = reed_solomon::encode_parity(&self.block, self.params.parity_len);
+            self.block.extend(parity);
+        }
+        let byte = self.block[self.pos];
+        self.pos += 1;
+        Some(Ok(byte))
+    }
+}
+
+/// A word that was corrected by [`FecDecode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FecCorrection {
+    /// Position of the word in the input, counting from zero and including parity words.
+    pub position: usize,
+    /// Whether the word was marked as unreadable, as opposed to being a wrong word.
+    pub erased: bool,
+}
+
+/// Corrects the output of a decoder using the Reed-Solomon parity words,
+/// yielding the payload bytes only.
+#[derive(Clone, Debug)]
+#[cfg(feature = "decode")]
+pub struct FecDecode<D> {
+    decoder: D,
+    params: FecParams,
+    block: Vec<u8>,
+    pos: usize,
+    words_read: usize,
+    corrections: Vec<FecCorrection>,
+}
+
+#[cfg(feature = "decode")]
+impl<D: crate::DecodeSymbol> FecDecode<D> {
+    pub fn new(decoder: D, params: FecParams) -> Self {
+        Self {
+            decoder,
+            params,
+            block: Vec::with_capacity(params.data_len + params.parity_len),
+            pos: 0,
+            words_read: 0,
+            corrections: vec![],
+        }
+    }
+
+    /// Words corrected so far.
+    pub fn corrections(&self) -> &[FecCorrection] {
+        &self.corrections
+    }
+
+    /// Read and correct the next block, returning `Ok(false)` at the end of input.
+    fn next_block(&mut self) -> Result<bool, std::io::Error> {
+        let block_start = self.words_read;
+        let block_len = self.params.data_len + self.params.parity_len;
+        if block_start == 0 {
+            // A checksum at the end of the input is to wait for the corrected bytes.
+            self.decoder.correct_symbols(&[])?;
+        }
+        let mut erasures = vec![];
+        self.block.clear();
+        self.pos = 0;
+        while self.block.len() < block_len {
+            match self.decoder.next_symbol() {
+                Some(Ok(Some(byte))) => self.block.push(byte),
+                Some(Ok(None)) => {
+                    erasures.push(self.block.len());
+                    self.block.push(0);
+                }
+                Some(Err(e)) => return Err(e),
+                None => break,
+            }
+        }
+        self.words_read += self.block.len();
+
+        if self.block.is_empty() {
+            return Ok(false);
+        }
+        if self.block.len() <= self.params.parity_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!("FEC block at word {block_start} is truncated"),
+            ));
+        }
+
+        let corrected = reed_solomon::correct(&mut self.block, self.params.parity_len, &erasures)
+            .map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("too many errors to correct in FEC block at word {block_start}"),
+            )
+        })?;
+        self.corrections
+            .extend(corrected.into_iter().map(|p| FecCorrection {
+                position: block_start + p,
+                erased: erasures.contains(&p),
+            }));
+        self.decoder.correct_symbols(&self.block)?;
+
+        self.block
+            .truncate(self.block.len() - self.params.parity_len);
+        Ok(true)
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<D: crate::DecodeSymbol> Iterator for FecDecode<D> {
+    type Item = Result<u8, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.block.len() {
+            match self.next_block() {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        let byte = self.block[self.pos];
+        self.pos += 1;
+        Some(Ok(byte))
+    }
+}
+
+#[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+#[cfg(test)]
+mod test_cases_fec {
+    use super::{FecCorrection, FecDecode, FecEncode, FecParams};
+    use crate::{Decode, Encode, PgpDecode, PgpEncode};
+    use std::io::{Cursor, Read};
+    use test_case::test_case;
+    use utf8_chars::BufReadCharsExt;
+
+    fn encode_words(bytes: &[u8], params: FecParams) -> Vec<&'static str> {
+        let bytes = FecEncode::new(Cursor::new(bytes).bytes(), params);
+        Encode::<_, PgpEncode<_>>::encode(bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    fn decode_words(words: &str, params: FecParams) -> (Vec<u8>, Vec<FecCorrection>) {
+        let mut cursor = Cursor::new(words);
+        let mut decoder = FecDecode::new(Decode::<_, PgpDecode<_>>::decode(cursor.chars()), params);
+        let bytes = decoder.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        (bytes, decoder.corrections().to_vec())
+    }
+
+    #[test]
+    fn test_fec_encode_block_layout() {
+        let params = FecParams::new(4, 2).unwrap();
+        let words = encode_words(&[0x05u8; 6], params);
+        // Two blocks: 4 + 2 words, then the remaining 2 + 2 words.
+        assert_eq!(words.len(), 10);
+        assert_eq!(&words[..4], &["adult", "amulet", "adult", "amulet"]);
+        assert_eq!(&words[6..8], &["adult", "amulet"]);
+    }
+
+    #[test_case(&[], &[] ; "intact")]
+    #[test_case(&[3], &[] ; "one word unreadable")]
+    #[test_case(&[0, 1, 2, 3], &[] ; "four words unreadable")]
+    #[test_case(&[], &[7, 30] ; "two wrong words in different blocks")]
+    #[test_case(&[12, 30], &[1] ; "unreadable and wrong words")]
+    fn test_positive_fec_roundtrip_pgp(unreadable: &[usize], wrong: &[usize]) {
+        let params = FecParams::new(16, 4).unwrap();
+        let bytes_orig: Vec<u8> = (0u8..40).map(|b| b.wrapping_mul(71)).collect();
+        let mut words = encode_words(&bytes_orig, params);
+        for &p in wrong {
+            // Substitute a valid word from the same list, so that only FEC can notice
+            let replacements = if p % 2 == 0 {
+                ["aardvark", "absurd"]
+            } else {
+                ["adroitness", "adviser"]
+            };
+            words[p] = if words[p] == replacements[0] {
+                replacements[1]
+            } else {
+                replacements[0]
+            };
+        }
+        for &p in unreadable {
+            words[p] = "?";
+        }
+        let (bytes_decoded, corrections) = decode_words(&words.join(" "), params);
+        assert_eq!(bytes_decoded, bytes_orig);
+
+        let mut expected: Vec<_> = unreadable
+            .iter()
+            .map(|&position| FecCorrection {
+                position,
+                erased: true,
+            })
+            .chain(wrong.iter().map(|&position| FecCorrection {
+                position,
+                erased: false,
+            }))
+            .collect();
+        expected.sort_by_key(|c| c.position);
+        assert_eq!(corrections, expected);
+    }
+
+    #[test]
+    fn test_negative_fec_too_many_unreadable_words() {
+        let params = FecParams::new(16, 4).unwrap();
+        let mut words = encode_words(&[0x42u8; 16], params);
+        for word in words.iter_mut().take(5) {
+            *word = "?";
+        }
+        let mut cursor = Cursor::new(words.join(" "));
+        let decoded = FecDecode::new(Decode::<_, PgpDecode<_>>::decode(cursor.chars()), params)
+            .collect::<Result<Vec<_>, _>>();
+        assert_eq!(decoded.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test_case(0, &[] ; "no data")]
+    #[test_case(4, &[Some(0), Some(1), Some(2), Some(3), None, None] ; "one block")]
+    #[test_case(5, &[Some(0), Some(1), Some(2), Some(3), None, None, Some(4), None, None] ; "partial block")]
+    fn test_positive_fec_payload_positions(payload_len: usize, expected: &[Option<usize>]) {
+        let params = FecParams::new(4, 2).unwrap();
+        assert_eq!(params.payload_positions(payload_len), expected);
+    }
+
+    #[test_case(0, 4 ; "no data bytes")]
+    #[test_case(16, 0 ; "no parity bytes")]
+    #[test_case(240, 16 ; "block too long")]
+    fn test_negative_fec_params(data_len: usize, parity_len: usize) {
+        assert!(FecParams::new(data_len, parity_len).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Reed-Solomon codes over GF(2^8), with errors-and-erasures decoding.
+//!
+//! Polynomials are represented as slices of coefficients, highest degree first.
+//! The implementation follows "Reed–Solomon codes for coders" on Wikiversity
+//! (https://en.wikiversity.org/wiki/Reed%E2%80%93Solomon_codes_for_coders).
+
+/// Primitive polynomial x^8 + x^4 + x^3 + x^2 + 1 used to generate the field.
+const PRIMITIVE_POLY: u16 = 0x11d;
+
+/// Exponent and logarithm tables for GF(2^8) with generator 2.
+/// The exponent table is doubled in size so that products of logs need no modulo.
+const GF_TABLES: ([u8; 512], [u8; 256]) = {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= PRIMITIVE_POLY;
+        }
+        i += 1;
+    }
+    while i < 512 {
+        exp[i] = exp[i - 255];
+        i += 1;
+    }
+    (exp, log)
+};
+const GF_EXP: [u8; 512] = GF_TABLES.0;
+const GF_LOG: [u8; 256] = GF_TABLES.1;
+
+fn gf_mul(x: u8, y: u8) -> u8 {
+    if x == 0 || y == 0 {
+        return 0;
+    }
+    GF_EXP[GF_LOG[x as usize] as usize + GF_LOG[y as usize] as usize]
+}
+
+#[cfg(feature = "decode")]
+fn gf_div(x: u8, y: u8) -> u8 {
+    assert_ne!(y, 0, "division by zero in GF(2^8)");
+    if x == 0 {
+        return 0;
+    }
+    GF_EXP[(GF_LOG[x as usize] as usize + 255 - GF_LOG[y as usize] as usize) % 255]
+}
+
+/// Power of the generator, alpha^power.
+fn gf_alpha_pow(power: usize) -> u8 {
+    GF_EXP[power % 255]
+}
+
+#[cfg(feature = "decode")]
+fn gf_inverse(x: u8) -> u8 {
+    GF_EXP[255 - GF_LOG[x as usize] as usize]
+}
+
+#[cfg(feature = "decode")]
+fn gf_poly_scale(p: &[u8], x: u8) -> Vec<u8> {
+    p.iter().map(|&c| gf_mul(c, x)).collect()
+}
+
+#[cfg(feature = "decode")]
+fn gf_poly_add(p: &[u8], q: &[u8]) -> Vec<u8> {
+    let len = p.len().max(q.len());
+    let mut r = vec![0u8; len];
+    for (i, &c) in p.iter().enumerate() {
+        r[i + len - p.len()] = c;
+    }
+    for (i, &c) in q.iter().enumerate() {
+        r[i + len - q.len()] ^= c;
+    }
+    r
+}
+
+fn gf_poly_mul(p: &[u8], q: &[u8]) -> Vec<u8> {
+    let mut r = vec![0u8; p.len() + q.len() - 1];
+    for (j, &qc) in q.iter().enumerate() {
+        for (i, &pc) in p.iter().enumerate() {
+            r[i + j] ^= gf_mul(pc, qc);
+        }
+    }
+    r
+}
+
+#[cfg(feature = "decode")]
+fn gf_poly_eval(p: &[u8], x: u8) -> u8 {
+    p.iter().skip(1).fold(p[0], |y, &c| gf_mul(y, x) ^ c)
+}
+
+fn generator_poly(nsym: usize) -> Vec<u8> {
+    (0..nsym).fold(vec![1], |g, i| gf_poly_mul(&g, &[1, gf_alpha_pow(i)]))
+}
+
+/// Compute the `nsym` parity bytes for a message.
+pub(crate) fn encode_parity(msg: &[u8], nsym: usize) -> Vec<u8> {
+    let gen = generator_poly(nsym);
+    let mut remainder = msg.to_vec();
+    remainder.resize(msg.len() + nsym, 0);
+    for i in 0..msg.len() {
+        let coef = remainder[i];
+        if coef != 0 {
+            for (j, &g) in gen.iter().enumerate().skip(1) {
+                remainder[i + j] ^= gf_mul(g, coef);
+            }
+        }
+    }
+    remainder.split_off(msg.len())
+}
+
+#[cfg(feature = "decode")]
+fn calc_syndromes(codeword: &[u8], nsym: usize) -> Vec<u8> {
+    // The leading zero keeps the indices in line with the mathematical notation.
+    std::iter::once(0)
+        .chain((0..nsym).map(|i| gf_poly_eval(codeword, gf_alpha_pow(i))))
+        .collect()
+}
+
+#[cfg(feature = "decode")]
+fn find_errata_locator(coef_pos: &[usize]) -> Vec<u8> {
+    coef_pos.iter().fold(vec![1], |e_loc, &i| {
+        gf_poly_mul(&e_loc, &gf_poly_add(&[1], &[gf_alpha_pow(i), 0]))
+    })
+}
+
+#[cfg(feature = "decode")]
+fn find_error_evaluator(synd: &[u8], err_loc: &[u8], nsym: usize) -> Vec<u8> {
+    // Remainder of the division by x^(nsym + 1) are the lowest nsym + 1 terms of the product.
+    let product = gf_poly_mul(synd, err_loc);
+    let keep = (nsym + 1).min(product.len());
+    product[product.len() - keep..].to_vec()
+}
+
+/// Forney algorithm: compute the error magnitudes and correct the codeword in place.
+#[cfg(feature = "decode")]
+fn correct_errata(codeword: &mut [u8], synd: &[u8], err_pos: &[usize]) -> Result<(), Error> {
+    let coef_pos: Vec<_> = err_pos.iter().map(|p| codeword.len() - 1 - p).collect();
+    let err_loc = find_errata_locator(&coef_pos);
+    let synd_rev: Vec<_> = synd.iter().rev().copied().collect();
+    let mut err_eval = find_error_evaluator(&synd_rev, &err_loc, err_loc.len() - 1);
+    err_eval.reverse();
+
+    let x: Vec<_> = coef_pos.iter().map(|&p| gf_alpha_pow(p)).collect();
+    let err_eval_rev: Vec<_> = err_eval.iter().rev().copied().collect();
+
+    for (i, &xi) in x.iter().enumerate() {
+        let xi_inv = gf_inverse(xi);
+        let err_loc_prime = x
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(1, |acc, (_, &xj)| gf_mul(acc, 1 ^ gf_mul(xi_inv, xj)));
+        if err_loc_prime == 0 {
+            return Err(Error::Uncorrectable);
+        }
+        let y = gf_mul(xi, gf_poly_eval(&err_eval_rev, xi_inv));
+        codeword[err_pos[i]] ^= gf_div(y, err_loc_prime);
+    }
+    Ok(())
+}
+
+/// Berlekamp-Massey algorithm: compute the error locator polynomial from the syndromes.
+#[cfg(feature = "decode")]
+fn find_error_locator(synd: &[u8], nsym: usize, erase_count: usize) -> Result<Vec<u8>, Error> {
+    let mut err_loc = vec![1u8];
+    let mut old_loc = vec![1u8];
+    let synd_shift = synd.len().saturating_sub(nsym);
+
+    for i in 0..nsym - erase_count {
+        let k = i + synd_shift;
+        let mut delta = synd[k];
+        for j in 1..err_loc.len() {
+            delta ^= gf_mul(err_loc[err_loc.len() - 1 - j], synd[k - j]);
+        }
+        old_loc.push(0);
+        if delta != 0 {
+            if old_loc.len() > err_loc.len() {
+                let new_loc = gf_poly_scale(&old_loc, delta);
+                old_loc = gf_poly_scale(&err_loc, gf_inverse(delta));
+                err_loc = new_loc;
+            }
+            err_loc = gf_poly_add(&err_loc, &gf_poly_scale(&old_loc, delta));
+        }
+    }
+
+    let leading_zeros = err_loc.iter().take_while(|&&c| c == 0).count();
+    err_loc.drain(..leading_zeros);
+    let errs = err_loc.len().saturating_sub(1);
+    if errs * 2 + erase_count > nsym {
+        return Err(Error::Uncorrectable);
+    }
+    Ok(err_loc)
+}
+
+/// Chien search: find the positions of the errors from the error locator polynomial.
+#[cfg(feature = "decode")]
+fn find_errors(err_loc_rev: &[u8], codeword_len: usize) -> Result<Vec<usize>, Error> {
+    let errs = err_loc_rev.len() - 1;
+    let err_pos: Vec<_> = (0..codeword_len)
+        .filter(|&i| gf_poly_eval(err_loc_rev, gf_alpha_pow(i)) == 0)
+        .map(|i| codeword_len - 1 - i)
+        .collect();
+    if err_pos.len() != errs {
+        return Err(Error::Uncorrectable);
+    }
+    Ok(err_pos)
+}
+
+/// Syndromes with the erasures trimmed off, so that only the errors remain to be located.
+#[cfg(feature = "decode")]
+fn forney_syndromes(synd: &[u8], erase_pos: &[usize], codeword_len: usize) -> Vec<u8> {
+    let mut fsynd = synd[1..].to_vec();
+    for &p in erase_pos {
+        let x = gf_alpha_pow(codeword_len - 1 - p);
+        for j in 0..fsynd.len() - 1 {
+            fsynd[j] = gf_mul(fsynd[j], x) ^ fsynd[j + 1];
+        }
+    }
+    fsynd
+}
+
+/// Reason for failing to correct a codeword.
+#[cfg(feature = "decode")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Error {
+    /// There are more errors and erasures than the parity bytes can correct.
+    Uncorrectable,
+}
+
+/// Correct a codeword (message followed by `nsym` parity bytes) in place.
+///
+/// `erase_pos` are the known positions of erasures in the codeword.
+/// Returns the positions that were corrected, which includes all erasures.
+#[cfg(feature = "decode")]
+pub(crate) fn correct(
+    codeword: &mut [u8],
+    nsym: usize,
+    erase_pos: &[usize],
+) -> Result<Vec<usize>, Error> {
+    assert!(codeword.len() <= 255 && codeword.len() > nsym);
+    if erase_pos.len() > nsym {
+        return Err(Error::Uncorrectable);
+    }
+    for &p in erase_pos {
+        codeword[p] = 0;
+    }
+
+    let synd = calc_syndromes(codeword, nsym);
+    if synd.iter().all(|&s| s == 0) {
+        return Ok(erase_pos.to_vec());
+    }
+
+    let fsynd = forney_syndromes(&synd, erase_pos, codeword.len());
+    let err_loc = find_error_locator(&fsynd, nsym, erase_pos.len())?;
+    let err_loc_rev: Vec<_> = err_loc.iter().rev().copied().collect();
+    let err_pos = find_errors(&err_loc_rev, codeword.len())?;
+
+    let mut errata_pos = erase_pos.to_vec();
+    errata_pos.extend(err_pos.iter().filter(|p| !erase_pos.contains(p)));
+    correct_errata(codeword, &synd, &errata_pos)?;
+
+    if calc_syndromes(codeword, nsym).iter().any(|&s| s != 0) {
+        return Err(Error::Uncorrectable);
+    }
+    errata_pos.sort_unstable();
+    Ok(errata_pos)
+}
+
+#[cfg(all(test, feature = "decode"))]
+mod test_cases_reed_solomon {
+    use super::{correct, encode_parity, Error};
+    use test_case::test_case;
+
+    fn codeword(msg: &[u8], nsym: usize) -> Vec<u8> {
+        let mut codeword = msg.to_vec();
+        codeword.extend(encode_parity(msg, nsym));
+        codeword
+    }
+
+    #[test_case(&[], &[] ; "no errors")]
+    #[test_case(&[3, 17, 38, 39], &[] ; "four errors")]
+    #[test_case(&[], &[0, 1, 2, 3, 4, 5, 6, 7] ; "eight erasures")]
+    #[test_case(&[9, 20], &[0, 5, 30, 39] ; "two errors and four erasures")]
+    fn test_positive_correct(errors: &[usize], erasures: &[usize]) {
+        let msg: Vec<u8> = (0..32u8)
+            .map(|b| b.wrapping_mul(37).wrapping_add(11))
+            .collect();
+        let orig = codeword(&msg, 8);
+        let mut received = orig.clone();
+        for &p in errors {
+            received[p] ^= 0x5a;
+        }
+        for &p in erasures {
+            received[p] = 0;
+        }
+        let corrected = correct(&mut received, 8, erasures).unwrap();
+        assert_eq!(received, orig);
+        let mut expected: Vec<_> = errors.iter().chain(erasures).copied().collect();
+        expected.sort_unstable();
+        assert_eq!(corrected, expected);
+    }
+
+    #[test_case(&[1, 2, 3, 4, 5], &[] ; "five errors")]
+    #[test_case(&[1, 2], &[10, 11, 12, 13, 14] ; "two errors and five erasures")]
+    fn test_negative_correct(errors: &[usize], erasures: &[usize]) {
+        let msg = b"ssh-ed25519 private key material";
+        let mut received = codeword(msg, 8);
+        for &p in errors {
+            received[p] ^= 0xff;
+        }
+        // Too many errata are either detected, or mis-corrected into another codeword.
+        match correct(&mut received, 8, erasures) {
+            Ok(_) => assert_ne!(&received[..msg.len()], msg),
+            Err(e) => assert_eq!(e, Error::Uncorrectable),
+        }
+    }
+}
@@ -0,0 +1,304 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Decoding a transcript past the words that cannot be read, collecting every error.
+
+use crate::{SourcePosition, Transcript, Typist};
+
+/// What is wrong with a word of a transcript, found when decoding it leniently.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WordProblem {
+    /// The word is not a word of the list, and is replaced by a placeholder for one byte.
+    NotAWord,
+    /// The word is not a word of the list, and is left out, since the word after it
+    /// is from the list that the word itself would have had to be from.
+    /// It stands in for no byte or for two bytes, like two words written as one.
+    NotOneWord,
+    /// The word is a word of the other list of the PGP Word List, so a word is missing
+    /// before it, which is replaced by a placeholder.
+    MissingBefore,
+    /// The word is a word of the other list of the PGP Word List, and is the same word
+    /// as the word before it, so it is left out as typed twice.
+    TypedTwice,
+}
+
+impl WordProblem {
+    /// Short name of the problem, like `not-a-word`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WordProblem::NotAWord => "not-a-word",
+            WordProblem::NotOneWord => "not-one-word",
+            WordProblem::MissingBefore => "missing-before",
+            WordProblem::TypedTwice => "typed-twice",
+        }
+    }
+}
+
+/// A word of a transcript that could not be decoded as it is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WordError {
+    /// Position of the byte that the word was decoded as, or that it came before
+    /// if it was left out, counting from zero.
+    pub position: usize,
+    /// Where the word starts in the transcript.
+    pub source: SourcePosition,
+    /// The word as typed.
+    pub typed: String,
+    pub problem: WordProblem,
+    /// Words that could have been meant, closest first.
+    pub suggestions: Vec<String>,
+}
+
+impl std::fmt::Display for WordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "word {} at {}: \"{}\" ",
+            self.position + 1,
+            self.source,
+            self.typed
+        )?;
+        match self.problem {
+            WordProblem::NotAWord => write!(f, "is not a word")?,
+            WordProblem::NotOneWord => write!(
+                f,
+                "is not a word, and does not stand in for exactly one word, since the word after it is from the same list; left out"
+            )?,
+            WordProblem::MissingBefore => write!(
+                f,
+                "is a word of the other list, so a word is missing before it"
+            )?,
+            WordProblem::TypedTwice => write!(
+                f,
+                "is a word of the other list, and the same as the word before it; left out as typed twice"
+            )?,
+        }
+        match self.suggestions.as_slice() {
+            [] => Ok(()),
+            [suggestion] => write!(f, "; did you mean \"{suggestion}\"?"),
+            suggestions => write!(
+                f,
+                "; did you mean one of {}?",
+                suggestions
+                    .iter()
+                    .map(|suggestion| format!("\"{suggestion}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// The outcome of decoding a transcript leniently.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LenientDecode {
+    /// The decoded bytes, with `None` for unreadable words and placeholders.
+    pub symbols: Vec<Option<u8>>,
+    /// Every word that could not be decoded as it is, in order.
+    pub errors: Vec<WordError>,
+}
+
+impl LenientDecode {
+    /// Decode the words read from `chars` with `typist`, which has to have no words typed yet,
+    /// going on past words that cannot be decoded.
+    ///
+    /// A word that is not a word of the list is replaced by a placeholder, as if it was
+    /// marked as unreadable (`?`), so that forward error correction can recover it.
+    /// With the PGP Word List, the list of the word after it tells whether it stands in for
+    /// exactly one byte; if it does not, it is left out instead. A word of the other list
+    /// is taken to have a word missing before it, unless it was typed twice.
+    pub fn read<I>(typist: Typist, chars: I) -> Result<Self, std::io::Error>
+    where
+        I: Iterator<Item = Result<char, std::io::Error>>,
+    {
+        Ok(Self::from_transcript(&Transcript::read(typist, chars)?))
+    }
+
+    /// Decode the words of `transcript` leniently.
+    pub fn from_transcript(transcript: &Transcript) -> Self {
+        let typist = &transcript.typist;
+        let words = &transcript.words;
+        let lists_len = typist.lists_len();
+        let mut decoded = Self::default();
+
+        for (i, word) in words.iter().enumerate() {
+            let position = decoded.symbols.len();
+            let expected_list = position % lists_len;
+            let problem = match word.word {
+                None if word.typed == "?" => {
+                    decoded.symbols.push(None);
+                    continue;
+                }
+                Some((list, byte)) if list == expected_list => {
+                    decoded.symbols.push(Some(byte));
+                    continue;
+                }
+                Some((_, byte)) => {
+                    let previous = i.checked_sub(1).map(|i| &words[i]);
+                    if previous.is_some_and(|previous| previous.word == word.word) {
+                        WordProblem::TypedTwice
+                    } else {
+                        decoded.symbols.extend([None, Some(byte)]);
+                        WordProblem::MissingBefore
+                    }
+                }
+                None => {
+                    // The next word that is a word tells which list the word would be from
+                    // if this one stands in for exactly one byte.
+                    let mut list = expected_list;
+                    let mut next_list = None;
+                    for next in &words[i + 1..] {
+                        list = (list + 1) % lists_len;
+                        match next.word {
+                            None if next.typed == "?" => {}
+                            None => break,
+                            Some((next, _)) => {
+                                next_list = Some(next);
+                                break;
+                            }
+                        }
+                    }
+                    if next_list.is_some_and(|next_list| next_list != list) {
+                        WordProblem::NotOneWord
+                    } else {
+                        decoded.symbols.push(None);
+                        WordProblem::NotAWord
+                    }
+                }
+            };
+            let suggestions = match problem {
+                WordProblem::NotAWord | WordProblem::NotOneWord => typist
+                    .suggestions(position, &word.typed)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+                _ => vec![],
+            };
+            decoded.errors.push(WordError {
+                position: match problem {
+                    WordProblem::MissingBefore => position + 1,
+                    _ => position,
+                },
+                source: word.source,
+                typed: word.typed.clone(),
+                problem,
+                suggestions,
+            });
+        }
+        decoded
+    }
+
+    /// The decoded bytes, if there are no unreadable words or placeholders among them.
+    pub fn bytes(&self) -> Option<Vec<u8>> {
+        self.symbols.iter().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod test_cases_lenient {
+    #[cfg(feature = "decode_pgp")]
+    use test_case::test_case;
+    #[cfg(any(feature = "decode_pgp", feature = "decode_eff"))]
+    use {
+        super::{LenientDecode, WordProblem},
+        crate::{Codec, Typist},
+    };
+
+    #[cfg(any(feature = "decode_pgp", feature = "decode_eff"))]
+    fn read(codec: Codec, text: &str) -> LenientDecode {
+        LenientDecode::read(Typist::for_codec(codec).unwrap(), text.chars().map(Ok)).unwrap()
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test]
+    fn test_positive_lenient_pgp() {
+        let decoded = read(Codec::Pgp, "aardvark adroitness absurd\nadviser");
+        assert_eq!(decoded.errors, []);
+        assert_eq!(decoded.bytes(), Some(vec![0, 0, 1, 1]));
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test_case("aardvark adroitnes absurd advizer accrue", &[Some(0), None, Some(1), None, Some(2)], &[(1, WordProblem::NotAWord, "adroitness"), (3, WordProblem::NotAWord, "adviser")] ; "not words")]
+    #[test_case("aardvark adroitnesabsurd adviser", &[Some(0), Some(1)], &[(1, WordProblem::NotOneWord, "adroitness")] ; "two words as one")]
+    #[test_case("aardvark absurd adviser", &[Some(0), None, Some(1), Some(1)], &[(2, WordProblem::MissingBefore, "")] ; "missing word")]
+    #[test_case("aardvark adroitness adroitness absurd", &[Some(0), Some(0), Some(1)], &[(2, WordProblem::TypedTwice, "")] ; "typed twice")]
+    #[test_case("aardvark ? absurd", &[Some(0), None, Some(1)], &[] ; "unreadable")]
+    fn test_negative_lenient_pgp(
+        text: &str,
+        symbols: &[Option<u8>],
+        errors: &[(usize, WordProblem, &str)],
+    ) {
+        let decoded = read(Codec::Pgp, text);
+        assert_eq!(decoded.symbols, symbols);
+        let found: Vec<_> = decoded
+            .errors
+            .iter()
+            .map(|error| {
+                let suggestion = error.suggestions.first().map_or("", String::as_str);
+                (error.position, error.problem.clone(), suggestion)
+            })
+            .collect();
+        assert_eq!(found, errors);
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test_case("aardvark advizer", "word 2 at line 1, column 10: \"advizer\" is not a word; did you mean \"adviser\"?" ; "not a word")]
+    #[test_case("aardvark absurd", "word 3 at line 1, column 10: \"absurd\" is a word of the other list, so a word is missing before it" ; "missing word")]
+    fn test_negative_lenient_pgp_display(text: &str, expected: &str) {
+        let decoded = read(Codec::Pgp, text);
+        assert_eq!(decoded.errors.len(), 1);
+        assert_eq!(decoded.errors[0].to_string(), expected);
+    }
+
+    #[cfg(feature = "decode_eff")]
+    #[test]
+    fn test_negative_lenient_eff() {
+        let decoded = read(Codec::Eff, "acuteness acutenes\nacuteness");
+        assert_eq!(decoded.symbols, [Some(0x05), None, Some(0x05)]);
+        assert_eq!(decoded.errors.len(), 1);
+        assert_eq!(decoded.errors[0].problem, WordProblem::NotAWord);
+        assert_eq!(decoded.errors[0].suggestions[0], "acuteness");
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test_case("id_ed25519-fold_w_78_s.txt")]
+    #[test_case("id_ed25519-fold_w_78_s-trimmed.txt")]
+    fn test_negative_lenient_pgp_sample_data_file_id_ed25519(fpath_encoded: &str) {
+        let original = std::fs::read("sample_data/original/id_ed25519").unwrap();
+        let text = std::fs::read_to_string(
+            std::path::Path::new("sample_data/encoded_corrupted/pgp").join(fpath_encoded),
+        )
+        .unwrap();
+
+        // Every corrupted word is found, and the word that was meant is suggested first.
+        let decoded = read(Codec::Pgp, &text);
+        assert_eq!(decoded.symbols.len(), original.len());
+        assert!(decoded.errors.len() > 1);
+        let typist = Typist::for_codec(Codec::Pgp).unwrap();
+        for error in &decoded.errors {
+            assert_eq!(error.problem, WordProblem::NotAWord);
+            let meant = typist.word(error.position, original[error.position]);
+            assert_eq!(
+                error.suggestions.first().map(|s| s.to_lowercase()),
+                Some(meant.to_lowercase()),
+                "{error}"
+            );
+        }
+        for (symbol, byte) in decoded.symbols.iter().zip(&original) {
+            assert!(symbol.is_none_or(|symbol| symbol == *byte));
+        }
+    }
+}
@@ -22,59 +22,147 @@
     feature = "encode",
     feature = "decode",
     feature = "wl_eff_encode",
-    feature = "wl_pgp_encode"
+    feature = "wl_pgp_encode",
+    feature = "wl_bytewords_encode",
+    feature = "wl_rfc1751_encode",
+    feature = "wl_eff_full_encode",
+    feature = "wl_norwegian_encode",
+    feature = "wl_german_encode"
 )))]
-compile_error!("Building lib target requires that at least one of the following features is enabled: encode; decode; wl_eff_encode; wl_pgp_encode");
+compile_error!("Building lib target requires that at least one of the following features is enabled: encode; decode; wl_eff_encode; wl_pgp_encode; wl_bytewords_encode; wl_rfc1751_encode; wl_eff_full_encode; wl_norwegian_encode; wl_german_encode");
 
 #[cfg(any(
     feature = "decode",
     feature = "wl_eff_decode",
-    feature = "wl_pgp_decode"
+    feature = "wl_pgp_decode",
+    feature = "wl_bytewords_decode",
+    feature = "wl_rfc1751_decode",
+    feature = "wl_eff_full_decode",
+    feature = "wl_norwegian_decode",
+    feature = "wl_german_decode"
 ))]
 mod decode;
 #[cfg(any(
     feature = "encode",
     feature = "wl_eff_encode",
-    feature = "wl_pgp_encode"
+    feature = "wl_pgp_encode",
+    feature = "wl_bytewords_encode",
+    feature = "wl_rfc1751_encode",
+    feature = "wl_eff_full_encode",
+    feature = "wl_norwegian_encode",
+    feature = "wl_german_encode"
 ))]
 mod encode;
 
+#[cfg(feature = "fec")]
+mod fec;
+
+#[cfg(any(
+    feature = "envelope",
+    feature = "encode_bytewords",
+    feature = "decode_bytewords",
+    feature = "encode_rfc1751",
+    feature = "decode_rfc1751"
+))]
+mod checksum;
+mod codec;
+#[cfg(feature = "envelope")]
+pub mod envelope;
+#[cfg(feature = "decode")]
+mod lenient;
+#[cfg(any(feature = "encode", feature = "decode"))]
+mod normalize;
+mod radix;
+#[cfg(all(feature = "encode", feature = "decode"))]
+mod transcode;
+#[cfg(feature = "decode")]
+mod typing;
+#[cfg(feature = "decode")]
+mod verify;
+#[cfg(any(feature = "encode", feature = "decode"))]
+mod wordlist;
+#[cfg(any(feature = "encode", feature = "decode"))]
+mod wordlist_check;
+
 #[cfg(any(
     feature = "decode",
     feature = "wl_eff_decode",
-    feature = "wl_pgp_decode"
+    feature = "wl_pgp_decode",
+    feature = "wl_bytewords_decode",
+    feature = "wl_rfc1751_decode",
+    feature = "wl_eff_full_decode",
+    feature = "wl_norwegian_decode",
+    feature = "wl_german_decode"
 ))]
 pub use decode::*;
 #[cfg(any(
     feature = "encode",
     feature = "wl_eff_encode",
-    feature = "wl_pgp_encode"
+    feature = "wl_pgp_encode",
+    feature = "wl_bytewords_encode",
+    feature = "wl_rfc1751_encode",
+    feature = "wl_eff_full_encode",
+    feature = "wl_norwegian_encode",
+    feature = "wl_german_encode"
 ))]
 pub use encode::*;
+#[cfg(feature = "fec")]
+pub use fec::*;
+#[cfg(feature = "decode")]
+pub use lenient::{LenientDecode, WordError, WordProblem};
 
+pub use codec::*;
+pub use radix::RadixScheme;
 #[cfg(all(feature = "encode", feature = "decode"))]
+pub use transcode::*;
+#[cfg(feature = "decode")]
+pub use typing::Typist;
+#[cfg(feature = "decode")]
+pub use verify::{DiffWord, Difference, Mismatch, Transcript};
+#[cfg(any(feature = "encode", feature = "decode"))]
+pub use wordlist::Wordlist;
+#[cfg(any(feature = "encode", feature = "decode"))]
+pub use wordlist_check::WordlistReport;
+
+#[cfg(any(
+    all(feature = "encode_pgp", feature = "decode_pgp"),
+    all(feature = "encode_eff", feature = "decode_eff"),
+    all(feature = "encode_norwegian", feature = "decode_norwegian"),
+    all(feature = "encode_german", feature = "decode_german"),
+    all(feature = "wl_eff_full_encode", feature = "wl_rfc1751_encode")
+))]
 #[cfg(test)]
 mod test_cases_encode {
-    use super::{Decode, Encode};
+    #[cfg(any(
+        all(feature = "encode_pgp", feature = "decode_pgp"),
+        all(feature = "encode_eff", feature = "decode_eff")
+    ))]
+    use super::Decode;
     #[cfg(all(feature = "encode_eff", feature = "decode_eff"))]
     use super::{EffDecode, EffEncode};
     #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
     use super::{PgpDecode, PgpEncode};
     use std::io::{Cursor, Read};
     use test_case::test_case;
-    use utf8_chars::BufReadCharsExt;
+    #[cfg(any(
+        all(feature = "encode_pgp", feature = "decode_pgp"),
+        all(feature = "encode_eff", feature = "decode_eff"),
+        all(feature = "encode_norwegian", feature = "decode_norwegian"),
+        all(feature = "encode_german", feature = "decode_german")
+    ))]
+    use {super::Encode, utf8_chars::BufReadCharsExt};
 
     #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
     #[test_case(&[0x00u8; 3] ; "data 0x00 0x00 0x00")]
     #[test_case(&*(0x00u8..=0xFF).collect::<Vec<_>>() ; "data 0x00..0xFF")]
     #[test_case(&*(0x01u8..=0xFF).collect::<Vec<_>>() ; "data 0x01..0xFF")]
     fn test_positive_roundtrip_pgp_codec(bytes_orig: &[u8]) {
-        let bytes = Cursor::new(bytes_orig).bytes().into_iter();
+        let bytes = Cursor::new(bytes_orig).bytes();
         let encoded_words = Encode::<_, PgpEncode<_>>::encode(bytes)
             .collect::<Result<String, _>>()
             .unwrap();
         let mut cursor = Cursor::new(encoded_words);
-        let words_chars = cursor.chars().into_iter();
+        let words_chars = cursor.chars();
         let decoded_bytes = Decode::<_, PgpDecode<_>>::decode(words_chars)
             .collect::<Result<Vec<_>, _>>()
             .unwrap();
@@ -86,15 +174,68 @@ mod test_cases_encode {
     #[test_case(&*(0x00u8..=0xFF).collect::<Vec<_>>() ; "data 0x00..0xFF")]
     #[test_case(&*(0x01u8..=0xFF).collect::<Vec<_>>() ; "data 0x01..0xFF")]
     fn test_positive_roundtrip_eff_codec(bytes_orig: &[u8]) {
-        let bytes = Cursor::new(bytes_orig).bytes().into_iter();
+        let bytes = Cursor::new(bytes_orig).bytes();
         let encoded_words = Encode::<_, EffEncode<_>>::encode(bytes)
             .collect::<Result<String, _>>()
             .unwrap();
         let mut cursor = Cursor::new(encoded_words);
-        let words_chars = cursor.chars().into_iter();
+        let words_chars = cursor.chars();
         let decoded_bytes = Decode::<_, EffDecode<_>>::decode(words_chars)
             .collect::<Result<Vec<_>, _>>()
             .unwrap();
         assert_eq!(bytes_orig, decoded_bytes);
     }
+
+    #[cfg(all(feature = "encode_norwegian", feature = "decode_norwegian"))]
+    #[test_case(&*(0x00u8..=0xFF).collect::<Vec<_>>() ; "data 0x00..0xFF")]
+    #[test_case(&*(0x00u8..=0xFF).rev().collect::<Vec<_>>() ; "data 0xFF..0x00")]
+    fn test_positive_roundtrip_norwegian_codec(bytes_orig: &[u8]) {
+        let bytes = Cursor::new(bytes_orig).bytes();
+        let encoded_words = Encode::<_, super::NorwegianEncode<_>>::encode(bytes)
+            .collect::<Result<String, _>>()
+            .unwrap();
+        let mut cursor = Cursor::new(encoded_words);
+        let decoded_bytes = super::LocalizedDecode::norwegian(cursor.chars())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(bytes_orig, decoded_bytes);
+    }
+
+    #[cfg(all(feature = "encode_german", feature = "decode_german"))]
+    #[test_case(&*(0x00u8..=0xFF).collect::<Vec<_>>() ; "data 0x00..0xFF")]
+    #[test_case(&*(0x00u8..=0xFF).rev().collect::<Vec<_>>() ; "data 0xFF..0x00")]
+    fn test_positive_roundtrip_german_codec(bytes_orig: &[u8]) {
+        let bytes = Cursor::new(bytes_orig).bytes();
+        let encoded_words = Encode::<_, super::GermanEncode<_>>::encode(bytes)
+            .collect::<Result<String, _>>()
+            .unwrap();
+        let mut cursor = Cursor::new(encoded_words);
+        let decoded_bytes = super::LocalizedDecode::german(cursor.chars())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(bytes_orig, decoded_bytes);
+    }
+
+    #[cfg(all(feature = "wl_eff_full_encode", feature = "wl_rfc1751_encode"))]
+    #[test_case(crate::WL_EFF_FULL_ENCODE ; "1296 words in blocks")]
+    #[test_case(crate::WL_RFC1751_ENCODE ; "2048 words bit packed")]
+    #[test_case(&crate::WL_EFF_FULL_ENCODE[..7] ; "7 words in blocks")]
+    fn test_positive_roundtrip_radix_codec(words: &[&str]) {
+        for len in 0..=40 {
+            let bytes_orig: Vec<u8> = (0..len).map(|i| (i * 37 + 255) as u8).collect();
+            let bytes = Cursor::new(&bytes_orig).bytes();
+            let encoded_words = super::RadixEncode::new(bytes, words)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let scheme = super::RadixScheme::for_wordlist_len(words.len()).unwrap();
+            assert_eq!(encoded_words.len(), scheme.word_count(len), "{len} bytes");
+            let encoded_words = encoded_words.join(" ");
+            let decoded_bytes = super::RadixDecode::new(encoded_words.chars().map(Ok), words)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(bytes_orig, decoded_bytes, "{len} bytes");
+        }
+    }
 }
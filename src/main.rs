@@ -20,30 +20,60 @@
 compile_error!("Building bin target requires that at least one encoder or decoder is enabled");
 
 use std::fs::{File, OpenOptions};
-use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, Read, Write};
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords"
+))]
+use std::io::Cursor;
+#[cfg(any(
+    feature = "encode",
+    feature = "envelope",
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords"
+))]
+use std::io::Read;
+use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, IsTerminal, Write};
+#[cfg(feature = "encode")]
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
-use clap::{Parser, ValueEnum};
-#[cfg(any(feature = "decode_pgp", feature = "decode_eff"))]
+use clap::{Parser, Subcommand, ValueEnum};
+#[cfg(feature = "decode")]
 use utf8_chars::BufReadCharsExt;
 
-#[cfg(feature = "encode_eff")]
-use base256::EffEncode;
-#[cfg(any(feature = "encode_pgp", feature = "encode_eff"))]
-use base256::Encode;
-#[cfg(feature = "encode_pgp")]
-use base256::PgpEncode;
-
-#[cfg(any(feature = "decode_pgp", feature = "decode_eff"))]
-use base256::Decode;
-#[cfg(feature = "decode_eff")]
-use base256::EffDecode;
-#[cfg(feature = "decode_pgp")]
-use base256::PgpDecode;
+use base256::Codec;
+#[cfg(feature = "decode")]
+use base256::DecodeSymbol;
+
+#[cfg(all(feature = "fec", feature = "decode"))]
+use base256::FecDecode;
+#[cfg(all(feature = "fec", feature = "encode"))]
+use base256::FecEncode;
+#[cfg(feature = "fec")]
+use base256::FecParams;
+
+#[cfg(feature = "envelope")]
+use base256::envelope;
+
+#[cfg(all(feature = "encode", feature = "decode"))]
+mod type_mode;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None, name = "lastresort")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    /*
+     * Subcommands for working with data split into several parts
+     * are available when the envelope feature is enabled at compile-time.
+     * Transcoding is available when both encoding and decoding are enabled.
+     * Checking wordlists is always available.
+     */
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /*
      * The decode feature can be enabled or disabled at compile-time.
      * If the decode feature is enabled at compile-time:
@@ -77,6 +107,449 @@ struct Cli {
     /// Write output to OUTPUT_FILE. Default is stdout; passing - also represents stdout
     #[arg(short, long, value_name = "OUTPUT_FILE")]
     output: Option<String>,
+    /// Fail instead of replacing OUTPUT_FILE if it already exists
+    #[arg(long)]
+    no_clobber: bool,
+
+    /*
+     * The format of the data applies to the input when encoding,
+     * and to the output when decoding.
+     */
+    /// Format of the data to encode
+    #[cfg(feature = "encode")]
+    #[arg(long, value_name = "FORMAT", default_value = "raw")]
+    input_format: InputFormat,
+    /// Format to write the decoded data in
+    #[cfg(feature = "decode")]
+    #[arg(long, value_name = "FORMAT", default_value = "raw")]
+    output_format: OutputFormat,
+
+    /// Read the 256 words of the custom encoder or decoder from WORDLIST_FILE, one word per line
+    #[arg(long, value_name = "WORDLIST_FILE")]
+    wordlist: Option<String>,
+
+    /// Interpret the input as a transcript made with speech-to-text: leave out filler words,
+    /// join words split in parts, and replace misrecognized words. Uncertain replacements
+    /// are reported on stderr, and nothing is decoded unless --accept-uncertain is given
+    #[cfg(any(
+        feature = "decode_eff",
+        feature = "decode_pgp",
+        feature = "decode_bytewords"
+    ))]
+    #[arg(long, requires("decode"))]
+    voice: bool,
+    /// Decode in voice mode even if some of the words were replaced with uncertainty,
+    /// after checking the replacements reported on stderr
+    #[cfg(any(
+        feature = "decode_eff",
+        feature = "decode_pgp",
+        feature = "decode_bytewords"
+    ))]
+    #[arg(long, requires("voice"))]
+    accept_uncertain: bool,
+
+    /// Go on decoding past words that cannot be decoded, and report all of them with
+    /// suggestions for what was meant. The data is only written once all of the words
+    /// are decoded, or corrected with forward error correction
+    #[cfg(feature = "decode")]
+    #[arg(long, requires("decode"))]
+    lenient: bool,
+
+    /*
+     * Forward error correction applies to both encoding and decoding.
+     */
+    #[cfg(feature = "fec")]
+    #[command(flatten)]
+    fec: CliFec,
+
+    /*
+     * The layout of the words applies to encoding.
+     */
+    #[cfg(feature = "encode")]
+    #[command(flatten)]
+    layout: CliLayout,
+
+    /*
+     * The envelope applies to both encoding and decoding,
+     * and the options describing the data in the envelope apply to encoding.
+     */
+    /// Wrap encoded data in a self-describing envelope. When decoding,
+    /// the codec and options are read from the envelope header
+    #[cfg(feature = "envelope")]
+    #[arg(long)]
+    envelope: bool,
+    /// Record the type of the data, for example openssh-private-key, in the envelope
+    #[cfg(feature = "envelope")]
+    #[arg(long, value_name = "CONTENT_TYPE", requires("envelope"))]
+    content_type: Option<String>,
+    /// Mark the data as compressed in the envelope
+    #[cfg(feature = "envelope")]
+    #[arg(long, requires("envelope"))]
+    compressed: bool,
+    /// Mark the data as encrypted in the envelope
+    #[cfg(feature = "envelope")]
+    #[arg(long, requires("envelope"))]
+    encrypted: bool,
+    /// Leave out the checksum of the data from the envelope
+    #[cfg(feature = "envelope")]
+    #[arg(long, requires("envelope"))]
+    no_checksum: bool,
+}
+
+#[cfg(feature = "fec")]
+#[derive(clap::Args)]
+struct CliFec {
+    /// Add PARITY_WORDS Reed-Solomon parity words to each block of encoded data,
+    /// or correct decoded data using them. Words that could not be read back
+    /// can be replaced by ? in the input to the decoder
+    #[arg(long, value_name = "PARITY_WORDS")]
+    fec: Option<usize>,
+    /// Number of data words in each block protected by forward error correction
+    #[arg(long, value_name = "DATA_WORDS", default_value_t = 32, requires("fec"))]
+    fec_block: usize,
+}
+
+#[cfg(feature = "encode")]
+#[derive(clap::Args, Clone, Copy, Default)]
+struct CliLayout {
+    /// Wrap lines of encoded words at WIDTH characters, margin included
+    #[arg(long, value_name = "WIDTH")]
+    width: Option<NonZeroUsize>,
+    /// Put at most N encoded words on each line
+    #[arg(long, value_name = "N")]
+    words_per_line: Option<NonZeroUsize>,
+    /// Leave an extra gap after every N encoded words
+    #[arg(long, value_name = "N")]
+    group: Option<NonZeroUsize>,
+    /// Number the lines of encoded words in the left margin
+    #[arg(long)]
+    line_numbers: bool,
+    /// Show the offset in the data of the first byte on each line, in hex, in the left margin
+    #[arg(long)]
+    byte_offsets: bool,
+    /// Letter case of the encoded words
+    #[arg(long, value_name = "CASE", default_value = "as-listed")]
+    case: WordCase,
+    /// Separator between encoded words. Default is space, or none with --case camel
+    #[arg(long, value_name = "SEPARATOR")]
+    separator: Option<WordSeparator>,
+    /// Write a script for reading the encoded words aloud, in groups of four words
+    /// unless --group is given
+    #[arg(long, value_name = "FORMAT", conflicts_with_all(["width", "words_per_line", "line_numbers", "byte_offsets", "case", "separator"]))]
+    speech: Option<SpeechFormat>,
+    /// Spell out words with the NATO phonetic alphabet in the speech script
+    #[arg(
+        long,
+        value_name = "WORDS",
+        default_value = "never",
+        requires("speech")
+    )]
+    spell_out: SpellOut,
+}
+
+#[cfg(feature = "encode")]
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+enum WordCase {
+    /// As written in the wordlist, for example Dupont and adult. The default case
+    #[default]
+    AsListed,
+    /// All lowercase
+    Lower,
+    /// All uppercase
+    Upper,
+    /// First letter of each word uppercase
+    Title,
+    /// First letter of each word uppercase, with no separator by default
+    Camel,
+}
+
+#[cfg(feature = "encode")]
+impl WordCase {
+    fn apply(self, word: &str) -> String {
+        match self {
+            WordCase::AsListed => word.to_string(),
+            WordCase::Lower => word.to_lowercase(),
+            WordCase::Upper => word.to_uppercase(),
+            WordCase::Title | WordCase::Camel => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first
+                        .to_uppercase()
+                        .chain(chars.flat_map(char::to_lowercase))
+                        .collect(),
+                    None => String::new(),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "encode")]
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum WordSeparator {
+    /// Words separated by spaces
+    Space,
+    /// Words separated by hyphens
+    Hyphen,
+    /// Each word on a line of its own
+    Newline,
+    /// Words written together with nothing in between
+    None,
+}
+
+#[cfg(feature = "encode")]
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum SpeechFormat {
+    /// Plain text for a person to read aloud
+    Script,
+    /// SSML for text-to-speech
+    Ssml,
+}
+
+#[cfg(feature = "encode")]
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+enum SpellOut {
+    /// Spell out no words. The default
+    #[default]
+    Never,
+    /// Spell out proper names, like Dupont
+    Names,
+    /// Spell out every word
+    All,
+}
+
+#[cfg(feature = "encode")]
+impl CliLayout {
+    fn speech_params(&self) -> Option<base256::SpeechParams> {
+        let format = match self.speech? {
+            SpeechFormat::Script => base256::SpeechFormat::Script,
+            SpeechFormat::Ssml => base256::SpeechFormat::Ssml,
+        };
+        let spell_out = match self.spell_out {
+            SpellOut::Never => base256::SpellOut::Never,
+            SpellOut::Names => base256::SpellOut::Names,
+            SpellOut::All => base256::SpellOut::Always,
+        };
+        let defaults = base256::SpeechParams::default();
+        Some(base256::SpeechParams {
+            format,
+            group: self.group.map_or(defaults.group, NonZeroUsize::get),
+            spell_out,
+        })
+    }
+}
+
+#[cfg(feature = "fec")]
+impl CliFec {
+    fn params(&self) -> Result<Option<FecParams>> {
+        match self.fec {
+            Some(parity_len) => Ok(Some(FecParams::new(self.fec_block, parity_len)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Encode data as numbered parts, each in its own envelope
+    #[cfg(all(feature = "envelope", feature = "encode"))]
+    Split(SplitArgs),
+    /// Decode and reassemble data from parts made with split, given in any order
+    #[cfg(all(feature = "envelope", feature = "decode"))]
+    Join(JoinArgs),
+    /// Convert words from one codec to another, keeping the line layout of the input
+    #[cfg(all(feature = "encode", feature = "decode"))]
+    Transcode(TranscodeArgs),
+    /// Check the quality of a candidate wordlist of 256 words
+    WordlistCheck(WordlistCheckArgs),
+    /// Type in words on the terminal, checking each word as it is typed,
+    /// and decode them once all of them check out
+    #[cfg(all(feature = "encode", feature = "decode"))]
+    Type(TypeArgs),
+    /// Check a transcript, word by word, against the file it was made from
+    #[cfg(all(feature = "encode", feature = "decode"))]
+    Verify(VerifyArgs),
+    /// Compare two transcripts of the same data word by word
+    #[cfg(feature = "decode")]
+    Diff(DiffArgs),
+    /// List every word of a transcript that cannot be decoded, with suggestions
+    #[cfg(feature = "decode")]
+    Lint(LintArgs),
+}
+
+#[derive(clap::Args)]
+struct WordlistCheckArgs {
+    /// File with one word per line. Blank lines and lines starting with # are skipped.
+    /// Passing - represents stdin
+    #[arg(value_name = "WORDLIST_FILE")]
+    file: String,
+}
+
+#[cfg(all(feature = "encode", feature = "decode"))]
+#[derive(clap::Args)]
+struct TranscodeArgs {
+    /// Codec of the input
+    #[arg(long, value_name = "DECODER")]
+    from: Decoder,
+    /// Codec of the output
+    #[arg(long, value_name = "ENCODER")]
+    to: Encoder,
+    /// Read input from INPUT_FILE. Default is stdin; passing - also represents stdin
+    #[arg(short, long, value_name = "INPUT_FILE")]
+    input: Option<String>,
+    /// Write output to OUTPUT_FILE. Default is stdout; passing - also represents stdout
+    #[arg(short, long, value_name = "OUTPUT_FILE")]
+    output: Option<String>,
+    /// Fail instead of replacing OUTPUT_FILE if it already exists
+    #[arg(long)]
+    no_clobber: bool,
+}
+
+#[cfg(all(feature = "encode", feature = "decode"))]
+#[derive(clap::Args)]
+struct TypeArgs {
+    /// Decoder of the words. Default is pgp
+    #[arg(short, long, value_name = "DECODER")]
+    decoder: Option<Decoder>,
+    /// Read the 256 words of the custom decoder from WORDLIST_FILE, one word per line
+    #[arg(long, value_name = "WORDLIST_FILE")]
+    wordlist: Option<String>,
+    /// The words are in an envelope, whose header tells how many words there are
+    #[cfg(feature = "envelope")]
+    #[arg(long)]
+    envelope: bool,
+    #[cfg(feature = "fec")]
+    #[command(flatten)]
+    fec: CliFec,
+    /// Write output to OUTPUT_FILE. Default is stdout; passing - also represents stdout
+    #[arg(short, long, value_name = "OUTPUT_FILE")]
+    output: Option<String>,
+    /// Fail instead of replacing OUTPUT_FILE if it already exists
+    #[arg(long)]
+    no_clobber: bool,
+    /// Format to write the decoded data in
+    #[arg(long, value_name = "FORMAT", default_value = "raw")]
+    output_format: OutputFormat,
+}
+
+#[cfg(all(feature = "encode", feature = "decode"))]
+#[derive(clap::Args)]
+struct VerifyArgs {
+    /// The file that the transcript was made from
+    #[arg(long, value_name = "ORIGINAL_FILE")]
+    against: String,
+    /// Decoder of the words. Default is pgp
+    #[arg(short, long, value_name = "DECODER")]
+    decoder: Option<Decoder>,
+    /// Read the 256 words of the custom decoder from WORDLIST_FILE, one word per line
+    #[arg(long, value_name = "WORDLIST_FILE")]
+    wordlist: Option<String>,
+    /// The transcript is in an envelope, whose header is checked against the original as well
+    #[cfg(feature = "envelope")]
+    #[arg(long)]
+    envelope: bool,
+    #[cfg(feature = "fec")]
+    #[command(flatten)]
+    fec: CliFec,
+    /// Read the transcript from INPUT_FILE. Default is stdin; passing - also represents stdin
+    #[arg(short, long, value_name = "INPUT_FILE")]
+    input: Option<String>,
+}
+
+#[cfg(feature = "decode")]
+#[derive(clap::Args)]
+struct DiffArgs {
+    /// The first transcript
+    #[arg(value_name = "FILE_A")]
+    a: String,
+    /// The second transcript
+    #[arg(value_name = "FILE_B")]
+    b: String,
+    /// Decoder of the words. Default is pgp
+    #[arg(short, long, value_name = "DECODER")]
+    decoder: Option<Decoder>,
+    /// Read the 256 words of the custom decoder from WORDLIST_FILE, one word per line
+    #[arg(long, value_name = "WORDLIST_FILE")]
+    wordlist: Option<String>,
+}
+
+#[cfg(feature = "decode")]
+#[derive(clap::Args)]
+struct LintArgs {
+    /// Decoder of the words. Default is pgp
+    #[arg(short, long, value_name = "DECODER")]
+    decoder: Option<Decoder>,
+    /// Read the 256 words of the custom decoder from WORDLIST_FILE, one word per line
+    #[arg(long, value_name = "WORDLIST_FILE")]
+    wordlist: Option<String>,
+    /// Read the transcript from INPUT_FILE. Default is stdin; passing - also represents stdin
+    #[arg(short, long, value_name = "INPUT_FILE")]
+    input: Option<String>,
+    /// Format of the report
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    format: LintFormat,
+}
+
+#[cfg(feature = "decode")]
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum LintFormat {
+    /// One line for each problem. The default format
+    Text,
+    /// A JSON object with the number of words and a list of the problems
+    Json,
+}
+
+#[cfg(all(feature = "envelope", feature = "encode"))]
+#[derive(clap::Args)]
+struct SplitArgs {
+    /// Split the data into N parts of nearly equal size
+    #[arg(long, value_name = "N", required_unless_present("part_words"))]
+    parts: Option<usize>,
+    /// Split the data into as few parts as possible, with at most N words in each part
+    #[arg(long, value_name = "N", conflicts_with("parts"))]
+    part_words: Option<usize>,
+    /// Encoder to use
+    #[arg(short, long)]
+    encoder: Option<Encoder>,
+    /// Record the type of the data, for example openssh-private-key, in each part
+    #[arg(long, value_name = "CONTENT_TYPE")]
+    content_type: Option<String>,
+    /// Format of the data to encode
+    #[arg(long, value_name = "FORMAT", default_value = "raw")]
+    input_format: InputFormat,
+    #[cfg(feature = "fec")]
+    #[command(flatten)]
+    fec: CliFec,
+    #[command(flatten)]
+    layout: CliLayout,
+    /// Read input from INPUT_FILE. Default is stdin; passing - also represents stdin
+    #[arg(short, long, value_name = "INPUT_FILE")]
+    input: Option<String>,
+    /// Write part N of M to OUTPUT_PREFIX-N-of-M.txt.
+    /// Default is to write all parts to stdout, separated by blank lines
+    #[arg(short, long, value_name = "OUTPUT_PREFIX")]
+    output: Option<String>,
+    /// Fail instead of replacing the file of a part if it already exists
+    #[arg(long)]
+    no_clobber: bool,
+}
+
+#[cfg(all(feature = "envelope", feature = "decode"))]
+#[derive(clap::Args)]
+struct JoinArgs {
+    /// Files containing the parts. Parts in the same file are separated by blank lines.
+    /// Default is stdin; passing - also represents stdin
+    #[arg(value_name = "INPUT_FILE")]
+    inputs: Vec<String>,
+    /// Write output to OUTPUT_FILE. Default is stdout; passing - also represents stdout
+    #[arg(short, long, value_name = "OUTPUT_FILE")]
+    output: Option<String>,
+    /// Fail instead of replacing OUTPUT_FILE if it already exists
+    #[arg(long)]
+    no_clobber: bool,
+    /// Format to write the decoded data in
+    #[arg(long, value_name = "FORMAT", default_value = "raw")]
+    output_format: OutputFormat,
 }
 
 /*
@@ -105,7 +578,7 @@ struct CliDecoding {
      * - The "-d" flag when used takes a REQUIRED argument to specify which decoder to use.
      */
     /// Decode data (default action is to encode data)
-    #[cfg(all(feature = "decode_eff", not(feature = "decode_pgp")))]
+    #[cfg(all(feature = "decode", not(feature = "decode_pgp")))]
     #[arg(short, long, value_name = "DECODER", conflicts_with("encoder"))]
     #[arg(required = false)]
     decode: Option<Decoder>,
@@ -137,7 +610,7 @@ struct CliDecoding {
      * - The "-d" flag takes a REQUIRED argument to specify which decoder to use.
      */
     /// Decode data
-    #[cfg(all(feature = "decode_eff", not(feature = "decode_pgp")))]
+    #[cfg(all(feature = "decode", not(feature = "decode_pgp")))]
     #[arg(short, long, value_name = "DECODER")]
     decode: Decoder,
 }
@@ -167,7 +640,7 @@ struct CliEncoding {
      * - The "-e" flag takes a REQUIRED argument to specify which decoder to use.
      */
     /// Encoder to use
-    #[cfg(all(feature = "encode_eff", not(feature = "encode_pgp")))]
+    #[cfg(all(feature = "encode", not(feature = "encode_pgp")))]
     #[arg(
         short,
         long,
@@ -202,7 +675,7 @@ struct CliEncoding {
      * - The "-e" flag takes a REQUIRED argument to specify which decoder to use.
      */
     /// Encoder to use
-    #[cfg(all(feature = "encode_eff", not(feature = "encode_pgp")))]
+    #[cfg(all(feature = "encode", not(feature = "encode_pgp")))]
     #[arg(short, long)]
     encoder: Encoder,
 }
@@ -216,6 +689,34 @@ enum Encoder {
     /// EFF Short Wordlist 2.0. The legacy encoder
     #[cfg(feature = "encode_eff")]
     Eff,
+    /// Bytewords, four-letter words separated by spaces, with a checksum
+    #[cfg(feature = "encode_bytewords")]
+    Bytewords,
+    /// Bytewords separated by hyphens, for use in URIs
+    #[cfg(feature = "encode_bytewords")]
+    BytewordsUri,
+    /// Bytewords in two-letter form, written together
+    #[cfg(feature = "encode_bytewords")]
+    BytewordsMinimal,
+    /// RFC 1751 (S/KEY), six words for each block of eight bytes;
+    /// other lengths only with --envelope
+    #[cfg(feature = "encode_rfc1751")]
+    Rfc1751,
+    /// EFF Short Wordlist 2.0, all 1296 words, nine bytes for every seven words
+    #[cfg(feature = "encode_eff_full")]
+    EffFull,
+    /// Proquints, five-letter words for each pair of bytes, separated by hyphens;
+    /// odd lengths only with --envelope
+    #[cfg(feature = "encode_proquint")]
+    Proquint,
+    /// Norwegian words, written with æ, ø and å
+    #[cfg(feature = "encode_norwegian")]
+    Norwegian,
+    /// German words, written with ä, ö, ü and ß
+    #[cfg(feature = "encode_german")]
+    German,
+    /// Words of your own, 256 of them, read from the file given with --wordlist
+    Custom,
 }
 
 #[cfg(feature = "decode")]
@@ -227,135 +728,1913 @@ enum Decoder {
     /// EFF Short Wordlist 2.0. The legacy decoder
     #[cfg(feature = "decode_eff")]
     Eff,
+    /// Bytewords, in the standard or URI style
+    #[cfg(feature = "decode_bytewords")]
+    #[value(alias("bytewords-uri"))]
+    Bytewords,
+    /// Bytewords in two-letter form
+    #[cfg(feature = "decode_bytewords")]
+    BytewordsMinimal,
+    /// RFC 1751 (S/KEY), six words for each block of eight bytes
+    #[cfg(feature = "decode_rfc1751")]
+    Rfc1751,
+    /// EFF Short Wordlist 2.0, all 1296 words
+    #[cfg(feature = "decode_eff_full")]
+    EffFull,
+    /// Proquints, five-letter words for each pair of bytes
+    #[cfg(feature = "decode_proquint")]
+    Proquint,
+    /// Norwegian words, with or without æ, ø and å
+    #[cfg(feature = "decode_norwegian")]
+    Norwegian,
+    /// German words, with or without ä, ö, ü and ß
+    #[cfg(feature = "decode_german")]
+    German,
+    /// Words of your own, 256 of them, read from the file given with --wordlist
+    Custom,
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+#[cfg(feature = "encode")]
+#[derive(ValueEnum, Clone, Copy)]
+enum InputFormat {
+    /// Raw bytes. The default format
+    Raw,
+    /// Hexadecimal digits, as printed by xxd -p. Whitespace is ignored
+    Hex,
+    /// Base 64, with either the standard or the URL-safe alphabet. Whitespace is ignored
+    Base64,
+    /// Base 32, case-insensitive. Whitespace is ignored
+    Base32,
+}
 
-    let mut input: Box<dyn BufRead> = match cli.input {
-        None => Box::new(stdin().lock()),
-        Some(path) => {
-            if path == "-" {
-                Box::new(stdin().lock())
-            } else {
-                Box::new(BufReader::new(File::open(path)?))
-            }
+#[cfg(feature = "decode")]
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    /// Raw bytes. The default format
+    Raw,
+    /// Lowercase hexadecimal digits, 32 bytes per line
+    Hex,
+    /// Base 64 with padding, 76 characters per line
+    Base64,
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+#[cfg(feature = "encode")]
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[cfg(feature = "encode")]
+/// Convert text of digits in the given base, with `bits` bits per digit, to bytes.
+/// Whitespace and trailing padding characters are ignored.
+fn parse_digits(
+    text: &str,
+    format: &str,
+    bits: u32,
+    digit_value: impl Fn(u8) -> Option<u8>,
+) -> Result<Vec<u8>> {
+    let digits = text
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    let padding = digits.iter().rev().take_while(|&&b| b == b'=').count();
+    let digits = &digits[..digits.len() - padding];
+
+    let mut bytes = Vec::with_capacity(digits.len() * bits as usize / 8);
+    let (mut acc, mut acc_bits) = (0u32, 0u32);
+    for (i, &digit) in digits.iter().enumerate() {
+        let Some(value) = digit_value(digit) else {
+            anyhow::bail!(
+                "invalid {format} input: unexpected character {:?} at digit {}",
+                digit as char,
+                i + 1
+            );
+        };
+        acc = (acc << bits) | value as u32;
+        acc_bits += bits;
+        if acc_bits >= 8 {
+            acc_bits -= 8;
+            bytes.push((acc >> acc_bits) as u8);
+            acc &= (1 << acc_bits) - 1;
         }
-    };
+    }
+    // Leftover bits must be fewer than a whole digit and all zero.
+    if acc_bits >= bits || acc != 0 {
+        anyhow::bail!(
+            "invalid {format} input: truncated after {} digits",
+            digits.len()
+        );
+    }
+    Ok(bytes)
+}
 
-    let mut output: Box<dyn Write> = match cli.output {
-        None => Box::new(stdout()),
-        Some(path) => {
-            if path == "-" {
-                Box::new(stdout())
-            } else {
-                let file = OpenOptions::new().create(true).write(true).open(path)?;
-                Box::new(BufWriter::new(file))
-            }
+#[cfg(feature = "encode")]
+fn parse_input(text: &str, format: InputFormat) -> Result<Vec<u8>> {
+    match format {
+        InputFormat::Raw => Ok(text.as_bytes().to_vec()),
+        InputFormat::Hex => parse_digits(text, "hex", 4, |b| {
+            (b as char).to_digit(16).map(|d| d as u8)
+        }),
+        InputFormat::Base64 => parse_digits(text, "base64", 6, |b| match b {
+            b'-' => Some(62),
+            b'_' => Some(63),
+            _ => BASE64_ALPHABET
+                .iter()
+                .position(|&c| c == b)
+                .map(|p| p as u8),
+        }),
+        InputFormat::Base32 => parse_digits(text, "base32", 5, |b| {
+            let b = b.to_ascii_uppercase();
+            BASE32_ALPHABET
+                .iter()
+                .position(|&c| c == b)
+                .map(|p| p as u8)
+        }),
+    }
+}
+
+/// Read the data to encode in the given format.
+#[cfg(feature = "encode")]
+fn read_input_bytes<'a>(
+    input: &'a mut dyn BufRead,
+    format: InputFormat,
+) -> Result<Box<dyn Iterator<Item = std::io::Result<u8>> + 'a>> {
+    match format {
+        InputFormat::Raw => Ok(Box::new(input.bytes())),
+        _ => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            Ok(Box::new(parse_input(&text, format)?.into_iter().map(Ok)))
         }
-    };
+    }
+}
 
-    #[cfg(feature = "decode")]
-    {
-        #[cfg(not(any(feature = "decode_pgp", feature = "decode_eff")))]
-        compile_error!("Building bin target with decoding feature enabled requires that at least one decoder is enabled");
+/// Writes decoded data in the chosen output format.
+/// The output is complete only once [`FormattedOutput::finish`] has been called.
+#[cfg(feature = "decode")]
+struct FormattedOutput<W: Write> {
+    inner: W,
+    format: OutputFormat,
+    pending: Vec<u8>,
+    line_len: usize,
+}
 
-        let decoder = cli.decoding.decode;
+#[cfg(feature = "decode")]
+impl<W: Write> FormattedOutput<W> {
+    fn new(inner: W, format: OutputFormat) -> Self {
+        Self {
+            inner,
+            format,
+            pending: vec![],
+            line_len: 0,
+        }
+    }
 
-        #[cfg(all(
-            feature = "decode_eff",
-            not(feature = "decode_pgp"),
-            not(feature = "encode")
-        ))]
-        let decoder = Some(Some(decoder)); // TODO: Something less messy for this feature set
+    fn write_text(&mut self, text: &[u8], max_line_len: usize) -> std::io::Result<()> {
+        for &c in text {
+            if self.line_len == max_line_len {
+                self.inner.write_all(b"\n")?;
+                self.line_len = 0;
+            }
+            self.inner.write_all(&[c])?;
+            self.line_len += 1;
+        }
+        Ok(())
+    }
 
-        if let Some(decoder) = decoder {
-            let input_chars = input.chars();
+    fn write_base64_group(&mut self, group: &[u8]) -> std::io::Result<()> {
+        let n = group
+            .iter()
+            .chain(std::iter::repeat(&0))
+            .take(3)
+            .fold(0u32, |acc, &b| (acc << 8) | b as u32);
+        let mut text = [b'='; 4];
+        for (i, c) in text.iter_mut().enumerate().take(group.len() + 1) {
+            *c = BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize];
+        }
+        self.write_text(&text, 76)
+    }
 
-            // If support for the PGP decoder was compiled, then it is the default decoder..
-            #[cfg(feature = "decode_pgp")]
-            let decoder = decoder.unwrap_or(Decoder::Pgp);
-            // ..otherwise, the decoder has to be provided as a cli arg.
-            #[cfg(not(any(feature = "decode_pgp", feature = "encode")))]
-            let decoder = match decoder {
-                Some(decoder) => decoder,
-                None => {
-                    unreachable!("This match arm should never be reached due to clap parse rules.");
-                }
-            };
+    /// Write what is left of the output, and give back where it was written to.
+    fn finish(mut self) -> std::io::Result<W> {
+        if self.format == OutputFormat::Base64 && !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            self.write_base64_group(&pending)?;
+        }
+        if self.line_len > 0 {
+            self.inner.write_all(b"\n")?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
 
-            match decoder {
-                #[cfg(feature = "decode_pgp")]
-                Decoder::Pgp => {
-                    for byte in Decode::<_, PgpDecode<_>>::decode(input_chars) {
-                        output.write_all(&[byte?])?;
-                    }
+#[cfg(feature = "decode")]
+impl<W: Write> Write for FormattedOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.format {
+            OutputFormat::Raw => self.inner.write_all(buf)?,
+            OutputFormat::Hex => {
+                for byte in buf {
+                    self.write_text(format!("{byte:02x}").as_bytes(), 64)?;
                 }
-                #[cfg(feature = "decode_eff")]
-                Decoder::Eff => {
-                    for byte in Decode::<_, EffDecode<_>>::decode(input_chars) {
-                        output.write_all(&[byte?])?;
-                    }
+            }
+            OutputFormat::Base64 => {
+                self.pending.extend(buf);
+                let whole_groups = self.pending.len() / 3 * 3;
+                let pending: Vec<u8> = self.pending.drain(..whole_groups).collect();
+                for group in pending.chunks(3) {
+                    self.write_base64_group(group)?;
                 }
             }
-            return Ok(());
         }
+        Ok(buf.len())
     }
 
-    #[cfg(feature = "encode")]
-    {
-        let input_bytes = input.bytes();
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
 
-        #[cfg(not(any(feature = "encode_pgp", feature = "encode_eff")))]
-        compile_error!("Building bin target with encoding feature enabled requires that at least one encoder is enabled");
+/// Writes encoded words laid out in lines, with an optional left margin.
+/// The output is complete only once [`WordLayout::finish`] has been called.
+#[cfg(feature = "encode")]
+struct WordLayout<W: Write> {
+    inner: W,
+    layout: CliLayout,
+    /// Offset in the data of each word, for the margin, as from [`word_offsets`].
+    offsets: Vec<Option<usize>>,
+    words: usize,
+    lines: usize,
+    line_words: usize,
+    line_len: usize,
+}
 
-        let encoder = cli.encoding.encoder;
+#[cfg(feature = "encode")]
+impl<W: Write> WordLayout<W> {
+    fn new(inner: W, layout: CliLayout, offsets: Vec<Option<usize>>) -> Self {
+        Self {
+            inner,
+            layout,
+            offsets,
+            words: 0,
+            lines: 0,
+            line_words: 0,
+            line_len: 0,
+        }
+    }
 
-        // If support for the PGP encoder was compiled, then it is the default encoder..
-        #[cfg(feature = "encode_pgp")]
-        let encoder = encoder.unwrap_or(Encoder::Pgp);
-        // ..otherwise, the encoder has to be provided as a cli arg.
-        #[cfg(all(feature = "decode", not(feature = "encode_pgp")))]
-        let encoder = match encoder {
-            Some(encoder) => encoder,
-            None => {
-                unreachable!("This match arm should never be reached due to clap parse rules.");
-            }
+    fn write_word(&mut self, word: &str) -> std::io::Result<()> {
+        let word = self.layout.case.apply(word);
+        let word_len = word.chars().count();
+        let separator = match self.layout.separator {
+            Some(WordSeparator::Space) => " ",
+            Some(WordSeparator::Hyphen) => "-",
+            Some(WordSeparator::Newline | WordSeparator::None) => "",
+            None if self.layout.case == WordCase::Camel => "",
+            None => " ",
         };
-        #[cfg(not(any(feature = "decode", feature = "encode_pgp")))]
-        let encoder = encoder;
-
-        let mut did_write_any_words = false;
+        // The extra gap between groups is the separator written twice.
+        let gap = match self.layout.group {
+            Some(group) if self.words.is_multiple_of(group.get()) => separator.repeat(2),
+            _ => separator.to_string(),
+        };
+        // A word longer than the width still gets a line of its own.
+        let line_full = self.line_words > 0
+            && (self.layout.separator == Some(WordSeparator::Newline)
+                || self
+                    .layout
+                    .words_per_line
+                    .is_some_and(|n| self.line_words == n.get())
+                || self
+                    .layout
+                    .width
+                    .is_some_and(|width| self.line_len + gap.len() + word_len > width.get()));
 
-        match encoder {
-            #[cfg(feature = "encode_pgp")]
-            Encoder::Pgp => {
-                let mut encoded = Encode::<_, PgpEncode<_>>::encode(input_bytes);
-                if let Some(word) = encoded.next() {
-                    did_write_any_words = true;
-                    write!(output, "{}", word?)?;
-                }
-                for word in encoded {
-                    write!(output, " {}", word?)?
-                }
+        if self.line_words == 0 || line_full {
+            if line_full {
+                self.inner.write_all(b"\n")?;
             }
-            #[cfg(feature = "encode_eff")]
-            Encoder::Eff => {
-                let mut encoded = Encode::<_, EffEncode<_>>::encode(input_bytes);
-                if let Some(word) = encoded.next() {
-                    did_write_any_words = true;
-                    write!(output, "{}", word?)?;
-                }
-                for word in encoded {
-                    write!(output, " {}", word?)?
-                }
+            self.lines += 1;
+            let mut margin = String::new();
+            if self.layout.line_numbers {
+                margin += &format!("{:>4}  ", self.lines);
+            }
+            if self.layout.byte_offsets {
+                // Lines starting with words that hold no data get a blank offset.
+                margin += &match self.offsets.get(self.words).copied().flatten() {
+                    Some(offset) => format!("{offset:04x}:  "),
+                    None => " ".repeat(7),
+                };
             }
+            self.inner.write_all(margin.as_bytes())?;
+            self.line_len = margin.len();
+            self.line_words = 0;
+        } else {
+            self.inner.write_all(gap.as_bytes())?;
+            self.line_len += gap.len();
         }
 
-        if did_write_any_words {
-            writeln!(output)?;
+        self.inner.write_all(word.as_bytes())?;
+        self.line_len += word_len;
+        self.line_words += 1;
+        self.words += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> std::io::Result<()> {
+        if self.words > 0 {
+            self.inner.write_all(b"\n")?;
         }
+        self.inner.flush()
     }
+}
 
-    Ok(())
+/// Offset in the data of the first byte of data that each word holds part of,
+/// given the position in the data of each byte encoded, if it is data.
+/// Words that hold no data, like those of an envelope header, of forward error correction
+/// and of the Bytewords checksum, have no offset.
+#[cfg(feature = "encode")]
+fn word_offsets(
+    word_span: impl Fn(usize) -> std::ops::RangeInclusive<usize>,
+    data_positions: &[Option<usize>],
+) -> Vec<Option<usize>> {
+    let mut offsets = vec![];
+    for (byte, &position) in data_positions.iter().enumerate() {
+        let span = word_span(byte);
+        if offsets.len() <= *span.end() {
+            offsets.resize(span.end() + 1, None);
+        }
+        for word in span {
+            offsets[word] = offsets[word].or(position);
+        }
+    }
+    offsets
+}
+
+/// Write encoded words laid out as described by `layout`, or as a speech script.
+/// `offsets` are the offsets in the data of the words, for the margin.
+#[cfg(feature = "encode")]
+fn write_words<'a, W: Write>(
+    output: W,
+    layout: CliLayout,
+    offsets: Vec<Option<usize>>,
+    words: impl Iterator<Item = std::io::Result<&'a str>>,
+) -> std::io::Result<()> {
+    if let Some(params) = layout.speech_params() {
+        let mut script = base256::SpeechScript::new(output, params);
+        for word in words {
+            script.write_word(word?)?;
+        }
+        script.finish()
+    } else {
+        let mut layout = WordLayout::new(output, layout, offsets);
+        for word in words {
+            layout.write_word(word?)?;
+        }
+        layout.finish()
+    }
+}
+
+/// Error for a custom wordlist where only codecs known to the library can be used,
+/// since the words are not known to whoever reads the envelope or the transcript.
+fn custom_unsupported() -> anyhow::Error {
+    anyhow::anyhow!(
+        "custom wordlists can only be used to encode and decode directly, not with envelopes, voice mode or transcoding"
+    )
+}
+
+/// Words to encode with or decode from: those of a codec, or a custom wordlist.
+enum Words {
+    Codec(Codec),
+    Custom(base256::Wordlist),
+}
+
+impl Words {
+    /// The words of `codec`. A wordlist file is only used with the custom encoder and decoder.
+    fn codec(codec: Codec, wordlist: Option<&str>) -> Result<Self> {
+        if wordlist.is_some() {
+            anyhow::bail!("--wordlist is only used with the custom encoder and decoder");
+        }
+        Ok(Words::Codec(codec))
+    }
+
+    /// The words of the wordlist file given with --wordlist.
+    fn custom(wordlist: Option<&str>) -> Result<Self> {
+        let Some(path) = wordlist else {
+            anyhow::bail!("the custom encoder and decoder need a wordlist, given with --wordlist");
+        };
+        let text = std::fs::read_to_string(path)?;
+        let wordlist = base256::Wordlist::parse(&text)
+            .map_err(|e| anyhow::anyhow!("invalid wordlist {path}: {e}"))?;
+        Ok(Words::Custom(wordlist))
+    }
+
+    #[cfg(feature = "encode")]
+    fn for_encoder(encoder: Encoder, wordlist: Option<&str>) -> Result<Self> {
+        match encoder {
+            Encoder::Custom => Words::custom(wordlist),
+            encoder => Words::codec(Codec::try_from(encoder)?, wordlist),
+        }
+    }
+
+    #[cfg(feature = "decode")]
+    fn for_decoder(decoder: Decoder, wordlist: Option<&str>) -> Result<Self> {
+        match decoder {
+            Decoder::Custom => Words::custom(wordlist),
+            decoder => Words::codec(Codec::try_from(decoder)?, wordlist),
+        }
+    }
+
+    /// The codec, for recording in an envelope header.
+    #[cfg(all(feature = "envelope", feature = "encode"))]
+    fn to_codec(&self) -> Result<Codec> {
+        match self {
+            Words::Codec(codec) => Ok(*codec),
+            Words::Custom(_) => Err(custom_unsupported()),
+        }
+    }
+
+    /// Indices of the words that hold the bits of the byte at index `byte_position`.
+    #[cfg(any(feature = "encode", all(feature = "fec", feature = "decode")))]
+    fn word_span(&self, byte_position: usize) -> std::ops::RangeInclusive<usize> {
+        match self {
+            Words::Codec(codec) => codec.word_span(byte_position),
+            Words::Custom(_) => byte_position..=byte_position,
+        }
+    }
+
+    /// Encode `bytes`, writing the words laid out as described by `layout`.
+    /// `data_positions` are the positions in the data of the bytes, for byte offsets.
+    #[cfg(feature = "encode")]
+    fn encode<W: Write>(
+        &self,
+        output: W,
+        layout: CliLayout,
+        data_positions: &[Option<usize>],
+        bytes: impl Iterator<Item = std::io::Result<u8>>,
+    ) -> Result<()> {
+        let offsets = word_offsets(|byte| self.word_span(byte), data_positions);
+        match self {
+            Words::Codec(codec) => {
+                let written = write_words(output, layout, offsets, codec.encoder(bytes)?);
+                // Only a partial block at the end of the input is invalid input to the encoder.
+                #[cfg(feature = "envelope")]
+                if let Err(e) = &written {
+                    if codec.block_len() > 1 && e.kind() == std::io::ErrorKind::InvalidInput {
+                        anyhow::bail!("{e}; with --envelope, the data is padded to whole blocks");
+                    }
+                }
+                written?
+            }
+            Words::Custom(wordlist) => {
+                write_words(output, layout, offsets, wordlist.encoder(bytes))?
+            }
+        }
+        Ok(())
+    }
+
+    /// The words that `bytes` are encoded as.
+    #[cfg(all(feature = "encode", feature = "decode"))]
+    fn encode_to_words(&self, bytes: &[u8]) -> Result<Vec<String>> {
+        let bytes = bytes.iter().copied().map(Ok::<_, std::io::Error>);
+        let words: std::io::Result<Vec<String>> = match self {
+            Words::Codec(codec) => codec.encoder(bytes)?.map(|w| w.map(String::from)).collect(),
+            Words::Custom(wordlist) => wordlist
+                .encoder(bytes)
+                .map(|w| w.map(String::from))
+                .collect(),
+        };
+        Ok(words?)
+    }
+
+    /// Typing the words one at a time.
+    #[cfg(feature = "decode")]
+    fn typist(&self) -> Result<base256::Typist> {
+        Ok(match self {
+            Words::Codec(codec) => base256::Typist::for_codec(*codec)?,
+            Words::Custom(wordlist) => base256::Typist::for_wordlist(wordlist),
+        })
+    }
+
+    #[cfg(feature = "decode")]
+    fn decoder<'a, I>(&'a self, chars: I) -> Result<Box<dyn DecodeSymbol + 'a>>
+    where
+        I: Iterator<Item = std::io::Result<char>> + 'a,
+    {
+        Ok(match self {
+            Words::Codec(codec) => codec.decoder(chars)?,
+            Words::Custom(wordlist) => Box::new(wordlist.decoder(chars)),
+        })
+    }
+}
+
+#[cfg(feature = "encode")]
+impl TryFrom<Encoder> for Codec {
+    type Error = anyhow::Error;
+
+    fn try_from(encoder: Encoder) -> Result<Self> {
+        Ok(match encoder {
+            #[cfg(feature = "encode_pgp")]
+            Encoder::Pgp => Codec::Pgp,
+            #[cfg(feature = "encode_eff")]
+            Encoder::Eff => Codec::Eff,
+            #[cfg(feature = "encode_bytewords")]
+            Encoder::Bytewords | Encoder::BytewordsUri => Codec::Bytewords,
+            #[cfg(feature = "encode_bytewords")]
+            Encoder::BytewordsMinimal => Codec::BytewordsMinimal,
+            #[cfg(feature = "encode_rfc1751")]
+            Encoder::Rfc1751 => Codec::Rfc1751,
+            #[cfg(feature = "encode_eff_full")]
+            Encoder::EffFull => Codec::EffFull,
+            #[cfg(feature = "encode_proquint")]
+            Encoder::Proquint => Codec::Proquint,
+            #[cfg(feature = "encode_norwegian")]
+            Encoder::Norwegian => Codec::Norwegian,
+            #[cfg(feature = "encode_german")]
+            Encoder::German => Codec::German,
+            Encoder::Custom => return Err(custom_unsupported()),
+        })
+    }
+}
+
+#[cfg(feature = "encode")]
+impl Encoder {
+    /// Separator between words that is part of the style of the encoder, if any.
+    fn separator(&self) -> Option<WordSeparator> {
+        match self {
+            #[cfg(feature = "encode_bytewords")]
+            Encoder::BytewordsUri => Some(WordSeparator::Hyphen),
+            #[cfg(feature = "encode_bytewords")]
+            Encoder::BytewordsMinimal => Some(WordSeparator::None),
+            #[cfg(feature = "encode_proquint")]
+            Encoder::Proquint => Some(WordSeparator::Hyphen),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "encode")]
+impl CliLayout {
+    /// The layout, with the separator of the encoder unless another one was given.
+    fn for_encoder(mut self, encoder: &Encoder) -> Self {
+        self.separator = self.separator.or(encoder.separator());
+        self
+    }
+}
+
+#[cfg(feature = "decode")]
+impl TryFrom<Decoder> for Codec {
+    type Error = anyhow::Error;
+
+    fn try_from(decoder: Decoder) -> Result<Self> {
+        Ok(match decoder {
+            #[cfg(feature = "decode_pgp")]
+            Decoder::Pgp => Codec::Pgp,
+            #[cfg(feature = "decode_eff")]
+            Decoder::Eff => Codec::Eff,
+            #[cfg(feature = "decode_bytewords")]
+            Decoder::Bytewords => Codec::Bytewords,
+            #[cfg(feature = "decode_bytewords")]
+            Decoder::BytewordsMinimal => Codec::BytewordsMinimal,
+            #[cfg(feature = "decode_rfc1751")]
+            Decoder::Rfc1751 => Codec::Rfc1751,
+            #[cfg(feature = "decode_eff_full")]
+            Decoder::EffFull => Codec::EffFull,
+            #[cfg(feature = "decode_proquint")]
+            Decoder::Proquint => Codec::Proquint,
+            #[cfg(feature = "decode_norwegian")]
+            Decoder::Norwegian => Codec::Norwegian,
+            #[cfg(feature = "decode_german")]
+            Decoder::German => Codec::German,
+            Decoder::Custom => return Err(custom_unsupported()),
+        })
+    }
+}
+
+/// Report words corrected by forward error correction on stderr.
+#[cfg(all(feature = "fec", feature = "decode"))]
+fn report_corrections(
+    word_span: impl Fn(usize) -> std::ops::RangeInclusive<usize>,
+    corrections: &[base256::FecCorrection],
+) {
+    // Bytes that share words, as in codecs with more than a byte per word, are reported once.
+    let mut reported = None;
+    for correction in corrections {
+        let span = word_span(correction.position);
+        let key = Some((span.clone(), correction.erased));
+        if reported == key {
+            continue;
+        }
+        reported = key;
+        eprintln!(
+            "lastresort: corrected {} {}",
+            if correction.erased {
+                "unreadable"
+            } else {
+                "wrong"
+            },
+            if span.start() == span.end() {
+                format!("word {}", span.start() + 1)
+            } else {
+                format!("words {} to {}", span.start() + 1, span.end() + 1)
+            }
+        );
+    }
+}
+
+/// Report uncertain substitutions made in voice mode on stderr, for checking,
+/// and fail unless they are accepted.
+#[cfg(any(
+    feature = "decode_eff",
+    feature = "decode_pgp",
+    feature = "decode_bytewords"
+))]
+fn check_substitutions(
+    substitutions: &[base256::VoiceSubstitution],
+    accept_uncertain: bool,
+) -> Result<()> {
+    let uncertain: Vec<_> = substitutions.iter().filter(|s| s.uncertain).collect();
+    for substitution in &uncertain {
+        eprintln!(
+            "lastresort: word {}: heard \"{}\", assuming {}",
+            substitution.position + 1,
+            substitution.heard,
+            substitution.word
+        );
+    }
+    match uncertain.len() {
+        0 => Ok(()),
+        _ if accept_uncertain => Ok(()),
+        1 => anyhow::bail!(
+            "1 word was replaced with uncertainty; if it is right, decode again with --accept-uncertain"
+        ),
+        n => anyhow::bail!(
+            "{n} words were replaced with uncertainty; if they are right, decode again with --accept-uncertain"
+        ),
+    }
+}
+
+/// Describe an envelope header on stderr.
+#[cfg(all(feature = "envelope", feature = "decode"))]
+fn report_header(header: &envelope::Header) {
+    let mut description = format!(
+        "lastresort: envelope version {}, codec {}, {} bytes",
+        envelope::ENVELOPE_VERSION,
+        header.codec,
+        header.payload_len
+    );
+    if let Some(content_type) = &header.content_type {
+        description += &format!(", content type {content_type}");
+    }
+    for (is_set, flag) in [
+        (header.checksum, "checksum"),
+        (header.compressed, "compressed"),
+        (header.encrypted, "encrypted"),
+    ] {
+        if is_set {
+            description += &format!(", {flag}");
+        }
+    }
+    if let Some(fec) = header.fec {
+        description += &format!(
+            ", FEC {} parity words per {} data words",
+            fec.parity_len(),
+            fec.data_len()
+        );
+    }
+    eprintln!("{description}");
+}
+
+fn open_input(path: Option<&str>) -> Result<Box<dyn BufRead>> {
+    Ok(match path {
+        None | Some("-") => Box::new(stdin().lock()),
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+    })
+}
+
+/// How to write output to a file.
+#[derive(Clone, Copy, Default)]
+struct OutputOptions {
+    /// Fail instead of replacing a file that already exists.
+    no_clobber: bool,
+    /// Make the file readable and writable by its owner only, as for decoded data,
+    /// which may well be a private key.
+    private: bool,
+}
+
+/// Output to stdout or to a file.
+///
+/// A file is written to a temporary file next to it, which only takes its place once
+/// [`Output::commit`] is called, so that an error never leaves partial output behind.
+/// Likewise, output to stdout is held back until then, unless stdout is a terminal.
+enum Output {
+    /// Stdout that is a terminal, written to as the output comes.
+    Stdout(std::io::Stdout),
+    /// Stdout that is not a terminal, like a pipe, with the output held back.
+    BufferedStdout(Vec<u8>),
+    File(OutputFile),
+    /// A path that is not a regular file, like a device or a named pipe, written to directly.
+    Direct(BufWriter<File>),
+}
+
+struct OutputFile {
+    file: BufWriter<File>,
+    path: PathBuf,
+    temp_path: PathBuf,
+    no_clobber: bool,
+    committed: bool,
+}
+
+impl Output {
+    /// Finish writing, putting the file in place of the output file.
+    fn commit(self) -> std::io::Result<()> {
+        match self {
+            Output::Stdout(mut stdout) => stdout.flush(),
+            Output::BufferedStdout(buffer) => {
+                let mut stdout = stdout();
+                stdout.write_all(&buffer)?;
+                stdout.flush()
+            }
+            Output::Direct(mut file) => file.flush(),
+            Output::File(mut output) => {
+                output.file.flush()?;
+                output.file.get_ref().sync_all()?;
+                if output.no_clobber {
+                    // Unlike a rename, a link fails if a file has taken the path in the meantime.
+                    match std::fs::hard_link(&output.temp_path, &output.path) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                            return Err(clobber_error(&output.path));
+                        }
+                        // File systems without hard links, like FAT, get a copy instead.
+                        Err(_) => copy_to_new(&output.temp_path, &output.path)?,
+                    }
+                    std::fs::remove_file(&output.temp_path)?;
+                } else {
+                    std::fs::rename(&output.temp_path, &output.path)?;
+                }
+                output.committed = true;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Output::Stdout(stdout) => stdout.write(buf),
+            Output::BufferedStdout(buffer) => buffer.write(buf),
+            Output::File(output) => output.file.write(buf),
+            Output::Direct(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Output::Stdout(stdout) => stdout.flush(),
+            Output::BufferedStdout(_) => Ok(()),
+            Output::File(output) => output.file.flush(),
+            Output::Direct(file) => file.flush(),
+        }
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+fn clobber_error(path: &Path) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        format!(
+            "{} already exists, and --no-clobber was given",
+            path.display()
+        ),
+    )
+}
+
+/// Copy `from` to a new file at `to`, which fails if a file is already there.
+fn copy_to_new(from: &Path, to: &Path) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    // The copy is only readable by others once it has the permissions of `from`.
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(to).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => clobber_error(to),
+        _ => e,
+    })?;
+    let mut copy = || {
+        std::io::copy(&mut File::open(from)?, &mut file)?;
+        file.set_permissions(std::fs::metadata(from)?.permissions())?;
+        file.sync_all()
+    };
+    let copied = copy();
+    if copied.is_err() {
+        let _ = std::fs::remove_file(to);
+    }
+    copied
+}
+
+fn open_output(path: Option<&str>, options: OutputOptions) -> Result<Output> {
+    let Some(path) = path.filter(|&path| path != "-") else {
+        let stdout = stdout();
+        if stdout.is_terminal() {
+            return Ok(Output::Stdout(stdout));
+        }
+        return Ok(Output::BufferedStdout(vec![]));
+    };
+    let path = Path::new(path);
+    let existing = match std::fs::metadata(path) {
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    if options.no_clobber && existing.is_some() {
+        return Err(clobber_error(path).into());
+    }
+    if existing
+        .as_ref()
+        .is_some_and(|metadata| !metadata.is_file())
+    {
+        let file = OpenOptions::new().write(true).truncate(true).open(path)?;
+        return Ok(Output::Direct(BufWriter::new(file)));
+    }
+
+    // The output file replaces the file that a symbolic link points to, not the link.
+    let path = match existing {
+        Some(_) => std::fs::canonicalize(path)?,
+        None => path.to_path_buf(),
+    };
+    let Some(name) = path.file_name() else {
+        anyhow::bail!("{} is not a file name", path.display());
+    };
+    let temp_path = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    let mut temp_options = OpenOptions::new();
+    temp_options.write(true).create_new(true);
+    #[cfg(unix)]
+    if options.private {
+        std::os::unix::fs::OpenOptionsExt::mode(&mut temp_options, 0o600);
+    }
+    let file = temp_options.open(&temp_path)?;
+    let output = OutputFile {
+        file: BufWriter::new(file),
+        path,
+        temp_path,
+        no_clobber: options.no_clobber,
+        committed: false,
+    };
+    // A file that is replaced keeps its permissions, unless the output is private.
+    if let Some(metadata) = existing.filter(|_| !options.private) {
+        output
+            .file
+            .get_ref()
+            .set_permissions(metadata.permissions())?;
+    }
+    Ok(Output::File(output))
+}
+
+#[cfg(all(feature = "envelope", feature = "encode"))]
+fn split(args: SplitArgs) -> Result<()> {
+    let payload = read_input_bytes(&mut open_input(args.input.as_deref())?, args.input_format)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let layout = match &args.encoder {
+        Some(encoder) => args.layout.for_encoder(encoder),
+        None => args.layout,
+    };
+    let codec = match args.encoder {
+        Some(encoder) => Codec::try_from(encoder)?,
+        None => Codec::Pgp,
+    };
+    let mut header = envelope::Header::new(codec, 0);
+    header.content_type = args.content_type;
+    #[cfg(feature = "fec")]
+    {
+        header.fec = args.fec.params()?;
+    }
+
+    let count = match (args.parts, args.part_words) {
+        (Some(count), _) => count,
+        (None, Some(max_words)) => {
+            envelope::part_count_for_words(&header, payload.len(), max_words)?
+        }
+        (None, None) => {
+            unreachable!("This match arm should never be reached due to clap parse rules.")
+        }
+    };
+    let parts = envelope::split(&header, &payload, count)?;
+
+    let mut stdout = open_output(None, OutputOptions::default())?;
+    // Byte offsets are offsets in the whole payload, not in the part.
+    let mut part_start = 0;
+    for (i, part) in parts.into_iter().enumerate() {
+        let part_header = envelope::Header::read(&mut part.iter().copied().map(Ok))?;
+        let data_positions: Vec<_> = envelope::payload_positions(&part_header)?
+            .into_iter()
+            .map(|position| position.map(|position| part_start + position))
+            .collect();
+        part_start += part_header.payload_len as usize;
+        let mut words = vec![];
+        write_words(
+            &mut words,
+            layout,
+            word_offsets(|byte| codec.word_span(byte), &data_positions),
+            codec.encoder(part.into_iter().map(Ok::<_, std::io::Error>))?,
+        )?;
+        if let Some(prefix) = &args.output {
+            let path = format!("{prefix}-{}-of-{count}.txt", i + 1);
+            let options = OutputOptions {
+                no_clobber: args.no_clobber,
+                private: false,
+            };
+            let mut output = open_output(Some(&path), options)?;
+            output.write_all(&words)?;
+            output.commit()?;
+            eprintln!("lastresort: wrote part {} of {count} to {path}", i + 1);
+        } else {
+            if i > 0 {
+                writeln!(stdout)?;
+            }
+            stdout.write_all(&words)?;
+        }
+    }
+    stdout.commit()?;
+    Ok(())
+}
+
+#[cfg(all(feature = "envelope", feature = "decode"))]
+fn join(args: JoinArgs) -> Result<()> {
+    let inputs = if args.inputs.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        args.inputs
+    };
+
+    let mut parts = vec![];
+    for path in &inputs {
+        let mut text = String::new();
+        open_input(Some(path))?.read_to_string(&mut text)?;
+
+        // Parts in the same input are separated by blank lines.
+        let mut paragraphs = vec![String::new()];
+        for line in text.lines() {
+            if line.trim().is_empty() {
+                paragraphs.push(String::new());
+            } else {
+                let paragraph = paragraphs.last_mut().unwrap();
+                paragraph.push_str(line);
+                paragraph.push('\n');
+            }
+        }
+
+        for paragraph in paragraphs.iter().filter(|p| !p.is_empty()) {
+            let Some(codec) = envelope::detect_codec(paragraph) else {
+                anyhow::bail!("part in {path} does not start with an envelope header");
+            };
+            let opened = envelope::open(codec.decoder(paragraph.chars().map(Ok))?)?;
+            if let Some(part) = opened.header.part {
+                eprintln!(
+                    "lastresort: read part {} of {} from {path}",
+                    part.number, part.count
+                );
+            }
+            report_corrections(|position| codec.word_span(position), &opened.corrections);
+            parts.push(opened);
+        }
+    }
+
+    let payload = envelope::join(parts)?;
+    let options = OutputOptions {
+        no_clobber: args.no_clobber,
+        private: true,
+    };
+    let mut output = FormattedOutput::new(
+        open_output(args.output.as_deref(), options)?,
+        args.output_format,
+    );
+    output.write_all(&payload)?;
+    output.finish()?.commit()?;
+    Ok(())
+}
+
+#[cfg(all(feature = "encode", feature = "decode"))]
+fn transcode(args: TranscodeArgs) -> Result<()> {
+    let mut input = open_input(args.input.as_deref())?;
+    let options = OutputOptions {
+        no_clobber: args.no_clobber,
+        private: false,
+    };
+    let mut output = open_output(args.output.as_deref(), options)?;
+
+    let separator = match args.to.separator() {
+        Some(WordSeparator::Hyphen) => "-",
+        Some(WordSeparator::None) => "",
+        _ => " ",
+    };
+
+    // Words start new lines where the words they were transcoded from did.
+    let mut prev_line = None;
+    for word in base256::transcode(
+        Codec::try_from(args.from)?,
+        Codec::try_from(args.to)?,
+        input.chars(),
+    )? {
+        let word = word?;
+        match prev_line {
+            None => {}
+            Some(line) if word.source.line > line => {
+                output.write_all("\n".repeat(word.source.line - line).as_bytes())?
+            }
+            Some(_) => output.write_all(separator.as_bytes())?,
+        }
+        output.write_all(word.word.as_bytes())?;
+        prev_line = Some(word.source.line);
+    }
+    if prev_line.is_some() {
+        writeln!(output)?;
+    }
+    output.commit()?;
+    Ok(())
+}
+
+fn wordlist_check(args: WordlistCheckArgs) -> Result<()> {
+    let text = std::io::read_to_string(open_input(Some(&args.file))?)?;
+    let words: Vec<&str> = base256::Wordlist::lines(&text).collect();
+
+    let report = base256::WordlistReport::new(&words);
+    write!(stdout(), "{report}")?;
+    if !report.is_usable() {
+        anyhow::bail!(
+            "the words cannot be used as a wordlist, which needs 256 valid words that are read as different words"
+        );
+    }
+    Ok(())
+}
+
+/// Decode the words of `text` leniently, reporting every word that cannot be decoded on stderr.
+///
+/// Nothing is written unless all of the words are decoded,
+/// or forward error correction recovers the ones that are not.
+#[cfg(feature = "decode")]
+fn decode_lenient(
+    text: &str,
+    words: Words,
+    mut output: FormattedOutput<Output>,
+    #[cfg(feature = "fec")] fec: Option<FecParams>,
+    #[cfg(feature = "envelope")] envelope: bool,
+) -> Result<()> {
+    #[cfg(feature = "envelope")]
+    let words = match envelope::detect_codec(text) {
+        Some(codec) if envelope => Words::Codec(codec),
+        None if envelope => anyhow::bail!("input does not start with an envelope header"),
+        _ => words,
+    };
+    let decoded = base256::LenientDecode::read(words.typist()?, text.chars().map(Ok))?;
+    for error in &decoded.errors {
+        eprintln!("lastresort: {error}");
+    }
+
+    #[cfg(feature = "envelope")]
+    if envelope {
+        let opened = envelope::open(Symbols(decoded.symbols.iter()))?;
+        report_header(&opened.header);
+        report_corrections(|position| words.word_span(position), &opened.corrections);
+        output.write_all(&opened.payload)?;
+        output.finish()?.commit()?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "fec")]
+    if let Some(fec) = fec {
+        let mut fec_decoded = FecDecode::new(Symbols(decoded.symbols.iter()), fec);
+        let bytes = fec_decoded.by_ref().collect::<std::io::Result<Vec<_>>>()?;
+        report_corrections(
+            |position| words.word_span(position),
+            fec_decoded.corrections(),
+        );
+        output.write_all(&bytes)?;
+        output.finish()?.commit()?;
+        return Ok(());
+    }
+
+    match (decoded.errors.len(), decoded.bytes()) {
+        (0, Some(bytes)) => {
+            output.write_all(&bytes)?;
+            output.finish()?.commit()?;
+            Ok(())
+        }
+        (0, None) => anyhow::bail!("words marked as unreadable can only be recovered with --fec"),
+        (1, _) => anyhow::bail!("1 word could not be decoded"),
+        (errors, _) => anyhow::bail!("{errors} words could not be decoded"),
+    }
+}
+
+/// Decoder of the symbols of words that have already been read, like the words typed in type mode.
+#[cfg(all(feature = "decode", feature = "fec"))]
+struct Symbols<'a>(std::slice::Iter<'a, Option<u8>>);
+
+#[cfg(all(feature = "decode", feature = "fec"))]
+impl Iterator for Symbols<'_> {
+    type Item = std::io::Result<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.0.next()?.ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "word marked as unreadable")
+        }))
+    }
+}
+
+#[cfg(all(feature = "decode", feature = "fec"))]
+impl DecodeSymbol for Symbols<'_> {
+    fn next_symbol(&mut self) -> Option<std::io::Result<Option<u8>>> {
+        Some(Ok(*self.0.next()?))
+    }
+}
+
+#[cfg(all(feature = "encode", feature = "decode"))]
+fn verify(args: VerifyArgs) -> Result<()> {
+    let words = match args.decoder {
+        Some(decoder) => Words::for_decoder(decoder, args.wordlist.as_deref())?,
+        None => Words::codec(Codec::Pgp, args.wordlist.as_deref())?,
+    };
+    let mut original = vec![];
+    File::open(&args.against)?.read_to_end(&mut original)?;
+    let transcript =
+        base256::Transcript::read(words.typist()?, open_input(args.input.as_deref())?.chars())?;
+
+    // The words are compared with the words that the original would be encoded as,
+    // with the same forward error correction and envelope as the transcript.
+    #[cfg(feature = "envelope")]
+    let in_envelope = args.envelope;
+    #[cfg(all(feature = "fec", not(feature = "envelope")))]
+    let in_envelope = false;
+    #[cfg(feature = "fec")]
+    match args.fec.params()? {
+        Some(fec) if !in_envelope => {
+            original = FecEncode::new(original.into_iter().map(Ok), fec).collect::<Result<_>>()?;
+        }
+        _ => {}
+    }
+    #[cfg(feature = "envelope")]
+    if in_envelope {
+        words.to_codec()?;
+        let header =
+            envelope::Header::read(&mut Symbols(transcript.symbols().iter())).map_err(|e| {
+                anyhow::anyhow!("cannot read the envelope header of the transcript: {e}")
+            })?;
+        if header.payload_len as usize != original.len() {
+            anyhow::bail!(
+                "the envelope of the transcript holds {} bytes, but {} has {}",
+                header.payload_len,
+                args.against,
+                original.len()
+            );
+        }
+        original = envelope::seal(&header, &original)?;
+    }
+    let expected = words.encode_to_words(&original)?;
+    let mismatches = transcript.compare(&expected);
+
+    let mut stdout = stdout();
+    let Some(first) = mismatches.first() else {
+        writeln!(
+            stdout,
+            "The transcript matches {}: all {} words are as expected.",
+            args.against,
+            transcript.len()
+        )?;
+        return Ok(());
+    };
+    writeln!(stdout, "First mismatch: {first}")?;
+    writeln!(
+        stdout,
+        "{} of {} words do not match.",
+        mismatches.len(),
+        transcript
+            .len()
+            .max(mismatches.last().unwrap().position + 1)
+    )?;
+    anyhow::bail!("the transcript does not match {}", args.against)
+}
+
+#[cfg(feature = "decode")]
+fn diff(args: DiffArgs) -> Result<()> {
+    let words = match args.decoder {
+        Some(decoder) => Words::for_decoder(decoder, args.wordlist.as_deref())?,
+        None => Words::codec(Codec::Pgp, args.wordlist.as_deref())?,
+    };
+    let read = |path: &str| -> Result<base256::Transcript> {
+        Ok(base256::Transcript::read(
+            words.typist()?,
+            open_input(Some(path))?.chars(),
+        )?)
+    };
+    let (a, b) = (read(&args.a)?, read(&args.b)?);
+    let differences = a.diff(&b);
+
+    let mut stdout = stdout();
+    if differences.is_empty() {
+        writeln!(
+            stdout,
+            "The transcripts match: all {} words are the same.",
+            a.len()
+        )?;
+        return Ok(());
+    }
+    writeln!(stdout, "--- {}", args.a)?;
+    writeln!(stdout, "+++ {}", args.b)?;
+    let (mut changed, mut deleted, mut inserted) = (0, 0, 0);
+    for difference in &differences {
+        writeln!(stdout, "{difference}")?;
+        match difference {
+            base256::Difference::Changed { .. } => changed += 1,
+            base256::Difference::Deleted { .. } => deleted += 1,
+            base256::Difference::Inserted { .. } => inserted += 1,
+        }
+    }
+    writeln!(
+        stdout,
+        "{} differences: {changed} changed, {deleted} deleted, {inserted} inserted.",
+        differences.len()
+    )?;
+    anyhow::bail!("the transcripts differ")
+}
+
+#[cfg(feature = "decode")]
+fn lint(args: LintArgs) -> Result<()> {
+    let words = match args.decoder {
+        Some(decoder) => Words::for_decoder(decoder, args.wordlist.as_deref())?,
+        None => Words::codec(Codec::Pgp, args.wordlist.as_deref())?,
+    };
+    let transcript =
+        base256::Transcript::read(words.typist()?, open_input(args.input.as_deref())?.chars())?;
+    let errors = base256::LenientDecode::from_transcript(&transcript).errors;
+
+    let mut stdout = stdout();
+    match args.format {
+        LintFormat::Text => {
+            for error in &errors {
+                writeln!(stdout, "{error}")?;
+            }
+            match errors.len() {
+                0 => writeln!(stdout, "All {} words are valid.", transcript.len())?,
+                1 => writeln!(stdout, "1 problem in {} words.", transcript.len())?,
+                n => writeln!(stdout, "{n} problems in {} words.", transcript.len())?,
+            }
+        }
+        LintFormat::Json => {
+            writeln!(stdout, "{{")?;
+            writeln!(stdout, "  \"words\": {},", transcript.len())?;
+            write!(stdout, "  \"problems\": [")?;
+            for (i, error) in errors.iter().enumerate() {
+                let suggestions: Vec<_> = error
+                    .suggestions
+                    .iter()
+                    .map(|suggestion| json_string(suggestion))
+                    .collect();
+                write!(
+                    stdout,
+                    "{}\n    {{\"word\": {}, \"line\": {}, \"column\": {}, \"typed\": {}, \"problem\": {}, \"suggestions\": [{}]}}",
+                    if i == 0 { "" } else { "," },
+                    error.position + 1,
+                    error.source.line,
+                    error.source.column,
+                    json_string(&error.typed),
+                    json_string(error.problem.name()),
+                    suggestions.join(", ")
+                )?;
+            }
+            writeln!(stdout, "{}]", if errors.is_empty() { "" } else { "\n  " })?;
+            writeln!(stdout, "}}")?;
+        }
+    }
+    if !errors.is_empty() {
+        anyhow::bail!("the transcript has words that cannot be decoded");
+    }
+    Ok(())
+}
+
+/// `s` as a JSON string, in quotes and with the characters that need to be escaped escaped.
+#[cfg(feature = "decode")]
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        #[cfg(all(feature = "envelope", feature = "encode"))]
+        Some(Command::Split(args)) => return split(args),
+        #[cfg(all(feature = "envelope", feature = "decode"))]
+        Some(Command::Join(args)) => return join(args),
+        #[cfg(all(feature = "encode", feature = "decode"))]
+        Some(Command::Transcode(args)) => return transcode(args),
+        Some(Command::WordlistCheck(args)) => return wordlist_check(args),
+        #[cfg(all(feature = "encode", feature = "decode"))]
+        Some(Command::Type(args)) => return type_mode::type_words(args),
+        #[cfg(all(feature = "encode", feature = "decode"))]
+        Some(Command::Verify(args)) => return verify(args),
+        #[cfg(feature = "decode")]
+        Some(Command::Diff(args)) => return diff(args),
+        #[cfg(feature = "decode")]
+        Some(Command::Lint(args)) => return lint(args),
+        None => {}
+    }
+
+    let mut input = open_input(cli.input.as_deref())?;
+
+    #[cfg(feature = "fec")]
+    let fec = cli.fec.params()?;
+
+    #[cfg(feature = "decode")]
+    {
+        #[cfg(not(any(
+            feature = "decode_pgp",
+            feature = "decode_eff",
+            feature = "decode_bytewords",
+            feature = "decode_rfc1751",
+            feature = "decode_eff_full",
+            feature = "decode_proquint",
+            feature = "decode_norwegian",
+            feature = "decode_german"
+        )))]
+        compile_error!("Building bin target with decoding feature enabled requires that at least one decoder is enabled");
+
+        let decoder = cli.decoding.decode;
+
+        #[cfg(all(
+            feature = "decode",
+            not(feature = "decode_pgp"),
+            not(feature = "encode")
+        ))]
+        let decoder = Some(Some(decoder)); // TODO: Something less messy for this feature set
+
+        if let Some(decoder) = decoder {
+            let options = OutputOptions {
+                no_clobber: cli.no_clobber,
+                private: true,
+            };
+            let output = open_output(cli.output.as_deref(), options)?;
+            let mut output = FormattedOutput::new(output, cli.output_format);
+
+            // If support for the PGP decoder was compiled, then it is the default decoder..
+            #[cfg(feature = "decode_pgp")]
+            let decoder = decoder.unwrap_or(Decoder::Pgp);
+            // ..otherwise, the decoder has to be provided as a cli arg.
+            #[cfg(not(any(feature = "decode_pgp", feature = "encode")))]
+            let decoder = match decoder {
+                Some(decoder) => decoder,
+                None => {
+                    unreachable!("This match arm should never be reached due to clap parse rules.");
+                }
+            };
+
+            // In voice mode, the whole transcript is interpreted ahead of decoding it.
+            #[cfg(any(
+                feature = "decode_eff",
+                feature = "decode_pgp",
+                feature = "decode_bytewords"
+            ))]
+            if cli.voice {
+                let mut text = String::new();
+                input.read_to_string(&mut text)?;
+                let transcript =
+                    base256::interpret_voice(Codec::try_from(decoder.clone())?, &text)?;
+                check_substitutions(&transcript.substitutions, cli.accept_uncertain)?;
+                input = Box::new(Cursor::new(transcript.text.into_bytes()));
+            }
+
+            #[cfg(feature = "decode")]
+            if cli.lenient {
+                let mut text = String::new();
+                input.read_to_string(&mut text)?;
+                return decode_lenient(
+                    &text,
+                    Words::for_decoder(decoder, cli.wordlist.as_deref())?,
+                    output,
+                    #[cfg(feature = "fec")]
+                    fec,
+                    #[cfg(feature = "envelope")]
+                    cli.envelope,
+                );
+            }
+
+            #[cfg(feature = "envelope")]
+            if cli.envelope {
+                let mut text = String::new();
+                input.read_to_string(&mut text)?;
+                let Some(codec) = envelope::detect_codec(&text) else {
+                    anyhow::bail!("input does not start with an envelope header");
+                };
+                let opened = envelope::open(codec.decoder(text.chars().map(Ok))?)?;
+                report_header(&opened.header);
+                report_corrections(|position| codec.word_span(position), &opened.corrections);
+                output.write_all(&opened.payload)?;
+                output.finish()?.commit()?;
+                return Ok(());
+            }
+
+            let input_chars = input.chars();
+
+            let words = Words::for_decoder(decoder, cli.wordlist.as_deref())?;
+            let decoded: Box<dyn DecodeSymbol> = words.decoder(input_chars)?;
+
+            #[cfg(feature = "fec")]
+            if let Some(fec) = fec {
+                let mut decoded = FecDecode::new(decoded, fec);
+                for byte in decoded.by_ref() {
+                    output.write_all(&[byte?])?;
+                }
+                report_corrections(|position| words.word_span(position), decoded.corrections());
+                output.finish()?.commit()?;
+                return Ok(());
+            }
+
+            for byte in decoded {
+                output.write_all(&[byte?])?;
+            }
+            output.finish()?.commit()?;
+            return Ok(());
+        }
+    }
+
+    #[cfg(feature = "encode")]
+    {
+        #[cfg(not(any(
+            feature = "encode_pgp",
+            feature = "encode_eff",
+            feature = "encode_bytewords",
+            feature = "encode_rfc1751",
+            feature = "encode_eff_full",
+            feature = "encode_proquint",
+            feature = "encode_norwegian",
+            feature = "encode_german"
+        )))]
+        compile_error!("Building bin target with encoding feature enabled requires that at least one encoder is enabled");
+
+        let encoder = cli.encoding.encoder;
+
+        // If support for the PGP encoder was compiled, then it is the default encoder..
+        #[cfg(feature = "encode_pgp")]
+        let encoder = encoder.unwrap_or(Encoder::Pgp);
+        // ..otherwise, the encoder has to be provided as a cli arg.
+        #[cfg(all(feature = "decode", not(feature = "encode_pgp")))]
+        let encoder = match encoder {
+            Some(encoder) => encoder,
+            None => {
+                unreachable!("This match arm should never be reached due to clap parse rules.");
+            }
+        };
+        #[cfg(not(any(feature = "decode", feature = "encode_pgp")))]
+        let encoder = encoder;
+
+        let options = OutputOptions {
+            no_clobber: cli.no_clobber,
+            private: false,
+        };
+        let mut output = open_output(cli.output.as_deref(), options)?;
+        let layout = cli.layout.for_encoder(&encoder);
+        let words = Words::for_encoder(encoder, cli.wordlist.as_deref())?;
+
+        let input_bytes = read_input_bytes(&mut input, cli.input_format)?;
+
+        // Byte offsets take the length of the data, to tell it from what is added to it.
+        let mut data_positions = vec![];
+        let input_bytes: Box<dyn Iterator<Item = std::io::Result<u8>>> = if layout.byte_offsets {
+            let bytes = input_bytes.collect::<Result<Vec<_>, _>>()?;
+            data_positions = (0..bytes.len()).map(Some).collect();
+            Box::new(bytes.into_iter().map(Ok))
+        } else {
+            input_bytes
+        };
+
+        #[cfg(feature = "fec")]
+        let input_bytes: Box<dyn Iterator<Item = std::io::Result<u8>>> = match fec {
+            #[cfg(feature = "envelope")]
+            Some(_) if cli.envelope => input_bytes,
+            Some(fec) => {
+                data_positions = fec.payload_positions(data_positions.len());
+                Box::new(FecEncode::new(input_bytes, fec))
+            }
+            None => input_bytes,
+        };
+
+        #[cfg(feature = "envelope")]
+        let input_bytes: Box<dyn Iterator<Item = std::io::Result<u8>>> = if cli.envelope {
+            let payload = input_bytes.collect::<Result<Vec<_>, _>>()?;
+            let mut header =
+                envelope::Header::new(words.to_codec()?, u32::try_from(payload.len())?);
+            header.checksum = !cli.no_checksum;
+            header.compressed = cli.compressed;
+            header.encrypted = cli.encrypted;
+            header.content_type = cli.content_type;
+            header.fec = fec;
+            if layout.byte_offsets {
+                data_positions = envelope::payload_positions(&header)?;
+            }
+            Box::new(envelope::seal(&header, &payload)?.into_iter().map(Ok))
+        } else {
+            input_bytes
+        };
+
+        words.encode(&mut output, layout, &data_positions, input_bytes)?;
+        output.commit()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_cases_formats {
+    #[cfg(feature = "encode")]
+    use super::{parse_digits, parse_input, InputFormat, BASE32_ALPHABET};
+    #[cfg(feature = "decode")]
+    use super::{FormattedOutput, OutputFormat};
+    #[cfg(feature = "decode")]
+    use std::io::Write;
+    use test_case::test_case;
+
+    #[cfg(feature = "encode")]
+    #[test_case(InputFormat::Hex, "0505 05\n", &[0x05; 3] ; "hex")]
+    #[test_case(InputFormat::Hex, "0A0a", &[0x0A; 2] ; "hex mixed case")]
+    #[test_case(InputFormat::Base64, "BQUF\n", &[0x05; 3] ; "base64")]
+    #[test_case(InputFormat::Base64, "Cgo=", &[0x0A; 2] ; "base64 padded")]
+    #[test_case(InputFormat::Base64, "-_8", &[0xFB, 0xFF] ; "base64 url-safe unpadded")]
+    #[test_case(InputFormat::Base32, "AUCQK===\n", &[0x05; 3] ; "base32")]
+    #[test_case(InputFormat::Base32, "bifa", &[0x0A; 2] ; "base32 lowercase unpadded")]
+    fn test_parse_input(format: InputFormat, text: &str, expected: &[u8]) {
+        assert_eq!(parse_input(text, format).unwrap(), expected);
+    }
+
+    #[cfg(feature = "encode")]
+    #[test_case(InputFormat::Hex, "0x0505" ; "hex with prefix")]
+    #[test_case(InputFormat::Hex, "05050" ; "hex odd number of digits")]
+    #[test_case(InputFormat::Base64, "BQ.F" ; "base64 invalid character")]
+    fn test_negative_parse_input(format: InputFormat, text: &str) {
+        assert!(parse_input(text, format).is_err());
+    }
+
+    #[cfg(feature = "encode")]
+    #[test_case("AUC" ; "base32 truncated")]
+    #[test_case("AUCQL" ; "base32 nonzero trailing bits")]
+    fn test_negative_parse_base32(text: &str) {
+        let digit_value = |b: u8| {
+            BASE32_ALPHABET
+                .iter()
+                .position(|&c| c == b)
+                .map(|p| p as u8)
+        };
+        assert!(parse_digits(text, "base32", 5, digit_value).is_err());
+    }
+
+    #[cfg(feature = "decode")]
+    #[test_case(OutputFormat::Raw, &[0x05; 3], b"\x05\x05\x05" ; "raw")]
+    #[test_case(OutputFormat::Hex, &[0x05; 3], b"050505\n" ; "hex")]
+    #[test_case(OutputFormat::Base64, &[0x05; 3], b"BQUF\n" ; "base64")]
+    #[test_case(OutputFormat::Base64, &[0x05; 4], b"BQUFBQ==\n" ; "base64 padded")]
+    #[test_case(OutputFormat::Base64, &[0xFF; 58], &[b"/".repeat(76), b"/w==\n".to_vec()].join(&b'\n') ; "base64 wrapped")]
+    fn test_formatted_output(format: OutputFormat, bytes: &[u8], expected: &[u8]) {
+        let mut written = vec![];
+        let mut output = FormattedOutput::new(&mut written, format);
+        for byte in bytes {
+            output.write_all(&[*byte]).unwrap();
+        }
+        output.finish().unwrap();
+        assert_eq!(written, expected);
+    }
+}
+
+#[cfg(feature = "encode")]
+#[cfg(test)]
+mod test_cases_layout {
+    use super::{word_offsets, CliLayout, WordCase, WordLayout, WordSeparator};
+    #[cfg(all(
+        feature = "encode_pgp",
+        feature = "encode_eff",
+        feature = "decode_pgp",
+        feature = "decode_eff"
+    ))]
+    use base256::Codec;
+    #[cfg(all(
+        feature = "encode_pgp",
+        feature = "encode_eff",
+        feature = "decode_pgp",
+        feature = "decode_eff"
+    ))]
+    use clap::ValueEnum;
+    use std::num::NonZeroUsize;
+    use test_case::test_case;
+
+    const WORDS: &[&str] = &[
+        "adult", "amulet", "adult", "amulet", "adult", "amulet", "allow",
+    ];
+
+    fn lay_out(layout: CliLayout) -> String {
+        lay_out_with_offsets(layout, (0..WORDS.len()).map(Some).collect())
+    }
+
+    fn lay_out_with_offsets(layout: CliLayout, offsets: Vec<Option<usize>>) -> String {
+        let mut output = vec![];
+        let mut words = WordLayout::new(&mut output, layout, offsets);
+        for word in WORDS {
+            words.write_word(word).unwrap();
+        }
+        words.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn n(n: usize) -> Option<NonZeroUsize> {
+        NonZeroUsize::new(n)
+    }
+
+    #[test_case(CliLayout::default(),
+        "adult amulet adult amulet adult amulet allow\n" ; "default")]
+    #[test_case(CliLayout { width: n(20), ..Default::default() },
+        "adult amulet adult\namulet adult amulet\nallow\n" ; "width")]
+    #[test_case(CliLayout { width: n(3), ..Default::default() },
+        "adult\namulet\nadult\namulet\nadult\namulet\nallow\n" ; "width narrower than words")]
+    #[test_case(CliLayout { words_per_line: n(3), ..Default::default() },
+        "adult amulet adult\namulet adult amulet\nallow\n" ; "words per line")]
+    #[test_case(CliLayout { words_per_line: n(4), group: n(2), ..Default::default() },
+        "adult amulet  adult amulet\nadult amulet  allow\n" ; "groups")]
+    #[test_case(CliLayout { words_per_line: n(4), line_numbers: true, byte_offsets: true, ..Default::default() },
+        "   1  0000:  adult amulet adult amulet\n   2  0004:  adult amulet allow\n" ; "margin")]
+    #[test_case(CliLayout { width: n(32), line_numbers: true, ..Default::default() },
+        "   1  adult amulet adult amulet\n   2  adult amulet allow\n" ; "width includes margin")]
+    fn test_positive_word_layout(layout: CliLayout, expected: &str) {
+        assert_eq!(lay_out(layout), expected);
+    }
+
+    #[test_case(&[], &[] ; "no data")]
+    #[test_case(&[Some(0), Some(1), None, Some(2)], &[Some(0), Some(2)] ; "word with parity and data")]
+    #[test_case(&[None, None, Some(0), Some(1), Some(2)], &[None, Some(0), Some(2)] ; "two bytes per word")]
+    #[test_case(&[Some(0), Some(1), None, None], &[Some(0), None] ; "parity after data")]
+    fn test_positive_word_offsets(data_positions: &[Option<usize>], expected: &[Option<usize>]) {
+        // Words of two bytes each, like proquints.
+        assert_eq!(
+            word_offsets(|byte| byte / 2..=byte / 2, data_positions),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_positive_word_layout_blank_offsets() {
+        let layout = CliLayout {
+            words_per_line: n(3),
+            byte_offsets: true,
+            ..Default::default()
+        };
+        // Header words first, and words after the data, like a checksum, have no offset.
+        let offsets = vec![None, None, None, Some(0), Some(0), Some(2)];
+        assert_eq!(
+            lay_out_with_offsets(layout, offsets),
+            "       adult amulet adult\n0000:  amulet adult amulet\n       allow\n"
+        );
+    }
+
+    #[test_case(CliLayout { case: WordCase::Lower, ..Default::default() },
+        "adult amulet adult amulet adult amulet allow\n" ; "lowercase")]
+    #[test_case(CliLayout { case: WordCase::Upper, separator: Some(WordSeparator::Hyphen), ..Default::default() },
+        "ADULT-AMULET-ADULT-AMULET-ADULT-AMULET-ALLOW\n" ; "uppercase with hyphens")]
+    #[test_case(CliLayout { case: WordCase::Camel, ..Default::default() },
+        "AdultAmuletAdultAmuletAdultAmuletAllow\n" ; "camel case")]
+    #[test_case(CliLayout { case: WordCase::Title, separator: Some(WordSeparator::Newline), line_numbers: true, ..Default::default() },
+        "   1  Adult\n   2  Amulet\n   3  Adult\n   4  Amulet\n   5  Adult\n   6  Amulet\n   7  Allow\n" ; "title case on separate lines")]
+    #[test_case(CliLayout { separator: Some(WordSeparator::Hyphen), group: n(3), ..Default::default() },
+        "adult-amulet-adult--amulet-adult-amulet--allow\n" ; "groups with hyphens")]
+    fn test_positive_word_layout_case_and_separator(layout: CliLayout, expected: &str) {
+        assert_eq!(lay_out(layout), expected);
+    }
+
+    /// Every combination of case and separator is decoded back to the same bytes.
+    #[cfg(all(
+        feature = "encode_pgp",
+        feature = "encode_eff",
+        feature = "decode_pgp",
+        feature = "decode_eff"
+    ))]
+    #[test]
+    fn test_positive_word_layout_case_and_separator_roundtrip() {
+        let bytes_orig: Vec<u8> = (0x00u8..=0xFF).collect();
+        for codec in [Codec::Pgp, Codec::Eff] {
+            for case in WordCase::value_variants() {
+                for separator in WordSeparator::value_variants() {
+                    let mut output = vec![];
+                    let layout = CliLayout {
+                        case: *case,
+                        separator: Some(*separator),
+                        ..Default::default()
+                    };
+                    let mut words = WordLayout::new(&mut output, layout, vec![]);
+                    for word in codec
+                        .encoder(bytes_orig.iter().copied().map(Ok::<_, std::io::Error>))
+                        .unwrap()
+                    {
+                        words.write_word(word.unwrap()).unwrap();
+                    }
+                    words.finish().unwrap();
+
+                    let text = String::from_utf8(output).unwrap();
+                    let bytes_decoded = codec
+                        .decoder(text.chars().map(Ok))
+                        .unwrap()
+                        .collect::<Result<Vec<_>, _>>()
+                        .unwrap();
+                    assert_eq!(bytes_decoded, bytes_orig, "{codec} {case:?} {separator:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_positive_word_layout_no_words() {
+        let mut output = vec![];
+        WordLayout::new(&mut output, CliLayout::default(), vec![])
+            .finish()
+            .unwrap();
+        assert!(output.is_empty());
+    }
+}
+
+#[cfg(all(feature = "encode", feature = "decode"))]
+#[cfg(feature = "decode_pgp")]
+#[cfg(test)]
+mod test_cases_voice {
+    use super::check_substitutions;
+    use base256::Codec;
+
+    #[test]
+    fn test_negative_voice_uncertain_substitutions_need_accepting() {
+        let transcript = base256::interpret_voice(Codec::Pgp, "do pont ohio janice").unwrap();
+        let error = check_substitutions(&transcript.substitutions, false).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "1 word was replaced with uncertainty; if it is right, decode again with --accept-uncertain"
+        );
+        assert!(check_substitutions(&transcript.substitutions, true).is_ok());
+    }
+}
+
+#[cfg(feature = "decode")]
+#[cfg(test)]
+mod test_cases_lint {
+    use super::json_string;
+    use test_case::test_case;
+
+    #[test_case("adviser", r#""adviser""# ; "plain")]
+    #[test_case("blåbær", r#""blåbær""# ; "non-ascii")]
+    #[test_case(r#"say "hi"\"#, r#""say \"hi\"\\""# ; "quotes and backslash")]
+    #[test_case("a\nb\tc", r#""a\nb\u0009c""# ; "control characters")]
+    fn test_json_string(s: &str, expected: &str) {
+        assert_eq!(json_string(s), expected);
+    }
+}
+
+#[cfg(test)]
+mod test_cases_output {
+    use super::{open_output, OutputOptions};
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    /// An empty directory of its own for each test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("lastresort-test-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    fn entries(dir: &PathBuf) -> Vec<String> {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn test_output_replaces_longer_file() {
+        let dir = test_dir("replace");
+        let path = dir.join("out");
+        std::fs::write(&path, b"a much longer file").unwrap();
+        let mut output = open_output(path.to_str(), OutputOptions::default()).unwrap();
+        output.write_all(b"short").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"a much longer file");
+        output.commit().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"short");
+        assert_eq!(entries(&dir), ["out"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_output_not_committed_leaves_nothing_behind() {
+        let dir = test_dir("uncommitted");
+        let path = dir.join("out");
+        std::fs::write(&path, b"old").unwrap();
+        let mut output = open_output(path.to_str(), OutputOptions::default()).unwrap();
+        output.write_all(b"partial").unwrap();
+        drop(output);
+        assert_eq!(std::fs::read(&path).unwrap(), b"old");
+        assert_eq!(entries(&dir), ["out"]);
+
+        let new_path = dir.join("new");
+        drop(open_output(new_path.to_str(), OutputOptions::default()).unwrap());
+        assert_eq!(entries(&dir), ["out"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_output_no_clobber() {
+        let dir = test_dir("no-clobber");
+        let path = dir.join("out");
+        let options = OutputOptions {
+            no_clobber: true,
+            private: false,
+        };
+        let mut output = open_output(path.to_str(), options).unwrap();
+        output.write_all(b"first").unwrap();
+        output.commit().unwrap();
+        let error = open_output(path.to_str(), options).err().unwrap();
+        assert!(error.to_string().contains("--no-clobber"));
+        assert_eq!(std::fs::read(&path).unwrap(), b"first");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_output_no_clobber_file_created_while_writing() {
+        let dir = test_dir("no-clobber-race");
+        let path = dir.join("out");
+        let options = OutputOptions {
+            no_clobber: true,
+            private: false,
+        };
+        let mut output = open_output(path.to_str(), options).unwrap();
+        output.write_all(b"mine").unwrap();
+        std::fs::write(&path, b"theirs").unwrap();
+        let error = output.commit().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read(&path).unwrap(), b"theirs");
+        assert_eq!(entries(&dir), ["out"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_copy_to_new() {
+        let dir = test_dir("copy-to-new");
+        let (from, to) = (dir.join("from"), dir.join("to"));
+        std::fs::write(&from, b"mine").unwrap();
+        super::copy_to_new(&from, &to).unwrap();
+        assert_eq!(std::fs::read(&to).unwrap(), b"mine");
+
+        std::fs::write(&to, b"theirs").unwrap();
+        let error = super::copy_to_new(&from, &to).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read(&to).unwrap(), b"theirs");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = test_dir("private");
+        let path = dir.join("out");
+        std::fs::write(&path, b"old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let options = OutputOptions {
+            no_clobber: false,
+            private: true,
+        };
+        let output = open_output(path.to_str(), options).unwrap();
+        output.commit().unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,174 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Normalization of the characters of transcripts typed on phones and international keyboards.
+
+/// Dashes and hyphens that keyboards and autocorrect put in place of `-`.
+const DASHES: &[char] = &[
+    '\u{2010}', '\u{2011}', '\u{2012}', '\u{2013}', '\u{2014}', '\u{2015}', '\u{2212}', '\u{FE58}',
+    '\u{FE63}', '\u{FF0D}',
+];
+
+/// Invisible characters that phones and word processors insert between letters.
+const INVISIBLE: &[char] = &[
+    '\u{00AD}', '\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}',
+];
+
+/// Cyrillic and Greek letters that look the same as Latin letters,
+/// along with the Latin letters they look like.
+const LOOK_ALIKES: &[(char, char)] = &[
+    ('а', 'a'),
+    ('е', 'e'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('у', 'y'),
+    ('х', 'x'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ѕ', 's'),
+    ('ԁ', 'd'),
+    ('һ', 'h'),
+    ('ԛ', 'q'),
+    ('ԝ', 'w'),
+    ('А', 'A'),
+    ('В', 'B'),
+    ('Е', 'E'),
+    ('К', 'K'),
+    ('М', 'M'),
+    ('Н', 'H'),
+    ('О', 'O'),
+    ('Р', 'P'),
+    ('С', 'C'),
+    ('Т', 'T'),
+    ('Х', 'X'),
+    ('І', 'I'),
+    ('Ј', 'J'),
+    ('Ѕ', 'S'),
+    ('Α', 'A'),
+    ('Β', 'B'),
+    ('Ε', 'E'),
+    ('Ζ', 'Z'),
+    ('Η', 'H'),
+    ('Ι', 'I'),
+    ('Κ', 'K'),
+    ('Μ', 'M'),
+    ('Ν', 'N'),
+    ('Ο', 'O'),
+    ('Ρ', 'P'),
+    ('Τ', 'T'),
+    ('Υ', 'Y'),
+    ('Χ', 'X'),
+    ('ο', 'o'),
+];
+
+/// Normalize one character of a transcript, pushing the result to `out`.
+///
+/// Whitespace other than line breaks becomes spaces, and invisible characters are left out.
+/// Dashes become `-`, and letters that look like Latin letters become Latin letters.
+/// With the unicode feature, everything else is normalized to NFKC with diacritics removed,
+/// so that for example full-width letters and accented letters become plain Latin letters.
+///
+/// Most characters are replaced by exactly one character, but invisible characters and
+/// diacritics on their own are replaced by none, and compatibility characters like ligatures
+/// may be replaced by several.
+pub(crate) fn normalize(c: char, out: &mut impl Extend<char>) {
+    if c.is_ascii() {
+        out.extend([c]);
+    } else if c == '\u{2028}' || c == '\u{2029}' || c == '\u{0085}' {
+        out.extend(['\n']);
+    } else if INVISIBLE.contains(&c) {
+        // Left out, so that they don't split the words they are in
+    } else if c.is_whitespace() {
+        out.extend([' ']);
+    } else if DASHES.contains(&c) {
+        out.extend(['-']);
+    } else if let Some(&(_, latin)) = LOOK_ALIKES.iter().find(|(look_alike, _)| *look_alike == c) {
+        out.extend([latin]);
+    } else {
+        fold(c, out);
+    }
+}
+
+/// Decompose a character for compatibility, and leave out the diacritics.
+///
+/// Applied one character at a time, this gives the same letters as NFKC followed by
+/// removal of diacritics, so a diacritic on its own is left out.
+#[cfg(feature = "unicode")]
+fn fold(c: char, out: &mut impl Extend<char>) {
+    unicode_normalization::char::decompose_compatible(c, |d| {
+        if !unicode_normalization::char::is_combining_mark(d) {
+            out.extend([d]);
+        }
+    });
+}
+
+#[cfg(not(feature = "unicode"))]
+fn fold(c: char, out: &mut impl Extend<char>) {
+    out.extend([c]);
+}
+
+/// Normalize a word of a wordlist the way the decoders normalize their input, and lowercase it,
+/// so that it can be compared with what the decoders read.
+///
+/// Spaces are left out, as the decoders never read them as part of a word.
+pub(crate) fn normalize_word(word: &str) -> String {
+    let mut normalized = String::new();
+    for c in word.chars() {
+        normalize(c, &mut normalized);
+    }
+    normalized
+        .chars()
+        .filter(|&c| c != ' ')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod test_cases_normalize {
+    use super::{normalize, normalize_word};
+    use test_case::test_case;
+
+    fn normalize_str(text: &str) -> String {
+        let mut out = String::new();
+        for c in text.chars() {
+            normalize(c, &mut out);
+        }
+        out
+    }
+
+    #[test_case("adult\u{00A0}amulet", "adult amulet" ; "no-break space")]
+    #[test_case("adult\u{3000}amulet", "adult amulet" ; "ideographic space")]
+    #[test_case("adult\u{2028}amulet", "adult\namulet" ; "line separator")]
+    #[test_case("ad\u{200B}ult", "adult" ; "zero width space")]
+    #[test_case("ad\u{00AD}ult", "adult" ; "soft hyphen")]
+    #[test_case("adult\u{2013}amulet", "adult-amulet" ; "en dash")]
+    #[test_case("аdult", "adult" ; "cyrillic look-alike")]
+    #[cfg_attr(feature = "unicode", test_case("ａｄｕｌｔ", "adult" ; "full-width"))]
+    #[cfg_attr(feature = "unicode", test_case("ÁDÜLT", "ADULT" ; "precomposed diacritics"))]
+    #[cfg_attr(feature = "unicode", test_case("a\u{0301}dult", "adult" ; "combining diacritic"))]
+    #[cfg_attr(feature = "unicode", test_case("\u{FB02}ytrap", "flytrap" ; "ligature"))]
+    #[test_case("ßadult", "ßadult" ; "foreign letter")]
+    fn test_positive_normalize(text: &str, expected: &str) {
+        assert_eq!(normalize_str(text), expected);
+    }
+
+    #[test_case("Adult", "adult" ; "uppercase")]
+    #[cfg_attr(feature = "unicode", test_case("Ca\u{0301}fe\u{0301}", "cafe" ; "combining diacritics"))]
+    fn test_positive_normalize_word(word: &str, expected: &str) {
+        assert_eq!(normalize_word(word), expected);
+    }
+}
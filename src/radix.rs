@@ -0,0 +1,187 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Encoding of bytes as words from wordlists of any size, instead of one word per byte.
+
+/// Largest block length, for which the value of the last block still fits in a `u128`.
+const MAX_BLOCK_LEN: usize = 15;
+
+/// How bytes are converted to words for a wordlist of a given size.
+///
+/// With a wordlist of 256 words, each byte is written as one word, like the other codecs do.
+///
+/// With any other wordlist of 2<sup>*k*</sup> words, like the 2048 words of BIP39,
+/// the bytes are packed *k* bits at a time, most significant bit first.
+/// The bits are followed by a single `1` bit, and then by as many `0` bits as it takes
+/// to fill the last word. The last word thus always holds the end marker, and the decoder
+/// finds the length of the data by dropping the trailing `0` bits and the `1` bit before them.
+///
+/// With a wordlist of any other size *n*, like the 1296 words of the full EFF Short Wordlist 2.0,
+/// the bytes are converted in blocks, each block being a big-endian integer written as
+/// a fixed number of base *n* digits, most significant digit first. The block length is
+/// chosen for each wordlist size to waste as few bits as possible, see [`RadixScheme::for_wordlist_len`].
+/// The last block holds the last 1 to *B* bytes, where *B* is the block length, and
+/// records how many bytes it holds by adding to its value the number of
+/// shorter byte strings, 1 + 256 + 256<sup>2</sup> + … for each length shorter than its own.
+/// It is written with as few digits as that value needs, so the decoder knows that
+/// the last 1 to *W* words form the last block, where *W* is the number of words in
+/// a whole block. No data encodes to no words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RadixScheme {
+    /// Each word is one byte, for wordlists of 256 words.
+    Bytes,
+    /// Each word holds `bits` bits, for wordlists of 2<sup>`bits`</sup> words.
+    BitPacked { bits: u32 },
+    /// Each block of `block_len` bytes is written as `block_words` words.
+    Blocks {
+        radix: u128,
+        block_len: usize,
+        block_words: usize,
+    },
+}
+
+impl RadixScheme {
+    /// Scheme for a wordlist of `len` words.
+    ///
+    /// For wordlists whose size is not a power of two, the block length is the one
+    /// of up to 15 bytes with the most bytes per word.
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidInput`] if the wordlist has fewer than two words,
+    /// or more than 2<sup>32</sup> words.
+    pub fn for_wordlist_len(len: usize) -> Result<Self, std::io::Error> {
+        if !(2..=1 << 32).contains(&(len as u128)) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("a wordlist needs between 2 and 2^32 words, but this one has {len}"),
+            ));
+        }
+        if len == 256 {
+            return Ok(RadixScheme::Bytes);
+        }
+        if len.is_power_of_two() {
+            return Ok(RadixScheme::BitPacked {
+                bits: len.trailing_zeros(),
+            });
+        }
+        let radix = len as u128;
+        // Prefer the block with the most bytes per word, and the shortest block among equals.
+        let (block_len, block_words) = (1..=MAX_BLOCK_LEN)
+            .map(|block_len| (block_len, digits_for(radix, offset(block_len + 1) - 1)))
+            .reduce(|best, candidate| {
+                if candidate.0 * best.1 > best.0 * candidate.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+            .unwrap();
+        Ok(RadixScheme::Blocks {
+            radix,
+            block_len,
+            block_words,
+        })
+    }
+
+    /// Number of words for `byte_len` bytes of data.
+    pub fn word_count(self, byte_len: usize) -> usize {
+        match self {
+            RadixScheme::Bytes => byte_len,
+            RadixScheme::BitPacked { bits } => byte_len * 8 / bits as usize + 1,
+            RadixScheme::Blocks {
+                radix,
+                block_len,
+                block_words,
+            } => {
+                if byte_len == 0 {
+                    return 0;
+                }
+                let last_len = (byte_len - 1) % block_len + 1;
+                (byte_len - last_len) / block_len * block_words
+                    + digits_for(radix, offset(last_len + 1) - 1)
+            }
+        }
+    }
+
+    /// Indices of the words that hold the bits of the byte at index `byte_position`.
+    /// When converting blocks, these are all the words of its block.
+    pub fn word_span(self, byte_position: usize) -> std::ops::RangeInclusive<usize> {
+        match self {
+            RadixScheme::Bytes => byte_position..=byte_position,
+            RadixScheme::BitPacked { bits } => {
+                let bit = byte_position * 8;
+                bit / bits as usize..=(bit + 7) / bits as usize
+            }
+            RadixScheme::Blocks {
+                block_len,
+                block_words,
+                ..
+            } => {
+                let first = byte_position / block_len * block_words;
+                first..=first + block_words - 1
+            }
+        }
+    }
+}
+
+/// Number of byte strings shorter than `len` bytes, which the last block adds to its value.
+pub(crate) fn offset(len: usize) -> u128 {
+    (0..len).map(|i| 1u128 << (8 * i)).sum()
+}
+
+/// Number of base `radix` digits needed to write `value`, and no fewer than one.
+pub(crate) fn digits_for(radix: u128, mut value: u128) -> usize {
+    let mut digits = 1;
+    while value >= radix {
+        value /= radix;
+        digits += 1;
+    }
+    digits
+}
+
+#[cfg(test)]
+mod test_cases_radix_scheme {
+    use super::RadixScheme;
+    use test_case::test_case;
+
+    #[test_case(2048, RadixScheme::BitPacked { bits: 11 } ; "bip39 sized")]
+    #[test_case(256, RadixScheme::Bytes ; "one byte per word")]
+    #[test_case(16, RadixScheme::BitPacked { bits: 4 } ; "half a byte per word")]
+    #[test_case(1296, RadixScheme::Blocks { radix: 1296, block_len: 9, block_words: 7 } ; "eff short full")]
+    #[test_case(7776, RadixScheme::Blocks { radix: 7776, block_len: 8, block_words: 5 } ; "eff long")]
+    fn test_positive_radix_scheme(len: usize, expected: RadixScheme) {
+        assert_eq!(RadixScheme::for_wordlist_len(len).unwrap(), expected);
+    }
+
+    #[test_case(2048, 0, 1)]
+    #[test_case(2048, 32, 24)]
+    #[test_case(256, 0, 0)]
+    #[test_case(256, 32, 32)]
+    #[test_case(1296, 0, 0)]
+    #[test_case(1296, 1, 1)]
+    #[test_case(1296, 9, 7)]
+    #[test_case(1296, 10, 8)]
+    fn test_positive_radix_word_count(len: usize, byte_len: usize, expected: usize) {
+        let scheme = RadixScheme::for_wordlist_len(len).unwrap();
+        assert_eq!(scheme.word_count(byte_len), expected);
+    }
+
+    #[test_case(0)]
+    #[test_case(1)]
+    fn test_negative_radix_scheme_too_few_words(len: usize) {
+        let error = RadixScheme::for_wordlist_len(len).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
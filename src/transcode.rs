@@ -0,0 +1,280 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Transcoding of words from one codec to another, without going through a file of bytes.
+
+use crate::{Codec, Ignored, SourcePosition};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// A word in the target codec, along with where the corresponding word started in the source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TranscodedWord {
+    /// The word, or `?` if the source word was marked as unreadable.
+    pub word: &'static str,
+    pub source: SourcePosition,
+}
+
+#[derive(Debug)]
+struct SourceState {
+    /// Position of the last character read.
+    curr: Cell<SourcePosition>,
+    /// Whether the last character read was a line break.
+    line_ended: Cell<bool>,
+    /// Position of the first character of the word currently being read.
+    word_start: Cell<Option<SourcePosition>>,
+    /// The bytes decoded so far.
+    bytes: RefCell<Vec<SourceByte>>,
+}
+
+/// A byte decoded from the source.
+#[derive(Clone, Copy, Debug)]
+struct SourceByte {
+    /// Position of the first character of the word that the byte was decoded from.
+    source: SourcePosition,
+    /// Whether the word was marked as unreadable.
+    erased: bool,
+}
+
+/// Transcodes words from one codec to another, one word at a time.
+///
+/// Words marked as unreadable (`?`) in the source are kept as `?` in the target,
+/// so that forward error correction can still recover them later.
+/// Since the target codec may encode several bytes at a time, every target word
+/// that holds bits of an unreadable byte is written as `?`, and so are the words
+/// after those of the last byte, like the Bytewords checksum, if any byte was unreadable.
+pub struct Transcode<'a> {
+    encoder: Box<dyn Iterator<Item = Result<&'static str, std::io::Error>> + 'a>,
+    state: Rc<SourceState>,
+    to: Codec,
+    /// Index of the next target word.
+    position: usize,
+    /// Index of the first byte whose target words have not all been written.
+    first_byte: usize,
+}
+
+impl Iterator for Transcode<'_> {
+    type Item = Result<TranscodedWord, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let word = match self.encoder.next()? {
+            Ok(word) => word,
+            Err(e) => return Some(Err(e)),
+        };
+        let position = self.position;
+        self.position += 1;
+
+        // The bytes that the word holds bits of have all been read by the encoder.
+        let bytes = self.state.bytes.borrow();
+        while self.first_byte < bytes.len() && *self.to.word_span(self.first_byte).end() < position
+        {
+            self.first_byte += 1;
+        }
+        let held = bytes[self.first_byte..]
+            .iter()
+            .enumerate()
+            .take_while(|(i, _)| *self.to.word_span(self.first_byte + i).start() <= position)
+            .map(|(_, byte)| byte);
+        let (source, erased) = match held.clone().next() {
+            Some(first) => (first.source, held.clone().any(|byte| byte.erased)),
+            None => (self.state.curr.get(), bytes.iter().any(|byte| byte.erased)),
+        };
+        Some(Ok(TranscodedWord {
+            word: if erased { "?" } else { word },
+            source,
+        }))
+    }
+}
+
+/// Transcode the words read from `chars` from codec `from` to codec `to`.
+///
+/// Everything that the decoders ignore by default, like line numbers and comments, is skipped.
+/// Errors in the source are reported along with the line and column where they were found.
+/// Fails with [`std::io::ErrorKind::Unsupported`] if either codec was not enabled at compile-time.
+pub fn transcode<'a, I>(from: Codec, to: Codec, chars: I) -> Result<Transcode<'a>, std::io::Error>
+where
+    I: Iterator<Item = Result<char, std::io::Error>> + 'a,
+{
+    let state = Rc::new(SourceState {
+        curr: Cell::new(SourcePosition { line: 1, column: 0 }),
+        line_ended: Cell::new(false),
+        word_start: Cell::new(None),
+        bytes: RefCell::new(vec![]),
+    });
+
+    // Skip ignored input ahead of tracking positions, so that line numbers and byte offsets
+    // in the margin are not taken to be the start of the first word of the line.
+    let chars_state = Rc::clone(&state);
+    let chars = Ignored::default().filter(chars).inspect(move |c| {
+        let Ok(c) = c else { return };
+        let mut pos = chars_state.curr.get();
+        if chars_state.line_ended.replace(*c == '\n') {
+            pos.line += 1;
+            pos.column = 0;
+        }
+        pos.column += 1;
+        chars_state.curr.set(pos);
+        if !c.is_whitespace() && chars_state.word_start.get().is_none() {
+            chars_state.word_start.set(Some(pos));
+        }
+    });
+    let mut decoder = from.decoder_ignoring(chars, Ignored::nothing())?;
+
+    let bytes_state = Rc::clone(&state);
+    let bytes = std::iter::from_fn(move || {
+        let symbol = decoder.next_symbol()?;
+        if let Ok(byte) = symbol {
+            // Bytes decoded from the same words share the position of the first of them.
+            let mut bytes = bytes_state.bytes.borrow_mut();
+            let source = match (bytes_state.word_start.take(), bytes.last()) {
+                (Some(source), _) => source,
+                (None, Some(prev)) => prev.source,
+                (None, None) => bytes_state.curr.get(),
+            };
+            bytes.push(SourceByte {
+                source,
+                erased: byte.is_none(),
+            });
+        }
+        Some(match symbol {
+            Ok(byte) => Ok(byte.unwrap_or(0)),
+            Err(e) => Err(std::io::Error::new(
+                e.kind(),
+                format!("{} at {}", error_description(&e), bytes_state.curr.get()),
+            )),
+        })
+    });
+
+    Ok(Transcode {
+        encoder: to.encoder(bytes)?,
+        state,
+        to,
+        position: 0,
+        first_byte: 0,
+    })
+}
+
+fn error_description(e: &std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::InvalidData if e.get_ref().is_none() => "invalid word".to_string(),
+        _ => e.to_string(),
+    }
+}
+
+#[cfg(all(
+    feature = "encode_pgp",
+    feature = "decode_pgp",
+    feature = "encode_eff",
+    feature = "decode_eff"
+))]
+#[cfg(test)]
+mod test_cases_transcode {
+    use super::{transcode, SourcePosition};
+    use crate::Codec;
+    use std::fs::File;
+    use std::io::{BufReader, Cursor};
+    use test_case::test_case;
+    use utf8_chars::BufReadCharsExt;
+
+    #[test_case(Codec::Eff, Codec::Pgp, "sample_data/encoded/eff/id_ed25519.txt", "sample_data/encoded/pgp/id_ed25519.txt" ; "eff to pgp")]
+    #[test_case(Codec::Pgp, Codec::Eff, "sample_data/encoded/pgp/id_ed25519.txt", "sample_data/encoded/eff/id_ed25519.txt" ; "pgp to eff")]
+    fn test_positive_transcode_sample_data_file_id_ed25519(
+        from: Codec,
+        to: Codec,
+        fpath_source: &str,
+        fpath_expected: &str,
+    ) {
+        let mut source = BufReader::new(File::open(fpath_source).unwrap());
+        let words = transcode(from, to, source.chars())
+            .unwrap()
+            .map(|w| w.map(|w| w.word))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let expected = std::fs::read_to_string(fpath_expected).unwrap();
+        assert_eq!(words, expected.split_whitespace().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_positive_transcode_keeps_source_positions_and_unreadable_words() {
+        let mut cursor = Cursor::new("acuteness ?\n  acute\nness");
+        let words = transcode(Codec::Eff, Codec::Pgp, cursor.chars())
+            .unwrap()
+            .map(|w| w.map(|w| (w.word, w.source.line, w.source.column)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(words, &[("adult", 1, 1), ("?", 1, 11), ("adult", 2, 3)]);
+    }
+
+    #[cfg_attr(
+        all(feature = "encode_bytewords", feature = "decode_bytewords"),
+        test_case(Codec::Bytewords, &[3, 16, 17, 18, 19] ; "bytewords checksum")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_rfc1751", feature = "decode_rfc1751"),
+        test_case(Codec::Rfc1751, &[2] ; "rfc1751 block")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_eff_full", feature = "decode_eff_full"),
+        test_case(Codec::EffFull, &[0, 1, 2, 3, 4, 5, 6] ; "eff full block")
+    )]
+    #[cfg_attr(
+        all(feature = "encode_proquint", feature = "decode_proquint"),
+        test_case(Codec::Proquint, &[1] ; "proquint pair")
+    )]
+    fn test_positive_transcode_unreadable_word_in_block(to: Codec, expected: &[usize]) {
+        let bytes: Vec<u8> = (0..16).collect();
+        let mut source: Vec<_> = Codec::Pgp
+            .encoder(bytes.iter().copied().map(Ok::<_, std::io::Error>))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        source[3] = "?";
+        let source = source.join(" ");
+
+        let words = transcode(Codec::Pgp, to, source.chars().map(Ok))
+            .unwrap()
+            .map(|w| w.map(|w| w.word))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let unreadable: Vec<_> = (0..words.len()).filter(|&i| words[i] == "?").collect();
+        assert_eq!(unreadable, expected);
+
+        // Decoding the words again gives back every byte that was readable in the source,
+        // and none that was not.
+        let target = words.join(" ");
+        let mut decoder = to.decoder(target.chars().map(Ok)).unwrap();
+        let symbols = std::iter::from_fn(|| decoder.next_symbol())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(symbols.len(), bytes.len());
+        assert_eq!(symbols[3], None);
+        for (symbol, byte) in symbols.iter().zip(&bytes) {
+            assert!(symbol.is_none_or(|symbol| symbol == *byte));
+        }
+    }
+
+    #[test_case("adult amulet\nadult xyz", SourcePosition { line: 2, column: 7 } ; "invalid word")]
+    #[test_case("adult\n\n  amulet adult amulet xyz", SourcePosition { line: 3, column: 23 } ; "invalid word after blank line")]
+    fn test_negative_transcode_error_position(source: &str, expected: SourcePosition) {
+        let mut cursor = Cursor::new(source);
+        let error = transcode(Codec::Pgp, Codec::Eff, cursor.chars())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), format!("invalid word at {expected}"));
+    }
+}
@@ -0,0 +1,520 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Type mode: words typed on the terminal, checked as they are typed.
+//!
+//! The terminal is set up with `stty`, which has to be installed.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Write};
+
+use anyhow::Result;
+use utf8_chars::BufReadCharsExt;
+
+use base256::Codec;
+#[cfg(feature = "fec")]
+use base256::{FecDecode, FecParams};
+
+#[cfg(feature = "envelope")]
+use base256::envelope;
+
+use crate::{open_output, FormattedOutput, OutputOptions, TypeArgs, Words};
+#[cfg(feature = "fec")]
+use crate::{report_corrections, Symbols};
+
+/// Number of words on each line of words typed in type mode.
+const TYPE_WORDS_PER_LINE: usize = 6;
+
+/// Number of lines of words typed that are shown in type mode, counting back from the last one.
+const TYPE_LINES_SHOWN: usize = 16;
+
+/// What is known of the words typed so far in type mode.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TypeProgress {
+    /// Number of words in all, once read from the envelope header.
+    expected_words: Option<usize>,
+    /// Checks of the words, by the number of the last word each check covers, counting from 1.
+    checks: Vec<(usize, std::result::Result<String, String>)>,
+}
+
+/// Check the envelope header and the blocks of forward error correction
+/// that have been typed in full.
+///
+/// The checksum of the payload is checked by decoding all of the words, once they are typed.
+fn type_progress(
+    symbols: &[Option<u8>],
+    codec: Option<Codec>,
+    #[cfg(feature = "envelope")] envelope: bool,
+    #[cfg(feature = "fec")] fec: Option<FecParams>,
+) -> TypeProgress {
+    #[cfg_attr(not(feature = "fec"), allow(unused_mut))]
+    let mut progress = TypeProgress::default();
+    // The words at the end that are not part of the data, like the checksum of Bytewords.
+    let trailing = codec.map_or(0, |codec| codec.word_count(0));
+    #[cfg_attr(not(feature = "envelope"), allow(unused_mut, unused_variables))]
+    let mut body = 0..symbols.len().saturating_sub(trailing);
+    #[cfg(feature = "fec")]
+    #[cfg_attr(not(feature = "envelope"), allow(unused_mut))]
+    let mut fec = fec;
+
+    #[cfg(feature = "envelope")]
+    if envelope {
+        let mut header_symbols = Symbols(symbols.iter());
+        let header = envelope::Header::read(&mut header_symbols);
+        let header_len = symbols.len() - header_symbols.0.len();
+        match header {
+            Ok(header) => {
+                let mark = if Some(header.codec) == codec {
+                    Ok("header ok".to_string())
+                } else {
+                    Err(format!("header is for codec {}", header.codec))
+                };
+                progress.checks.push((header_len, mark));
+                progress.expected_words = header.word_count().ok();
+                let sealed_len = progress
+                    .expected_words
+                    .unwrap_or(0)
+                    .saturating_sub(trailing);
+                body = header_len..body.end.min(sealed_len).max(header_len);
+                fec = header.fec;
+            }
+            // The header is not typed in full yet.
+            Err(_) if header_symbols.0.len() == 0 => return progress,
+            Err(e) => {
+                progress.checks.push((header_len, Err(e.to_string())));
+                return progress;
+            }
+        }
+    }
+
+    #[cfg(feature = "fec")]
+    if let Some(fec) = fec {
+        let block_len = fec.data_len() + fec.parity_len();
+        for (i, block) in symbols[body.clone()].chunks_exact(block_len).enumerate() {
+            let block_start = body.start + i * block_len;
+            let mut decoded = FecDecode::new(Symbols(block.iter()), fec);
+            let mark = match decoded.by_ref().collect::<Result<Vec<_>, _>>() {
+                Ok(_) if decoded.corrections().is_empty() => Ok(format!("FEC block {} ok", i + 1)),
+                Ok(_) => Ok(format!(
+                    "FEC block {} ok after correcting word {}",
+                    i + 1,
+                    decoded
+                        .corrections()
+                        .iter()
+                        .map(|c| (block_start + c.position + 1).to_string())
+                        .collect::<Vec<_>>()
+                        .join(" and ")
+                )),
+                Err(_) => Err(format!("FEC block {}: too many errors to correct", i + 1)),
+            };
+            progress.checks.push((block_start + block_len, mark));
+        }
+    }
+    progress
+}
+
+/// Data decoded in type mode.
+struct TypedData {
+    payload: Vec<u8>,
+    #[cfg(feature = "fec")]
+    corrections: Vec<base256::FecCorrection>,
+}
+
+/// Decode all of the words typed, the same way as they are decoded from a file.
+fn decode_typed(
+    words: &Words,
+    text: &str,
+    #[cfg(feature = "envelope")] envelope: bool,
+    #[cfg(feature = "fec")] fec: Option<FecParams>,
+) -> Result<TypedData> {
+    #[cfg(feature = "envelope")]
+    if envelope {
+        let opened = envelope::open(words.to_codec()?.decoder(text.chars().map(Ok))?)?;
+        return Ok(TypedData {
+            payload: opened.payload,
+            corrections: opened.corrections,
+        });
+    }
+    let decoded = words.decoder(text.chars().map(Ok))?;
+    #[cfg(feature = "fec")]
+    if let Some(fec) = fec {
+        let mut decoded = FecDecode::new(decoded, fec);
+        let payload = decoded.by_ref().collect::<Result<Vec<_>, _>>()?;
+        return Ok(TypedData {
+            payload,
+            corrections: decoded.corrections().to_vec(),
+        });
+    }
+    Ok(TypedData {
+        payload: decoded.collect::<Result<Vec<_>, _>>()?,
+        #[cfg(feature = "fec")]
+        corrections: vec![],
+    })
+}
+
+/// The terminal that type mode reads keys from and draws on,
+/// set to pass on each key as it is pressed, without echo, for as long as this is kept.
+struct RawTerminal {
+    tty: File,
+    /// Settings of the terminal from before, to restore.
+    saved: String,
+}
+
+impl RawTerminal {
+    fn open() -> Result<Self> {
+        let tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .map_err(|e| anyhow::anyhow!("type mode needs a terminal: {e}"))?;
+        let saved = stty(&tty, &["-g"])?.trim().to_string();
+        stty(&tty, &["-icanon", "-echo", "-isig", "-iexten", "min", "1"])?;
+        Ok(Self { tty, saved })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = write!(self.tty, "\r\n");
+        let _ = stty(&self.tty, &[&self.saved]);
+    }
+}
+
+/// Run stty on the terminal, which it reads as its stdin.
+fn stty(tty: &File, args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("stty")
+        .args(args)
+        .stdin(tty.try_clone()?)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => anyhow::anyhow!(
+                "type mode needs the stty command to set up the terminal, and it is not installed"
+            ),
+            _ => anyhow::anyhow!("type mode needs stty to set up the terminal: {e}"),
+        })?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "stty failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Draw the words typed so far, with the checks marked at the ends of the lines
+/// where they pass or fail, followed by the status and the word being typed.
+fn draw_typed(
+    screen: &mut impl Write,
+    typist: &base256::Typist,
+    codec: Option<Codec>,
+    progress: &TypeProgress,
+    message: &str,
+) -> std::io::Result<()> {
+    let mut text = String::from("\x1b[H\x1b[2J");
+    text += "lastresort type: Tab completes, Backspace and Ctrl-W undo, Ctrl-D decodes, Ctrl-C quits\r\n\r\n";
+
+    let words = typist.words();
+    let lines: Vec<&[&str]> = words.chunks(TYPE_WORDS_PER_LINE).collect();
+    // The last line is drawn along with the word being typed, unless it is full.
+    let (done, last) = match lines.split_last() {
+        Some((last, done)) if last.len() < TYPE_WORDS_PER_LINE => (done, *last),
+        _ => (&lines[..], &[][..]),
+    };
+    for (n, line) in done
+        .iter()
+        .enumerate()
+        .skip(done.len().saturating_sub(TYPE_LINES_SHOWN))
+    {
+        text += &format!("{:>4}  {:<72}", n + 1, line.join(" "));
+        let line_words = n * TYPE_WORDS_PER_LINE + 1..=(n + 1) * TYPE_WORDS_PER_LINE;
+        for (_, mark) in progress
+            .checks
+            .iter()
+            .filter(|(end, _)| line_words.contains(end))
+        {
+            text += &match mark {
+                Ok(passed) => format!("  <- {passed}"),
+                Err(failed) => format!("  <- FAILED: {failed}"),
+            };
+        }
+        text += "\r\n";
+    }
+
+    text += "\r\n";
+    text += &match progress.expected_words {
+        Some(expected) => format!("words: {} of {expected}", words.len()),
+        None => format!("words: {}", words.len()),
+    };
+    if codec == Some(Codec::Pgp) {
+        text += match typist.list_index() {
+            0 => ", next: two-syllable word (even)",
+            _ => ", next: three-syllable word (odd)",
+        };
+    }
+    // Checks that end on the line being typed are shown here, until the line is full.
+    for (_, mark) in progress
+        .checks
+        .iter()
+        .filter(|(end, _)| *end > done.len() * TYPE_WORDS_PER_LINE)
+    {
+        text += &match mark {
+            Ok(passed) => format!(", {passed}"),
+            Err(failed) => format!(", FAILED: {failed}"),
+        };
+    }
+    text += "\r\n";
+    if !message.is_empty() {
+        text += message;
+    } else if !typist.current().is_empty() && typist.candidates().is_empty() {
+        text += "no word that can come here begins like this";
+    }
+    text += "\r\n\r\n";
+
+    text += &format!("{:>4}  ", done.len() + 1);
+    for word in last {
+        text += word;
+        text += " ";
+    }
+    text += typist.current();
+    screen.write_all(text.as_bytes())?;
+    screen.flush()
+}
+
+/// Describe the words that begin with what has been typed, after completion with Tab.
+fn describe_candidates(candidates: &[String]) -> String {
+    const SHOWN: usize = 8;
+    match candidates {
+        [] => "no word that can come here begins like this".to_string(),
+        [_] => String::new(),
+        _ if candidates.len() > SHOWN => format!(
+            "{} words begin like this: {} and {} more",
+            candidates.len(),
+            candidates[..SHOWN].join(" "),
+            candidates.len() - SHOWN
+        ),
+        _ => format!(
+            "{} words begin like this: {}",
+            candidates.len(),
+            candidates.join(" ")
+        ),
+    }
+}
+
+pub(crate) fn type_words(args: TypeArgs) -> Result<()> {
+    let words = match args.decoder {
+        Some(decoder) => Words::for_decoder(decoder, args.wordlist.as_deref())?,
+        None => Words::codec(Codec::Pgp, args.wordlist.as_deref())?,
+    };
+    let codec = match &words {
+        Words::Codec(codec) => Some(*codec),
+        Words::Custom(_) => None,
+    };
+    #[cfg(feature = "envelope")]
+    if args.envelope {
+        words.to_codec()?;
+    }
+    #[cfg(feature = "fec")]
+    let fec = args.fec.params()?;
+    let mut typist = words.typist()?;
+    // The output file is opened before typing, so that a file that cannot be written
+    // is found out before all of the words are typed.
+    let options = OutputOptions {
+        no_clobber: args.no_clobber,
+        private: true,
+    };
+    let output = open_output(args.output.as_deref(), options)?;
+
+    let terminal = RawTerminal::open()?;
+    let mut screen = &terminal.tty;
+    let mut keys = BufReader::new(terminal.tty.try_clone()?);
+    let mut keys = keys.chars();
+    let mut message = String::new();
+    let mut pending_key = None;
+    let typed = loop {
+        let mut progress = type_progress(
+            typist.symbols(),
+            codec,
+            #[cfg(feature = "envelope")]
+            args.envelope,
+            #[cfg(feature = "fec")]
+            fec,
+        );
+        // Once all of the words that the envelope holds are typed, the payload is checked.
+        if progress.expected_words == Some(typist.symbols().len()) {
+            let mark = decode_typed(
+                &words,
+                &typist.text(),
+                #[cfg(feature = "envelope")]
+                args.envelope,
+                #[cfg(feature = "fec")]
+                fec,
+            )
+            .map(|_| "all words check out; press Ctrl-D to decode".to_string())
+            .map_err(|e| e.to_string());
+            progress.checks.push((typist.symbols().len(), mark));
+        }
+        draw_typed(&mut screen, &typist, codec, &progress, &message)?;
+        message.clear();
+
+        let key = match pending_key.take() {
+            Some(key) => key,
+            None => match keys.next() {
+                Some(key) => key?,
+                None => anyhow::bail!("the terminal was closed; nothing was written"),
+            },
+        };
+        match key {
+            ' ' | '\n' | '\r' => {
+                if let Err(e) = typist.finish_word() {
+                    message = e.to_string();
+                }
+            }
+            '\t' => message = describe_candidates(&typist.complete()),
+            '\x7f' | '\x08' => typist.backspace(),
+            '\x17' => typist.undo(),
+            '\x03' => anyhow::bail!("typing was interrupted; nothing was written"),
+            '\x04' => {
+                let decoded = typist
+                    .finish_word()
+                    .map_err(anyhow::Error::from)
+                    .and_then(|_| {
+                        decode_typed(
+                            &words,
+                            &typist.text(),
+                            #[cfg(feature = "envelope")]
+                            args.envelope,
+                            #[cfg(feature = "fec")]
+                            fec,
+                        )
+                    });
+                match decoded {
+                    Ok(typed) => break typed,
+                    Err(e) => message = format!("cannot decode yet: {e}"),
+                }
+            }
+            // Escape sequences, like those of arrow keys, are skipped.
+            '\x1b' => match keys.next().transpose()? {
+                Some('[' | 'O') => {
+                    for key in keys.by_ref() {
+                        if ('\x40'..='\x7e').contains(&key?) {
+                            break;
+                        }
+                    }
+                }
+                key => pending_key = key,
+            },
+            key if key.is_control() => {}
+            key => {
+                if !typist.push_char(key) {
+                    message = format!("no word continues with {key:?}");
+                }
+            }
+        }
+    };
+    drop(terminal);
+
+    #[cfg(feature = "fec")]
+    report_corrections(|position| words.word_span(position), &typed.corrections);
+    eprintln!(
+        "lastresort: decoded {} bytes from {} words",
+        typed.payload.len(),
+        typist.symbols().len()
+    );
+    let mut output = FormattedOutput::new(output, args.output_format);
+    output.write_all(&typed.payload)?;
+    output.finish()?.commit()?;
+    Ok(())
+}
+
+#[cfg(all(feature = "encode_pgp", feature = "decode_pgp", feature = "envelope"))]
+#[cfg(test)]
+mod test_cases_type {
+    use super::{type_progress, TypeProgress};
+    use base256::{envelope, Codec, FecParams};
+
+    /// Symbols of an envelope of 20 bytes with a header of 10 bytes,
+    /// and forward error correction in blocks of 8 data bytes and 2 parity bytes.
+    fn sealed() -> Vec<Option<u8>> {
+        let mut header = envelope::Header::new(Codec::Pgp, 20);
+        header.fec = Some(FecParams::new(8, 2).unwrap());
+        envelope::seal(&header, &[0x05; 20])
+            .unwrap()
+            .into_iter()
+            .map(Some)
+            .collect()
+    }
+
+    fn progress(symbols: &[Option<u8>]) -> TypeProgress {
+        type_progress(symbols, Some(Codec::Pgp), true, None)
+    }
+
+    #[test]
+    fn test_type_progress_header_typed_in_part() {
+        assert_eq!(progress(&sealed()[..9]), TypeProgress::default());
+    }
+
+    #[test]
+    fn test_type_progress_checks() {
+        let mut symbols = sealed();
+        assert_eq!(symbols.len(), 40);
+        symbols[11] = None;
+        symbols[25] = Some(0x00);
+        symbols.truncate(35);
+        let progress = progress(&symbols);
+        assert_eq!(progress.expected_words, Some(40));
+        assert_eq!(
+            progress.checks,
+            [
+                (10, Ok("header ok".to_string())),
+                (
+                    20,
+                    Ok("FEC block 1 ok after correcting word 12".to_string())
+                ),
+                (
+                    30,
+                    Ok("FEC block 2 ok after correcting word 26".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type_progress_wrong_header() {
+        let mut symbols = sealed();
+        symbols[0] = Some(0x00);
+        let progress = progress(&symbols);
+        assert_eq!(progress.expected_words, None);
+        assert_eq!(
+            progress.checks,
+            [(
+                7,
+                Err("data does not start with an envelope header".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_type_progress_without_envelope() {
+        let progress = type_progress(
+            &[Some(0x05); 25],
+            Some(Codec::Pgp),
+            false,
+            Some(FecParams::new(8, 2).unwrap()),
+        );
+        assert_eq!(progress.checks.len(), 2);
+        assert!(progress.checks.iter().all(|(_, mark)| mark.is_err()));
+    }
+}
@@ -0,0 +1,514 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Typing words in one at a time, checking each word as it is typed.
+
+use crate::normalize::{normalize, normalize_word};
+use crate::wordlist::edit_distance;
+use crate::{Codec, Wordlist};
+
+/// A word that can be typed, along with every way of typing it in normalized form.
+#[derive(Clone, Debug)]
+struct TypeableWord {
+    word: String,
+    spellings: Vec<String>,
+}
+
+/// Words typed in one at a time, like when copying a paper backup by hand.
+///
+/// Every character is checked as it is typed, so that a character that does not continue
+/// any word is refused, and a word is only accepted once it is a whole word of the list
+/// that belongs at its position. For the PGP Word List, words alternate between
+/// the two-syllable list at even positions and the three-syllable list at odd positions,
+/// counting from zero. A `?` in place of a word marks it as unreadable,
+/// for forward error correction to recover.
+#[derive(Clone, Debug)]
+pub struct Typist {
+    /// The words that can be typed, by position modulo the number of lists.
+    lists: Vec<Vec<TypeableWord>>,
+    /// ASCII spellings that Norwegian and German letters are read as.
+    spelled: &'static [(char, &'static [&'static str])],
+    /// The bytes of the accepted words, or `None` for words marked as unreadable.
+    symbols: Vec<Option<u8>>,
+    /// The word being typed, as typed.
+    current: String,
+}
+
+impl Typist {
+    /// Typing words of `codec`.
+    ///
+    /// Fails with [`std::io::ErrorKind::Unsupported`] for codecs that do not have
+    /// exactly one word for each byte, like RFC 1751, proquints and all 1296 EFF words,
+    /// and for codecs whose decoder was not enabled at compile-time.
+    pub fn for_codec(codec: Codec) -> Result<Self, std::io::Error> {
+        if codec.block_len() != 1 || codec.word_span(1) != (1..=1) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("words of codec {codec} cannot be typed one at a time, since they do not stand for one byte each"),
+            ));
+        }
+        codec.decoder(std::iter::empty::<Result<char, std::io::Error>>())?;
+
+        let spelled = match codec {
+            #[cfg(any(feature = "decode_norwegian", feature = "decode_german"))]
+            Codec::Norwegian | Codec::German => crate::decode::LETTER_SPELLINGS,
+            _ => &[],
+        };
+        let lists = crate::decode::byte_wordlists(codec)
+            .expect("the decoder of a codec with one word for each byte has a list of the words")
+            .into_iter()
+            .map(|list| {
+                list.into_iter()
+                    .map(|(word, spellings)| TypeableWord {
+                        word: word.to_string(),
+                        spellings: spellings.into_iter().map(str::to_string).collect(),
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self::new(lists, spelled))
+    }
+
+    /// Typing words of a wordlist loaded at run-time.
+    pub fn for_wordlist(wordlist: &Wordlist) -> Self {
+        let list = wordlist
+            .words()
+            .iter()
+            .map(|word| TypeableWord {
+                word: word.clone(),
+                spellings: vec![normalize_word(word)],
+            })
+            .collect();
+        Self::new(vec![list], &[])
+    }
+
+    fn new(
+        lists: Vec<Vec<TypeableWord>>,
+        spelled: &'static [(char, &'static [&'static str])],
+    ) -> Self {
+        Self {
+            lists,
+            spelled,
+            symbols: vec![],
+            current: String::new(),
+        }
+    }
+
+    /// The words accepted so far, as they are written in the list, with `?` for unreadable words.
+    pub fn words(&self) -> Vec<&str> {
+        self.symbols
+            .iter()
+            .enumerate()
+            .map(|(position, symbol)| match symbol {
+                Some(byte) => self.word(position, *byte),
+                None => "?",
+            })
+            .collect()
+    }
+
+    /// The bytes of the words accepted so far, or `None` for words marked as unreadable.
+    pub fn symbols(&self) -> &[Option<u8>] {
+        &self.symbols
+    }
+
+    /// The words accepted so far, separated by spaces, for decoding.
+    pub fn text(&self) -> String {
+        self.words().join(" ")
+    }
+
+    /// The word being typed, as typed.
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+
+    /// Which one of the lists the next word is from. For the PGP Word List,
+    /// 0 is the two-syllable list and 1 the three-syllable list. Other codecs have one list.
+    pub fn list_index(&self) -> usize {
+        self.symbols.len() % self.lists.len()
+    }
+
+    /// The words that begin with what has been typed of the current word.
+    pub fn candidates(&self) -> Vec<&str> {
+        let typed = self.normalized(&self.current);
+        self.lists[self.list_index()]
+            .iter()
+            .filter(|word| word.spellings.iter().any(|s| s.starts_with(&typed)))
+            .map(|word| word.word.as_str())
+            .collect()
+    }
+
+    /// Type a character of the current word.
+    /// Returns `false`, leaving the word as it was, if no word continues with the character.
+    ///
+    /// Words of the other list of the PGP Word List are let through,
+    /// so that [`Typist::finish_word`] can tell that a word is missing or typed twice.
+    pub fn push_char(&mut self, c: char) -> bool {
+        if c == '?' || self.current == "?" {
+            if !self.current.is_empty() {
+                return false;
+            }
+            self.current.push(c);
+            return true;
+        }
+        self.current.push(c);
+        let typed = self.normalized(&self.current);
+        let continued = self
+            .lists
+            .iter()
+            .flatten()
+            .any(|word| word.spellings.iter().any(|s| s.starts_with(&typed)));
+        if !continued {
+            self.current.pop();
+        }
+        continued
+    }
+
+    /// Remove the last character typed. If nothing has been typed of the current word,
+    /// the previous word is opened for editing instead.
+    pub fn backspace(&mut self) {
+        if self.current.pop().is_none() {
+            if let Some(position) = self.symbols.len().checked_sub(1) {
+                self.current = match self.symbols.pop().unwrap() {
+                    Some(byte) => self.word(position, byte).to_string(),
+                    None => "?".to_string(),
+                };
+            }
+        }
+    }
+
+    /// Clear the current word, or remove the previous word if nothing has been typed.
+    pub fn undo(&mut self) {
+        if self.current.is_empty() {
+            self.symbols.pop();
+        } else {
+            self.current.clear();
+        }
+    }
+
+    /// Complete the current word. If only one word begins with what has been typed,
+    /// that word is accepted. Otherwise, the current word is extended as far as all of the
+    /// words that begin with it agree. Returns the words that begin with what has been typed.
+    pub fn complete(&mut self) -> Vec<String> {
+        let typed = self.normalized(&self.current);
+        let matching: Vec<(&String, &str)> = self.lists[self.list_index()]
+            .iter()
+            .flat_map(|word| {
+                word.spellings
+                    .iter()
+                    .filter(|s| s.starts_with(&typed))
+                    .map(|s| (s, word.word.as_str()))
+            })
+            .collect();
+        let mut candidates: Vec<String> = matching.iter().map(|(_, w)| w.to_string()).collect();
+        candidates.dedup();
+        let common = match matching.split_first() {
+            Some(((first, _), rest)) => rest
+                .iter()
+                .fold(first.as_str(), |common, (s, _)| common_prefix(common, s)),
+            None => &typed,
+        };
+
+        if let [word] = &candidates[..] {
+            self.current = word.clone();
+            self.finish_word()
+                .expect("the only word that begins with what has been typed is a whole word");
+        } else {
+            self.current.push_str(&common[typed.len()..]);
+        }
+        candidates
+    }
+
+    /// Accept the current word, which has to be a whole word of the list for its position.
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidData`] otherwise, leaving the word to be edited.
+    pub fn finish_word(&mut self) -> Result<(), std::io::Error> {
+        if self.current.is_empty() {
+            return Ok(());
+        }
+        if self.current == "?" {
+            self.symbols.push(None);
+            self.current.clear();
+            return Ok(());
+        }
+
+        let message = match self.lookup() {
+            Some((list, byte)) if list == self.list_index() => {
+                self.symbols.push(Some(byte));
+                self.current.clear();
+                return Ok(());
+            }
+            Some(_) => format!(
+                "\"{}\" is a word of the other list, but word {} is from this one; is a word missing or typed twice?",
+                self.current,
+                self.symbols.len() + 1
+            ),
+            None => format!("\"{}\" is not a whole word", self.current),
+        };
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            message,
+        ))
+    }
+
+    /// Whether the current word is a whole word that no other word of its list begins with,
+    /// so that what comes after it can only be the next word.
+    pub(crate) fn ends_word(&self) -> bool {
+        let typed = self.normalized(&self.current);
+        let mut matching = self.lists[self.list_index()]
+            .iter()
+            .filter(|word| word.spellings.iter().any(|s| s.starts_with(&typed)));
+        matches!(
+            (matching.next(), matching.next()),
+            (Some(word), None) if word.spellings.contains(&typed)
+        )
+    }
+
+    /// The list and the byte of the current word, if it is a whole word of any list,
+    /// looking in the list that belongs at its position first.
+    pub(crate) fn lookup(&self) -> Option<(usize, u8)> {
+        let typed = self.normalized(&self.current);
+        let lists_len = self.lists.len();
+        (0..lists_len)
+            .map(|i| (self.list_index() + i) % lists_len)
+            .find_map(|list| {
+                self.lists[list]
+                    .iter()
+                    .position(|word| word.spellings.contains(&typed))
+                    .map(|byte| (list, byte as u8))
+            })
+    }
+
+    /// Up to three words that can come at `position` and are close to `typed`, closest first.
+    ///
+    /// Words are close when they are at most two edits away, or for longer words,
+    /// at most a third of the letters typed, or when they sound the same
+    /// and have at least three consonant sounds.
+    /// Words that are as many edits away are ordered by how much alike they sound.
+    pub(crate) fn suggestions(&self, position: usize, typed: &str) -> Vec<&str> {
+        let typed = self.normalized(typed);
+        let typed_key = phonetic_key(&typed);
+        let max_distance = (typed.chars().count() / 3).max(2);
+        let mut close: Vec<_> =
+            self.lists[position % self.lists.len()]
+                .iter()
+                .filter_map(|word| {
+                    let (distance, key_distance) = word
+                        .spellings
+                        .iter()
+                        .map(|spelling| {
+                            let key_distance = edit_distance(&phonetic_key(spelling), &typed_key);
+                            (edit_distance(spelling, &typed), key_distance)
+                        })
+                        .min()?;
+                    (distance <= max_distance || key_distance == 0 && typed_key.len() >= 3)
+                        .then_some((distance, key_distance, word.word.as_str()))
+                })
+                .collect();
+        close.sort_by_key(|&(distance, key_distance, _)| (distance, key_distance));
+        close.into_iter().take(3).map(|(_, _, word)| word).collect()
+    }
+
+    /// Number of lists that the words alternate between.
+    pub(crate) fn lists_len(&self) -> usize {
+        self.lists.len()
+    }
+
+    /// The word for `byte` at `position`.
+    pub(crate) fn word(&self, position: usize, byte: u8) -> &str {
+        &self.lists[position % self.lists.len()][byte as usize].word
+    }
+
+    /// What has been typed, in the form that the decoders read it in.
+    fn normalized(&self, typed: &str) -> String {
+        let mut normalized = String::new();
+        for c in typed.chars().flat_map(char::to_lowercase) {
+            if let Some((_, spellings)) = self.spelled.iter().find(|(letter, _)| *letter == c) {
+                normalized.push_str(spellings[0]);
+                continue;
+            }
+            normalize(c, &mut normalized);
+        }
+        normalized
+            .chars()
+            .filter(|&c| c != ' ')
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+}
+
+/// A rough key for how a normalized word sounds, like in Soundex: consonants that sound alike
+/// are written the same, vowels and the letters h, w and y are left out,
+/// and the same sound written several times in a row is written once.
+fn phonetic_key(word: &str) -> String {
+    let mut key = String::new();
+    let mut last = None;
+    for c in word.chars() {
+        let sound = match c {
+            'b' | 'f' | 'p' | 'v' => Some('1'),
+            'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+            'd' | 't' => Some('3'),
+            'l' => Some('4'),
+            'm' | 'n' => Some('5'),
+            'r' => Some('6'),
+            _ => None,
+        };
+        if let Some(sound) = sound.filter(|&sound| last != Some(sound)) {
+            key.push(sound);
+        }
+        last = sound;
+    }
+    key
+}
+
+/// The longest beginning that `a` and `b` have in common.
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .chars()
+        .zip(b.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    &a[..len]
+}
+
+#[cfg(test)]
+mod test_cases_typing {
+    use super::Typist;
+    use crate::{Codec, Wordlist};
+    use test_case::test_case;
+
+    fn type_words(typist: &mut Typist, text: &str) -> Result<(), std::io::Error> {
+        for word in text.split(' ') {
+            for c in word.chars() {
+                assert!(typist.push_char(c), "{c:?} of {word:?} was refused");
+            }
+            typist.finish_word()?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test_case("aardvark adroitness", &[Some(0x00), Some(0x00)] ; "even and odd")]
+    #[test_case("Absurd ADVISER ? adroitness", &[Some(0x01), Some(0x01), None, Some(0x00)] ; "mixed case and unreadable")]
+    fn test_positive_typist_pgp(text: &str, expected: &[Option<u8>]) {
+        let mut typist = Typist::for_codec(Codec::Pgp).unwrap();
+        type_words(&mut typist, text).unwrap();
+        assert_eq!(typist.symbols(), expected);
+        assert_eq!(typist.text(), text.to_lowercase());
+        assert_eq!(typist.list_index(), expected.len() % 2);
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test_case("adroitness", "\"adroitness\" is a word of the other list, but word 1 is from this one; is a word missing or typed twice?" ; "wrong list")]
+    #[test_case("aard", "\"aard\" is not a whole word" ; "beginning of a word")]
+    fn test_negative_typist_pgp(text: &str, expected: &str) {
+        let mut typist = Typist::for_codec(Codec::Pgp).unwrap();
+        let error = type_words(&mut typist, text).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), expected);
+        assert_eq!(typist.current(), text);
+        assert!(typist.symbols().is_empty());
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test]
+    fn test_typist_refuses_characters_that_continue_no_word() {
+        let mut typist = Typist::for_codec(Codec::Pgp).unwrap();
+        assert!(typist.push_char('a'));
+        assert!(!typist.push_char('q'));
+        assert!(!typist.push_char('?'));
+        assert_eq!(typist.current(), "a");
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test_case("all", &["allow"], "", "allow" ; "unique")]
+    #[test_case("bac", &["backfield", "backward"], "back", "" ; "extended to common beginning")]
+    #[test_case("al", &["Algol", "allow", "alone"], "al", "" ; "not extended")]
+    fn test_typist_complete(typed: &str, candidates: &[&str], current: &str, text: &str) {
+        let mut typist = Typist::for_codec(Codec::Pgp).unwrap();
+        for c in typed.chars() {
+            typist.push_char(c);
+        }
+        assert_eq!(typist.complete(), candidates);
+        assert_eq!(typist.current(), current);
+        assert_eq!(typist.text(), text);
+    }
+
+    #[cfg(feature = "decode_pgp")]
+    #[test]
+    fn test_typist_backspace_and_undo() {
+        let mut typist = Typist::for_codec(Codec::Pgp).unwrap();
+        type_words(&mut typist, "aardvark adroitness").unwrap();
+        typist.backspace();
+        assert_eq!(typist.current(), "adroitness");
+        assert_eq!(typist.text(), "aardvark");
+        typist.backspace();
+        assert_eq!(typist.current(), "adroitnes");
+        typist.undo();
+        assert_eq!(typist.current(), "");
+        typist.undo();
+        assert_eq!(typist.text(), "");
+        assert_eq!(typist.list_index(), 0);
+    }
+
+    #[cfg(feature = "decode_norwegian")]
+    #[test_case("blåbær" ; "as written")]
+    #[test_case("BLÅBÆR" ; "uppercase")]
+    #[test_case("blaabaer" ; "first spelling")]
+    #[test_case("blabaer" ; "second spelling")]
+    fn test_positive_typist_norwegian(word: &str) {
+        let mut typist = Typist::for_codec(Codec::Norwegian).unwrap();
+        type_words(&mut typist, word).unwrap();
+        assert_eq!(typist.symbols(), &[Some(0x10)]);
+        assert_eq!(typist.text(), "blåbær");
+    }
+
+    #[test]
+    fn test_positive_typist_wordlist() {
+        let words: Vec<String> = (0..256).map(|i| format!("w{i:03}")).collect();
+        let mut typist = Typist::for_wordlist(&Wordlist::new(words).unwrap());
+        type_words(&mut typist, "W255 w007").unwrap();
+        assert_eq!(typist.symbols(), &[Some(0xFF), Some(0x07)]);
+        assert_eq!(typist.list_index(), 0);
+    }
+    #[cfg(feature = "decode_pgp")]
+    #[test_case(1, "advizer", "adviser" ; "one edit")]
+    #[test_case(1, "fonetik", "phonetic" ; "sounds the same")]
+    #[test_case(0, "tiekoon", "tycoon" ; "sounds the same two syllables")]
+    #[test_case(0, "xlxlxlxl", "" ; "like no word")]
+    #[test_case(0, "xy", "" ; "too short to sound like a word")]
+    fn test_typist_suggestions(position: usize, typed: &str, expected: &str) {
+        let typist = Typist::for_codec(Codec::Pgp).unwrap();
+        let suggestions = typist.suggestions(position, typed);
+        assert_eq!(suggestions.first().copied().unwrap_or(""), expected);
+    }
+
+    #[test_case("phonetic", "1532")]
+    #[test_case("fonetik", "1532")]
+    #[test_case("tycoon", "325")]
+    #[test_case("classroom", "24265")]
+    fn test_phonetic_key(word: &str, key: &str) {
+        assert_eq!(super::phonetic_key(word), key);
+    }
+
+    #[test_case(Codec::Rfc1751 ; "rfc1751")]
+    #[test_case(Codec::Proquint ; "proquint")]
+    #[test_case(Codec::EffFull ; "eff full")]
+    fn test_negative_typist_unsupported_codec(codec: Codec) {
+        let error = Typist::for_codec(codec).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,609 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Checking a transcript, word by word, against the data it was made from
+//! or against another transcript of the same data.
+
+use crate::decode::read_word;
+use crate::{Ignored, SourcePosition, Typist};
+use std::cell::Cell;
+
+/// A word of a transcript, as it was read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TranscriptWord {
+    /// The word as typed.
+    pub(crate) typed: String,
+    pub(crate) source: SourcePosition,
+    /// The byte of the word, or `None` if it is marked as unreadable or is not a word
+    /// that can come at its position.
+    pub(crate) symbol: Option<u8>,
+    /// The list and the byte of the word wherever it comes, or `None` if it is not a word.
+    pub(crate) word: Option<(usize, u8)>,
+}
+
+/// The words of a transcript, for checking against the data it was made from.
+///
+/// Words are read the way the decoders read them, skipping what they skip by default,
+/// like line numbers and comments. Unlike when decoding, reading goes on past a word that
+/// is not a word of the list, so that every word that does not match can be reported.
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    pub(crate) typist: Typist,
+    pub(crate) words: Vec<TranscriptWord>,
+}
+
+/// A word of a transcript that does not match the word of the data at its position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Position of the word, counting from zero.
+    pub position: usize,
+    /// Where the word starts in the transcript, or `None` if the transcript ends before it.
+    pub source: Option<SourcePosition>,
+    /// The word that was expected, or `None` if the data ends before it.
+    pub expected: Option<String>,
+    /// The word as typed, or `None` if the transcript ends before it.
+    pub typed: Option<String>,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "word {}", self.position + 1)?;
+        if let Some(source) = self.source {
+            write!(f, " at {source}")?;
+        }
+        match (&self.expected, &self.typed) {
+            (Some(expected), Some(typed)) => {
+                write!(f, ": expected \"{expected}\", typed \"{typed}\"")
+            }
+            (Some(expected), None) => {
+                write!(
+                    f,
+                    ": expected \"{expected}\", missing from the end of the transcript"
+                )
+            }
+            (None, Some(typed)) => write!(f, ": typed \"{typed}\", past the end of the data"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// A word of a transcript, as told apart from the word of another transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffWord {
+    /// Position of the word, and of the byte it stands for, counting from zero.
+    pub offset: usize,
+    /// Where the word starts in the transcript.
+    pub source: SourcePosition,
+    /// The word as typed.
+    pub typed: String,
+    /// The byte of the word, or `None` if it is marked as unreadable or is not a word.
+    pub byte: Option<u8>,
+}
+
+impl std::fmt::Display for DiffWord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "byte {} \"{}\" ", self.offset, self.typed)?;
+        match self.byte {
+            Some(byte) => write!(f, "(0x{byte:02x})")?,
+            None if self.typed == "?" => write!(f, "(unreadable)")?,
+            None => write!(f, "(not a word)")?,
+        }
+        write!(f, " at {}", self.source)
+    }
+}
+
+/// A difference between two transcripts of the same data, `a` and `b`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// A word of `a` that is another word in `b`.
+    Changed { a: DiffWord, b: DiffWord },
+    /// A word of `a` that is missing from `b`, where it would come before the word at offset `b`.
+    Deleted { a: DiffWord, b: usize },
+    /// A word of `b` that is missing from `a`, where it would come before the word at offset `a`.
+    Inserted { a: usize, b: DiffWord },
+}
+
+impl std::fmt::Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difference::Changed { a, b } => write!(f, "changed: {a} | {b}"),
+            Difference::Deleted { a, b } => write!(f, "deleted: {a} | before byte {b}"),
+            Difference::Inserted { a, b } => write!(f, "inserted: before byte {a} | {b}"),
+        }
+    }
+}
+
+impl Transcript {
+    /// Read the words of a transcript using `typist`, which has to have no words typed yet.
+    pub fn read<I>(typist: Typist, chars: I) -> Result<Self, std::io::Error>
+    where
+        I: Iterator<Item = Result<char, std::io::Error>>,
+    {
+        let pos = Cell::new(SourcePosition { line: 1, column: 0 });
+        let mut line_ended = false;
+        let mut chars = Ignored::default().filter(chars).inspect(|c| {
+            let mut next = pos.get();
+            if std::mem::replace(&mut line_ended, matches!(c, Ok('\n'))) {
+                next.line += 1;
+                next.column = 0;
+            }
+            next.column += 1;
+            pos.set(next);
+        });
+
+        let mut reading = Reading {
+            typist,
+            words: vec![],
+            word: None,
+            broken: false,
+        };
+        loop {
+            let read = read_word(&mut chars, String::push, |typed, unreadable| {
+                reading.read_char(typed, unreadable, pos.get())
+            });
+            match read {
+                Some(Ok(Some(_))) => match reading.word {
+                    Some((_, true)) => reading.end_word(),
+                    Some(_) => reading.broken = true,
+                    None => {}
+                },
+                Some(Ok(None)) => reading.end_word(),
+                Some(Err(e)) => return Err(e),
+                None => break,
+            }
+        }
+        reading.end_word();
+        Ok(Self {
+            typist: reading.typist,
+            words: reading.words,
+        })
+    }
+
+    /// Number of words read.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether no words were read.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// The bytes of the words, or `None` for words that are unreadable or not words.
+    pub fn symbols(&self) -> Vec<Option<u8>> {
+        self.words.iter().map(|word| word.symbol).collect()
+    }
+
+    /// The words that do not match the `expected` words, like those that the data
+    /// was encoded as, in order.
+    pub fn compare<S: AsRef<str>>(&self, expected: &[S]) -> Vec<Mismatch> {
+        (0..self.words.len().max(expected.len()))
+            .filter_map(|position| {
+                let typed = self.words.get(position);
+                let expected = expected.get(position).map(AsRef::as_ref);
+                let read = typed
+                    .and_then(|typed| typed.symbol)
+                    .map(|byte| self.typist.word(position, byte));
+                if read.is_some() && read == expected {
+                    return None;
+                }
+                Some(Mismatch {
+                    position,
+                    source: typed.map(|typed| typed.source),
+                    expected: expected.map(str::to_string),
+                    typed: typed.map(|typed| typed.typed.clone()),
+                })
+            })
+            .collect()
+    }
+
+    /// The differences between this transcript and `other`, made by aligning their words
+    /// so that as few words as possible are changed, left out or added.
+    ///
+    /// Words are the same when they are the same word of the list, however they are
+    /// written, so that only the words tell transcripts apart, not how they are laid out.
+    /// Words of the PGP Word List are told apart by the list they are from
+    /// rather than by their position, so that a word that is left out
+    /// does not make all of the words after it differ.
+    pub fn diff(&self, other: &Transcript) -> Vec<Difference> {
+        let (a, b) = (&self.words, &other.words);
+        let prefix = a.iter().zip(b).take_while(|(a, b)| same_word(a, b)).count();
+        let suffix = a[prefix..]
+            .iter()
+            .rev()
+            .zip(b[prefix..].iter().rev())
+            .take_while(|(a, b)| same_word(a, b))
+            .count();
+        let a = &a[prefix..a.len() - suffix];
+        let b = &b[prefix..b.len() - suffix];
+
+        let mut differences = vec![];
+        align(a, b, (prefix, prefix), &mut differences);
+        differences
+    }
+}
+
+/// Add the differences between the words `a` and `b`, at offsets `i` and `j` of their
+/// transcripts on, to `differences`.
+///
+/// This is Hirschberg's algorithm: `a` is split in half, and `b` where it takes the fewest
+/// differences to align the halves with it, so that only a row of distances is kept at a time.
+fn align(
+    a: &[TranscriptWord],
+    b: &[TranscriptWord],
+    (i, j): (usize, usize),
+    differences: &mut Vec<Difference>,
+) {
+    match a {
+        [] => differences.extend(b.iter().enumerate().map(|(k, word)| Difference::Inserted {
+            a: i,
+            b: diff_word(word, j + k),
+        })),
+        [word] if b.is_empty() => differences.push(Difference::Deleted {
+            a: diff_word(word, i),
+            b: j,
+        }),
+        [word] => {
+            // A single word is the first of `b` that is the same, or else changed into the first.
+            let same = b.iter().position(|other| same_word(word, other));
+            let paired = same.unwrap_or(0);
+            for (k, other) in b.iter().enumerate() {
+                if k != paired {
+                    differences.push(Difference::Inserted {
+                        a: i + usize::from(k > paired),
+                        b: diff_word(other, j + k),
+                    });
+                } else if same.is_none() {
+                    differences.push(Difference::Changed {
+                        a: diff_word(word, i),
+                        b: diff_word(other, j + k),
+                    });
+                }
+            }
+        }
+        _ => {
+            let mid = a.len() / 2;
+            let forward = distances(a[..mid].iter(), b.iter());
+            let backward = distances(a[mid..].iter().rev(), b.iter().rev());
+            // Of the splits that take the fewest differences, the last one pairs up the words
+            // of `a` with words of `b` as early as possible.
+            let split = (0..=b.len())
+                .rev()
+                .min_by_key(|&k| forward[k] + backward[b.len() - k])
+                .unwrap();
+            align(&a[..mid], &b[..split], (i, j), differences);
+            align(&a[mid..], &b[split..], (i + mid, j + split), differences);
+        }
+    }
+}
+
+/// The number of words to change, leave out or add to turn the words `a` into each beginning
+/// of the words `b`, from none of them to all of them.
+fn distances<'a, A, B>(a: A, b: B) -> Vec<usize>
+where
+    A: Iterator<Item = &'a TranscriptWord>,
+    B: Iterator<Item = &'a TranscriptWord> + Clone,
+{
+    let mut row: Vec<usize> = (0..=b.clone().count()).collect();
+    for a_word in a {
+        let mut diagonal = row[0];
+        row[0] += 1;
+        for (k, b_word) in b.clone().enumerate() {
+            let changed = diagonal + usize::from(!same_word(a_word, b_word));
+            diagonal = row[k + 1];
+            row[k + 1] = changed.min(row[k + 1] + 1).min(row[k] + 1);
+        }
+    }
+    row
+}
+
+fn diff_word(word: &TranscriptWord, offset: usize) -> DiffWord {
+    DiffWord {
+        offset,
+        source: word.source,
+        typed: word.typed.clone(),
+        byte: word.word.map(|(_, byte)| byte),
+    }
+}
+
+/// Whether two words of transcripts are the same word, or the same thing that is not a word.
+fn same_word(a: &TranscriptWord, b: &TranscriptWord) -> bool {
+    match (a.word, b.word) {
+        (Some(a), Some(b)) => a == b,
+        (None, None) => a.typed.to_lowercase() == b.typed.to_lowercase(),
+        _ => false,
+    }
+}
+
+/// Words of a transcript being read, handed the characters between separators
+/// one at a time by [`read_word`].
+struct Reading {
+    typist: Typist,
+    words: Vec<TranscriptWord>,
+    /// The word being read, and whether it has gone wrong.
+    word: Option<(TranscriptWord, bool)>,
+    /// Whether a separator has come after the word being read, which may still go on
+    /// if it was broken across lines.
+    broken: bool,
+}
+
+impl Reading {
+    /// Read the last character of `typed`, which is what has been read since the last separator,
+    /// at `pos`. Returns whether a word ended with it.
+    fn read_char(&mut self, typed: &str, unreadable: bool, pos: SourcePosition) -> bool {
+        let broken = std::mem::take(&mut self.broken);
+        if unreadable {
+            if broken {
+                self.end_word();
+            }
+            // Like for the decoders, the whole word that a question mark is part of is unreadable.
+            let (word, failed) = self.word.get_or_insert_with(|| (new_word(pos), false));
+            if word.typed != "?" {
+                word.typed = "?".to_string();
+                *failed = false;
+                if !self.typist.current().is_empty() {
+                    self.typist.undo();
+                }
+                self.typist.push_char('?');
+            }
+            return false;
+        }
+
+        let c = typed.chars().last().expect("a character has been read");
+        if broken {
+            // Like the decoders, a word that has not ended goes on past separators
+            // as long as what follows continues it.
+            if self.typist.push_char(c) {
+                let (word, _) = self.word.as_mut().expect("a word has been broken");
+                word.typed.push(c);
+                return self.end_whole_word();
+            }
+            self.end_word();
+        }
+        let (word, failed) = self.word.get_or_insert_with(|| (new_word(pos), false));
+        word.typed.push(c);
+        if *failed {
+            return false;
+        }
+        if !self.typist.push_char(c) {
+            *failed = true;
+            return false;
+        }
+        self.end_whole_word()
+    }
+
+    /// End the word being read if no other word begins with it,
+    /// so that words written together are told apart where one of them ends.
+    fn end_whole_word(&mut self) -> bool {
+        let ends = self.typist.ends_word();
+        if ends {
+            self.end_word();
+        }
+        ends
+    }
+
+    /// End the word being read, if any.
+    fn end_word(&mut self) {
+        if let Some((word, failed)) = self.word.take() {
+            self.words.push(finish_word(&mut self.typist, word, failed));
+        }
+    }
+}
+
+fn new_word(source: SourcePosition) -> TranscriptWord {
+    TranscriptWord {
+        typed: String::new(),
+        source,
+        symbol: None,
+        word: None,
+    }
+}
+
+/// Finish reading a word, which is marked as unreadable in `typist`
+/// if it is not a word that can come at its position.
+fn finish_word(typist: &mut Typist, mut word: TranscriptWord, failed: bool) -> TranscriptWord {
+    if !failed {
+        word.word = typist.lookup();
+    }
+    if failed || typist.finish_word().is_err() {
+        if !typist.current().is_empty() {
+            typist.undo();
+        }
+        typist.push_char('?');
+        typist
+            .finish_word()
+            .expect("a word can always be marked as unreadable");
+    }
+    word.symbol = *typist.symbols().last().unwrap();
+    word
+}
+
+#[cfg(test)]
+mod test_cases_verify {
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    use super::{DiffWord, Difference, Mismatch};
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    use crate::SourcePosition;
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    use test_case::test_case;
+    #[cfg(any(
+        all(feature = "encode_pgp", feature = "decode_pgp"),
+        all(feature = "encode_norwegian", feature = "decode_norwegian"),
+        all(feature = "encode_bytewords", feature = "decode_bytewords")
+    ))]
+    use {super::Transcript, crate::Codec, crate::Typist};
+
+    #[cfg(any(
+        all(feature = "encode_pgp", feature = "decode_pgp"),
+        all(feature = "encode_norwegian", feature = "decode_norwegian"),
+        all(feature = "encode_bytewords", feature = "decode_bytewords")
+    ))]
+    fn read(codec: Codec, text: &str) -> Transcript {
+        Transcript::read(Typist::for_codec(codec).unwrap(), text.chars().map(Ok)).unwrap()
+    }
+
+    #[cfg(any(
+        all(feature = "encode_pgp", feature = "decode_pgp"),
+        all(feature = "encode_norwegian", feature = "decode_norwegian"),
+        all(feature = "encode_bytewords", feature = "decode_bytewords")
+    ))]
+    fn encode(codec: Codec, bytes: &[u8]) -> Vec<&'static str> {
+        codec
+            .encoder(bytes.iter().copied().map(Ok::<_, std::io::Error>))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test_case("aardvark adroitness absurd adviser" ; "plain")]
+    #[test_case("   1  0000:  Aardvark ADROITNESS  # two words\n\n   2  0002:\tabsurd-adviser\n" ; "laid out")]
+    #[test_case("aardvarkadroitness\nabsurdadviser" ; "written together")]
+    #[test_case("aardvark adroit\nness absur\nd adviser" ; "broken across lines")]
+    fn test_positive_verify_pgp(text: &str) {
+        let transcript = read(Codec::Pgp, text);
+        assert_eq!(transcript.len(), 4);
+        assert_eq!(transcript.symbols(), [Some(0), Some(0), Some(1), Some(1)]);
+        assert!(transcript
+            .compare(&encode(Codec::Pgp, &[0, 0, 1, 1]))
+            .is_empty());
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test_case("aardvark adroitnes absurd adviser", 1, 10, "adroitnes" ; "misspelled")]
+    #[test_case("aardvark\nadxroitness absurd adviser", 2, 1, "adxroitness" ; "not a word")]
+    #[test_case("aardvark adviser absurd adviser", 1, 10, "adviser" ; "other word")]
+    #[test_case("aardvark absurd absurd adviser", 1, 10, "absurd" ; "word of the other list")]
+    #[test_case("aardvark ? absurd adviser", 1, 10, "?" ; "unreadable")]
+    #[test_case("aardvark adro?tness absurd adviser", 1, 10, "?" ; "unreadable in a word")]
+    fn test_negative_verify_pgp(text: &str, line: usize, column: usize, typed: &str) {
+        let mismatches = read(Codec::Pgp, text).compare(&encode(Codec::Pgp, &[0, 0, 1, 1]));
+        assert_eq!(
+            mismatches,
+            [Mismatch {
+                position: 1,
+                source: Some(SourcePosition { line, column }),
+                expected: Some("adroitness".to_string()),
+                typed: Some(typed.to_string()),
+            }]
+        );
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test_case("aardvark", "word 2: expected \"adroitness\", missing from the end of the transcript" ; "missing")]
+    #[test_case("aardvark adroitness absurd", "word 3 at line 1, column 21: typed \"absurd\", past the end of the data" ; "extra")]
+    #[test_case("aardvark adviser", "word 2 at line 1, column 10: expected \"adroitness\", typed \"adviser\"" ; "wrong")]
+    fn test_negative_verify_pgp_display(text: &str, expected: &str) {
+        let mismatches = read(Codec::Pgp, text).compare(&encode(Codec::Pgp, &[0, 0]));
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].to_string(), expected);
+    }
+
+    #[cfg(all(feature = "encode_norwegian", feature = "decode_norwegian"))]
+    #[test]
+    fn test_positive_verify_norwegian_spellings() {
+        let transcript = read(Codec::Norwegian, "blaabaer bjorn ØRN");
+        assert!(transcript
+            .compare(&encode(Codec::Norwegian, &[0x10, 0x0B, 0xFC]))
+            .is_empty());
+    }
+
+    #[cfg(all(feature = "encode_bytewords", feature = "decode_bytewords"))]
+    #[test]
+    fn test_positive_verify_bytewords_minimal_with_checksum() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let words = encode(Codec::BytewordsMinimal, &bytes);
+        let transcript = read(Codec::BytewordsMinimal, &words.concat());
+        assert_eq!(transcript.len(), 260);
+        assert!(transcript.compare(&words).is_empty());
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    fn diff_word(offset: usize, column: usize, typed: &str, byte: Option<u8>) -> DiffWord {
+        DiffWord {
+            offset,
+            source: SourcePosition { line: 1, column },
+            typed: typed.to_string(),
+            byte,
+        }
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test_case("aardvark adroitness absurd adviser" ; "same")]
+    #[test_case("AARDVARK ADROITNESS\nABSURD ADVISER" ; "other case and lines")]
+    #[test_case("   1  0000:  aardvark adroit\nness  # two words\n   2  0002:\tabsurdadviser\n" ; "laid out")]
+    fn test_positive_diff_pgp_same(b: &str) {
+        let a = read(Codec::Pgp, "aardvark adroitness absurd adviser");
+        assert_eq!(a.diff(&read(Codec::Pgp, b)), []);
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test]
+    fn test_negative_diff_pgp_changed() {
+        let a = read(Codec::Pgp, "aardvark adroitness absurd adviser");
+        let b = read(Codec::Pgp, "aardvark adroitness absurd advizer");
+        assert_eq!(
+            a.diff(&b),
+            [Difference::Changed {
+                a: diff_word(3, 28, "adviser", Some(0x01)),
+                b: diff_word(3, 28, "advizer", None),
+            }]
+        );
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test]
+    fn test_negative_diff_pgp_deleted_and_inserted() {
+        let a = read(Codec::Pgp, "aardvark adroitness absurd adviser");
+        let b = read(Codec::Pgp, "aardvark absurd adviser aimless");
+        assert_eq!(
+            a.diff(&b),
+            [
+                Difference::Deleted {
+                    a: diff_word(1, 10, "adroitness", Some(0x00)),
+                    b: 1,
+                },
+                Difference::Inserted {
+                    a: 4,
+                    b: diff_word(3, 25, "aimless", Some(0x08)),
+                },
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "encode_pgp", feature = "decode_pgp"))]
+    #[test]
+    fn test_negative_diff_pgp_display() {
+        let a = read(Codec::Pgp, "aardvark ? absurd");
+        let b = read(Codec::Pgp, "aardvark adroitness");
+        let differences: Vec<_> = a.diff(&b).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            differences,
+            [
+                "changed: byte 1 \"?\" (unreadable) at line 1, column 10 | byte 1 \"adroitness\" (0x00) at line 1, column 10",
+                "deleted: byte 2 \"absurd\" (0x01) at line 1, column 12 | before byte 2",
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "encode_norwegian", feature = "decode_norwegian"))]
+    #[test]
+    fn test_positive_diff_norwegian_spellings() {
+        let a = read(Codec::Norwegian, "blåbær bjørn ørn");
+        let b = read(Codec::Norwegian, "BLAABAER\nbjorn oern");
+        assert_eq!(a.diff(&b), []);
+    }
+}
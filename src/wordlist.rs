@@ -0,0 +1,265 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Wordlists loaded at run-time.
+
+use crate::normalize::normalize_word;
+use std::collections::HashMap;
+
+/// Number of words in a wordlist, one for each value of a byte.
+const WORDLIST_LEN: usize = 256;
+
+/// Wordlist of 256 words of one's own, loaded at run-time.
+///
+/// Bytes are encoded one word per byte with [`RadixEncode`](crate::RadixEncode),
+/// and decoded with [`RadixDecode`](crate::RadixDecode). Words are compared in the form
+/// the decoder reads them in: lowercase, and with diacritics removed if the unicode feature
+/// is enabled. The decoder reads words written together as long as they can only be split
+/// one way, which [`Wordlist::require_prefix_free`] makes sure of for any words.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wordlist {
+    words: Vec<String>,
+    /// The words in normalized form, in the same order.
+    normalized: Vec<String>,
+}
+
+impl Wordlist {
+    /// Wordlist of the given words, the first one standing for the byte `0x00`.
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidData`] unless there are exactly 256 words,
+    /// none of them empty or containing whitespace or `?`, and no two of them the same
+    /// in normalized form.
+    pub fn new<S: Into<String>>(
+        words: impl IntoIterator<Item = S>,
+    ) -> Result<Self, std::io::Error> {
+        let words: Vec<String> = words.into_iter().map(Into::into).collect();
+        if words.len() != WORDLIST_LEN {
+            return Err(invalid(format!(
+                "a wordlist needs {WORDLIST_LEN} words, but this one has {}",
+                words.len()
+            )));
+        }
+        if let Some(word) = words
+            .iter()
+            .find(|word| word.is_empty() || word.contains(|c: char| c.is_whitespace() || c == '?'))
+        {
+            return Err(invalid(format!(
+                "word {word:?} is empty or contains whitespace or ?, which end words or mark them as unreadable"
+            )));
+        }
+        let normalized: Vec<String> = words.iter().map(|word| normalize_word(word)).collect();
+        let mut seen = HashMap::with_capacity(WORDLIST_LEN);
+        for (word, normalized) in words.iter().zip(&normalized) {
+            match seen.insert(normalized, word) {
+                Some(other) if other == word => {
+                    return Err(invalid(format!(
+                        "word {word:?} appears more than once in the wordlist"
+                    )))
+                }
+                Some(other) => {
+                    return Err(invalid(format!(
+                        "words {other:?} and {word:?} are read as the same word"
+                    )))
+                }
+                None => {}
+            }
+        }
+        Ok(Self { words, normalized })
+    }
+
+    /// Read a wordlist of one word per line, split with [`Wordlist::lines`].
+    pub fn parse(text: &str) -> Result<Self, std::io::Error> {
+        Self::new(Self::lines(text))
+    }
+
+    /// The words of a wordlist of one word per line, without checking them.
+    /// Blank lines, and lines starting with `#`, are skipped.
+    pub fn lines(text: &str) -> impl Iterator<Item = &str> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    }
+
+    /// The words, as given.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// Make sure that no word is the beginning of another, so that words written together
+    /// can be told apart, like `adultamulet` for `adult amulet`.
+    pub fn require_prefix_free(self) -> Result<Self, std::io::Error> {
+        let mut sorted: Vec<usize> = (0..WORDLIST_LEN).collect();
+        sorted.sort_by(|&a, &b| self.normalized[a].cmp(&self.normalized[b]));
+        // A word that begins with another comes right after it when sorted, or after
+        // other words that also begin with it.
+        if let Some(w) = sorted
+            .windows(2)
+            .find(|w| self.normalized[w[1]].starts_with(&self.normalized[w[0]]))
+        {
+            return Err(invalid(format!(
+                "word {:?} is the beginning of {:?}, so they cannot be told apart when written together",
+                self.words[w[0]], self.words[w[1]]
+            )));
+        }
+        Ok(self)
+    }
+
+    /// Make sure that it takes at least `min` letters added, removed or replaced
+    /// to turn any word into another, so that a misspelled word is not taken for another one.
+    pub fn require_min_distance(self, min: usize) -> Result<Self, std::io::Error> {
+        for (i, a) in self.normalized.iter().enumerate() {
+            for (j, b) in self.normalized.iter().enumerate().skip(i + 1) {
+                let distance = edit_distance(a, b);
+                if distance < min {
+                    return Err(invalid(format!(
+                        "words {:?} and {:?} are {distance} edits apart, but need to be at least {min}",
+                        self.words[i], self.words[j]
+                    )));
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Encode bytes as words from this wordlist.
+    #[cfg(feature = "encode")]
+    pub fn encoder<I: Iterator>(&self, bytes: I) -> crate::RadixEncode<'_, I, String> {
+        crate::RadixEncode::new(bytes, &self.words).expect("a wordlist has 256 words")
+    }
+
+    /// Decode words from this wordlist into bytes.
+    #[cfg(feature = "decode")]
+    pub fn decoder<I: Iterator>(&self, chars: I) -> crate::RadixDecode<I> {
+        crate::RadixDecode::new(chars, &self.words).expect("words of a wordlist are unique")
+    }
+}
+
+fn invalid(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Number of letters added, removed or replaced to turn `a` into `b` (Levenshtein distance).
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod test_cases_wordlist {
+    use super::{edit_distance, Wordlist};
+    use test_case::test_case;
+
+    /// Words `a000` to `a255`, with `replace` put in place of some of them.
+    fn words(replace: &[(usize, &str)]) -> Vec<String> {
+        let mut words: Vec<String> = (0..256).map(|i| format!("a{i:03}")).collect();
+        for &(i, word) in replace {
+            words[i] = word.to_string();
+        }
+        words
+    }
+
+    #[test_case("kitten", "sitting", 3)]
+    #[test_case("adult", "adult", 0)]
+    #[test_case("", "abc", 3)]
+    #[test_case("flaw", "lawn", 2)]
+    fn test_edit_distance(a: &str, b: &str, expected: usize) {
+        assert_eq!(edit_distance(a, b), expected);
+    }
+
+    #[test]
+    fn test_positive_wordlist_parse() {
+        let text = format!("# Our words\n\n{}\n", words(&[]).join("\n  "));
+        let wordlist = Wordlist::parse(&text).unwrap();
+        assert_eq!(wordlist.words(), words(&[]));
+        assert!(wordlist.require_prefix_free().is_ok());
+    }
+
+    #[test]
+    fn test_negative_wordlist_parse_several_words_per_line() {
+        let text = words(&[])
+            .chunks(8)
+            .map(|line| line.join(" "))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(Wordlist::lines(&text).count(), 32);
+        let error = Wordlist::parse(&text).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "a wordlist needs 256 words, but this one has 32"
+        );
+    }
+
+    #[test_case(&words(&[])[..255], "a wordlist needs 256 words, but this one has 255" ; "too few words")]
+    #[test_case(&words(&[(7, "a000")]), "word \"a000\" appears more than once in the wordlist" ; "duplicate")]
+    #[test_case(&words(&[(7, "A000")]), "words \"a000\" and \"A000\" are read as the same word" ; "duplicate in other case")]
+    #[test_case(&words(&[(7, "a 7")]), "word \"a 7\" is empty or contains whitespace or ?, which end words or mark them as unreadable" ; "whitespace")]
+    #[test_case(&words(&[(7, "")]), "word \"\" is empty or contains whitespace or ?, which end words or mark them as unreadable" ; "empty")]
+    fn test_negative_wordlist_new(words: &[String], expected: &str) {
+        let error = Wordlist::new(words.iter().cloned()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test]
+    fn test_negative_wordlist_not_prefix_free() {
+        let wordlist = Wordlist::new(words(&[(7, "a00"), (9, "b")])).unwrap();
+        let error = wordlist.require_prefix_free().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "word \"a00\" is the beginning of \"a000\", so they cannot be told apart when written together"
+        );
+    }
+
+    #[test]
+    fn test_wordlist_min_distance() {
+        let wordlist = Wordlist::new(words(&[])).unwrap();
+        assert!(wordlist.clone().require_min_distance(1).is_ok());
+        let error = wordlist.require_min_distance(2).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "words \"a000\" and \"a001\" are 1 edits apart, but need to be at least 2"
+        );
+    }
+
+    #[cfg(all(feature = "encode", feature = "decode"))]
+    #[test_case(" " ; "separated")]
+    #[test_case("" ; "written together")]
+    fn test_positive_wordlist_roundtrip(separator: &str) {
+        let wordlist = Wordlist::new(words(&[(0, "Zero"), (255, "ÿ")])).unwrap();
+        let bytes_orig: Vec<u8> = (0..=255).rev().collect();
+        let encoded = wordlist
+            .encoder(bytes_orig.iter().map(|&b| Ok::<_, std::io::Error>(b)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(encoded.len(), bytes_orig.len());
+        let text = encoded.join(separator);
+        let decoded = wordlist
+            .decoder(text.chars().map(Ok))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, bytes_orig);
+    }
+}
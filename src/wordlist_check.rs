@@ -0,0 +1,445 @@
+/*
+ * Copyright (c) 2023 Erik Nordstrøm <erik@nordstroem.no>
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR
+ * ANY SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN
+ * ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF
+ * OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+ */
+
+//! Quality analysis of candidate wordlists.
+
+use crate::normalize::normalize_word;
+use crate::wordlist::edit_distance;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+/// Quality report of a candidate wordlist.
+///
+/// Words are compared in the form the decoders read them in: lowercase, and with diacritics
+/// removed if the unicode feature is enabled. The phonetic codes only take the letters A to Z
+/// into account, and are made for English words.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WordlistReport {
+    /// Words in the list, as given.
+    pub words: Vec<String>,
+    /// Words that are empty, or contain whitespace or `?`, which end words or mark them as unreadable.
+    pub invalid: Vec<String>,
+    /// Pairs of words that are read as the same word.
+    pub duplicates: Vec<(String, String)>,
+    /// Pairs of words where the first word is the beginning of the second one.
+    pub prefix_collisions: Vec<(String, String)>,
+    /// Two different sequences of words that are the same when written together, if there are any.
+    pub ambiguous_segmentation: Option<(Vec<String>, Vec<String>)>,
+    /// Smallest edit distance between two words, along with two words that far apart.
+    pub min_edit_distance: Option<(usize, String, String)>,
+    /// Average edit distance between two words.
+    pub mean_edit_distance: f64,
+    /// Number of letters at the beginning of each word that tell the words apart,
+    /// unless some words are read as the same word.
+    pub unique_prefix_len: Option<usize>,
+    /// Groups of words with the same Soundex code, along with the code.
+    pub soundex_collisions: Vec<(String, Vec<String>)>,
+    /// Groups of words with the same Metaphone code, along with the code.
+    pub metaphone_collisions: Vec<(String, Vec<String>)>,
+}
+
+impl WordlistReport {
+    /// Analyze the given words, the first one standing for the byte `0x00`.
+    pub fn new<S: AsRef<str>>(words: &[S]) -> Self {
+        let words: Vec<String> = words.iter().map(|word| word.as_ref().to_string()).collect();
+        let normalized: Vec<String> = words.iter().map(|word| normalize_word(word)).collect();
+
+        let invalid = words
+            .iter()
+            .filter(|word| {
+                word.is_empty() || word.contains(|c: char| c.is_whitespace() || c == '?')
+            })
+            .cloned()
+            .collect();
+
+        // Words that begin with a word come right after it when sorted.
+        let mut sorted: Vec<usize> = (0..words.len()).collect();
+        sorted.sort_by(|&a, &b| normalized[a].cmp(&normalized[b]));
+        let mut duplicates = vec![];
+        let mut prefix_collisions = vec![];
+        for (i, &a) in sorted.iter().enumerate() {
+            for &b in sorted[i + 1..]
+                .iter()
+                .take_while(|&&b| normalized[b].starts_with(&normalized[a]))
+            {
+                let pair = (words[a].clone(), words[b].clone());
+                if normalized[a] == normalized[b] {
+                    duplicates.push(pair);
+                } else {
+                    prefix_collisions.push(pair);
+                }
+            }
+        }
+
+        let unique_prefix_len = duplicates.is_empty().then(|| {
+            sorted
+                .windows(2)
+                .map(|w| common_prefix_len(&normalized[w[0]], &normalized[w[1]]) + 1)
+                .max()
+                .unwrap_or(0)
+        });
+
+        let mut min_edit_distance: Option<(usize, String, String)> = None;
+        let mut distance_sum = 0;
+        let mut pair_count = 0;
+        for (i, a) in normalized.iter().enumerate() {
+            for (j, b) in normalized.iter().enumerate().skip(i + 1) {
+                let distance = edit_distance(a, b);
+                distance_sum += distance;
+                pair_count += 1;
+                if min_edit_distance
+                    .as_ref()
+                    .is_none_or(|(min, _, _)| distance < *min)
+                {
+                    min_edit_distance = Some((distance, words[i].clone(), words[j].clone()));
+                }
+            }
+        }
+        let mean_edit_distance = match pair_count {
+            0 => 0.0,
+            _ => distance_sum as f64 / pair_count as f64,
+        };
+
+        Self {
+            ambiguous_segmentation: ambiguous_segmentation(&words, &normalized),
+            soundex_collisions: collisions(&words, &normalized, soundex),
+            metaphone_collisions: collisions(&words, &normalized, metaphone),
+            words,
+            invalid,
+            duplicates,
+            prefix_collisions,
+            min_edit_distance,
+            mean_edit_distance,
+            unique_prefix_len,
+        }
+    }
+
+    /// Whether the words can be used as a wordlist of one word per byte: there are 256 of them,
+    /// none of them invalid, and no two of them read as the same word.
+    ///
+    /// Words that are the beginning of others, or that sound alike, make for a worse wordlist,
+    /// but one that can still be used.
+    pub fn is_usable(&self) -> bool {
+        self.words.len() == 256 && self.invalid.is_empty() && self.duplicates.is_empty()
+    }
+}
+
+impl std::fmt::Display for WordlistReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Words: {}", self.words.len())?;
+        writeln!(f, "Invalid words: {}", self.invalid.len())?;
+        for word in &self.invalid {
+            writeln!(f, "  {word:?}")?;
+        }
+        writeln!(f, "Duplicate words: {}", self.duplicates.len())?;
+        for (a, b) in &self.duplicates {
+            writeln!(f, "  {a:?} and {b:?}")?;
+        }
+        writeln!(
+            f,
+            "Words that begin other words: {}",
+            self.prefix_collisions.len()
+        )?;
+        for (a, b) in &self.prefix_collisions {
+            writeln!(f, "  {a:?} begins {b:?}")?;
+        }
+        match &self.ambiguous_segmentation {
+            None => writeln!(f, "Words written together: can only be read one way")?,
+            Some((a, b)) => writeln!(
+                f,
+                "Words written together: can be read in more than one way, like {:?} for {:?}",
+                b.join(" "),
+                a.join(" ")
+            )?,
+        }
+        match &self.min_edit_distance {
+            None => writeln!(f, "Edit distance: no pairs of words")?,
+            Some((min, a, b)) => writeln!(
+                f,
+                "Edit distance: at least {min}, like {a:?} and {b:?}, and {:.2} on average",
+                self.mean_edit_distance
+            )?,
+        }
+        match self.unique_prefix_len {
+            None => writeln!(f, "Unique prefix length: none, as some words are the same")?,
+            Some(len) => writeln!(f, "Unique prefix length: {len}")?,
+        }
+        for (name, groups) in [
+            ("Soundex", &self.soundex_collisions),
+            ("Metaphone", &self.metaphone_collisions),
+        ] {
+            writeln!(f, "Words with the same {name} code: {}", groups.len())?;
+            for (code, words) in groups {
+                writeln!(f, "  {code}: {words:?}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of characters that `a` and `b` begin with in common.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(a, b)| a == b).count()
+}
+
+/// Two different sequences of words that are the same when written together,
+/// found with the Sardinas-Patterson algorithm.
+///
+/// Words read as the same word are left out, as they are reported as duplicates.
+fn ambiguous_segmentation(
+    words: &[String],
+    normalized: &[String],
+) -> Option<(Vec<String>, Vec<String>)> {
+    let mut distinct: Vec<usize> = (0..words.len())
+        .filter(|&i| !normalized[i].is_empty())
+        .collect();
+    distinct.sort_by(|&a, &b| normalized[a].cmp(&normalized[b]));
+    distinct.dedup_by(|a, b| normalized[*a] == normalized[*b]);
+
+    // Each state is a pair of sequences of words, where the first sequence written together
+    // is the second one written together followed by a dangling suffix. The sequences are
+    // ambiguous once a word makes up the dangling suffix. Only the dangling suffix matters
+    // for how the sequences can go on, so each one is only looked at once.
+    let mut queue = VecDeque::new();
+    let mut seen = HashSet::new();
+    for &a in &distinct {
+        for &b in &distinct {
+            if let Some(rest) = normalized[a].strip_prefix(&normalized[b]) {
+                if !rest.is_empty() && seen.insert(rest) {
+                    queue.push_back((vec![a], vec![b], rest));
+                }
+            }
+        }
+    }
+    while let Some((ahead, behind, dangling)) = queue.pop_front() {
+        for &w in &distinct {
+            let word = normalized[w].as_str();
+            let mut behind = behind.clone();
+            behind.push(w);
+            if word == dangling {
+                let to_words =
+                    |seq: Vec<usize>| seq.into_iter().map(|i| words[i].clone()).collect();
+                return Some((to_words(ahead), to_words(behind)));
+            } else if let Some(rest) = word.strip_prefix(dangling) {
+                if seen.insert(rest) {
+                    queue.push_back((behind, ahead.clone(), rest));
+                }
+            } else if let Some(rest) = dangling.strip_prefix(word) {
+                if seen.insert(rest) {
+                    queue.push_back((ahead.clone(), behind, rest));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Groups of words with the same phonetic code, along with the code.
+///
+/// Words read as the same word are left out, as they are reported as duplicates.
+fn collisions(
+    words: &[String],
+    normalized: &[String],
+    code: fn(&str) -> String,
+) -> Vec<(String, Vec<String>)> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    for (word, normalized) in words.iter().zip(normalized) {
+        let code = code(normalized);
+        if !code.is_empty() && seen.insert(normalized) {
+            groups.entry(code).or_default().push(word.clone());
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|(_, words)| words.len() > 1)
+        .collect()
+}
+
+/// American Soundex code of the letters A to Z of a word, like `R163` for `Robert`.
+fn soundex(word: &str) -> String {
+    let digit = |c: char| match c {
+        'B' | 'F' | 'P' | 'V' => '1',
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => '2',
+        'D' | 'T' => '3',
+        'L' => '4',
+        'M' | 'N' => '5',
+        'R' => '6',
+        _ => '0',
+    };
+    let mut letters = word
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+    let Some(first) = letters.next() else {
+        return String::new();
+    };
+    let mut code = String::from(first);
+    let mut prev = digit(first);
+    // H and W do not separate letters with the same digit, whereas vowels do.
+    for c in letters.filter(|&c| c != 'H' && c != 'W') {
+        let d = digit(c);
+        if d != '0' && d != prev && code.len() < 4 {
+            code.push(d);
+        }
+        prev = d;
+    }
+    format!("{code:0<4}")
+}
+
+/// Metaphone code of the letters A to Z of a word, like `SKL` for `school`.
+fn metaphone(word: &str) -> String {
+    let mut w: Vec<char> = vec![];
+    // Double letters are read as one, apart from C.
+    for c in word
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+    {
+        if w.last() != Some(&c) || c == 'C' {
+            w.push(c);
+        }
+    }
+    if matches!(
+        w.as_slice(),
+        ['K', 'N', ..] | ['G', 'N', ..] | ['P', 'N', ..] | ['A', 'E', ..] | ['W', 'R', ..]
+    ) {
+        w.remove(0);
+    }
+    if w.ends_with(&['M', 'B']) {
+        w.pop();
+    }
+
+    let is_vowel = |c: Option<char>| matches!(c, Some('A' | 'E' | 'I' | 'O' | 'U'));
+    let mut code = String::new();
+    for (i, &c) in w.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|i| w[i]);
+        let next = w.get(i + 1).copied();
+        let next2 = w.get(i + 2).copied();
+        match c {
+            'A' | 'E' | 'I' | 'O' | 'U' if i == 0 => code.push(c),
+            'A' | 'E' | 'I' | 'O' | 'U' => {}
+            'B' | 'F' | 'J' | 'L' | 'M' | 'N' | 'R' => code.push(c),
+            'C' if next == Some('I') && next2 == Some('A') => code.push('X'),
+            'C' if next == Some('H') => code.push(if prev == Some('S') { 'K' } else { 'X' }),
+            'C' if matches!(next, Some('I' | 'E' | 'Y')) => code.push('S'),
+            'C' if next == Some('K') => {}
+            'C' => code.push('K'),
+            'D' if next == Some('G') && matches!(next2, Some('E' | 'Y' | 'I')) => code.push('J'),
+            'D' => code.push('T'),
+            'G' if next == Some('H') && next2.is_some() && !is_vowel(next2) => {}
+            'G' if next == Some('N') && (i + 2 == w.len() || w[i + 1..] == ['N', 'E', 'D']) => {}
+            'G' if matches!(next, Some('I' | 'E' | 'Y')) => code.push('J'),
+            'G' => code.push('K'),
+            'H' if matches!(prev, Some('C' | 'G' | 'P' | 'S' | 'T')) => {}
+            'H' if i == 1 && prev == Some('W') => {}
+            'H' if is_vowel(prev) && !is_vowel(next) => {}
+            'H' => code.push('H'),
+            'K' if prev == Some('C') => {}
+            'K' | 'Q' => code.push('K'),
+            'P' if next == Some('H') => code.push('F'),
+            'P' => code.push('P'),
+            'S' if next == Some('H') => code.push('X'),
+            'S' if next == Some('I') && matches!(next2, Some('O' | 'A')) => code.push('X'),
+            'S' | 'Z' => code.push('S'),
+            'T' if next == Some('I') && matches!(next2, Some('O' | 'A')) => code.push('X'),
+            'T' if next == Some('H') => code.push('0'),
+            'T' if next == Some('C') && next2 == Some('H') => {}
+            'T' => code.push('T'),
+            'V' => code.push('F'),
+            'W' if i == 0 && next == Some('H') => code.push('W'),
+            'W' | 'Y' if is_vowel(next) => code.push(c),
+            'W' | 'Y' => {}
+            'X' if i == 0 => code.push('S'),
+            'X' => code.push_str("KS"),
+            _ => {}
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod test_cases_wordlist_check {
+    use super::{metaphone, soundex, WordlistReport};
+    use test_case::test_case;
+
+    #[test_case("Robert", "R163")]
+    #[test_case("Rupert", "R163")]
+    #[test_case("Tymczak", "T522")]
+    #[test_case("Pfister", "P236")]
+    #[test_case("Ashcraft", "A261")]
+    #[test_case("Lee", "L000")]
+    fn test_soundex(word: &str, expected: &str) {
+        assert_eq!(soundex(word), expected);
+    }
+
+    #[test_case("school", "SKL")]
+    #[test_case("knight", "NT")]
+    #[test_case("phone", "FN")]
+    #[test_case("Xavier", "SFR")]
+    #[test_case("thumb", "0M")]
+    #[test_case("which", "WX")]
+    fn test_metaphone(word: &str, expected: &str) {
+        assert_eq!(metaphone(word), expected);
+    }
+
+    #[test]
+    fn test_wordlist_report_clean() {
+        let report = WordlistReport::new(&["acid", "bulb", "cozy"]);
+        assert!(report.invalid.is_empty());
+        assert!(report.duplicates.is_empty());
+        assert!(report.prefix_collisions.is_empty());
+        assert_eq!(report.ambiguous_segmentation, None);
+        assert_eq!(
+            report.min_edit_distance,
+            Some((4, "acid".to_string(), "bulb".to_string()))
+        );
+        assert_eq!(report.mean_edit_distance, 4.0);
+        assert_eq!(report.unique_prefix_len, Some(1));
+        assert!(!report.is_usable());
+    }
+
+    #[test]
+    fn test_wordlist_report_problems() {
+        let report = WordlistReport::new(&["ab", "abc", "cd", "d", "Cd", "a?", "rain", "rein"]);
+        assert_eq!(report.invalid, ["a?"]);
+        assert_eq!(report.duplicates, [("cd".to_string(), "Cd".to_string())]);
+        assert_eq!(
+            report.prefix_collisions,
+            [("ab".to_string(), "abc".to_string())]
+        );
+        assert_eq!(report.unique_prefix_len, None);
+        assert_eq!(report.min_edit_distance.unwrap().0, 0);
+        assert_eq!(
+            report.soundex_collisions,
+            [(
+                "R500".to_string(),
+                vec!["rain".to_string(), "rein".to_string()]
+            )]
+        );
+    }
+
+    #[test_case(&["ab", "abc", "cd", "d"], Some((&["ab", "cd"][..], &["abc", "d"][..])) ; "ambiguous")]
+    #[test_case(&["a", "ab", "bb"], None ; "prefix but suffix-free")]
+    #[test_case(&["a", "ab", "b"], Some((&["ab"][..], &["a", "b"][..])) ; "word of two words")]
+    fn test_wordlist_report_segmentation(words: &[&str], expected: Option<(&[&str], &[&str])>) {
+        let report = WordlistReport::new(words);
+        let expected = expected.map(|(a, b)| {
+            let to_vec = |s: &[&str]| s.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+            (to_vec(a), to_vec(b))
+        });
+        assert_eq!(report.ambiguous_segmentation, expected);
+    }
+}